@@ -0,0 +1,80 @@
+//! Measures allocations and throughput of `SDP::marshal`, `SDP::serialize`
+//! and `SDP::write_to` into a reused buffer on a browser offer.
+//!
+//! cargo bench -p rtc --bench serialize
+
+use rtc::SDP;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+struct CountingAllocator {
+    alloc_count: AtomicUsize,
+    alloc_bytes: AtomicUsize,
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        self.alloc_count.fetch_add(1, Ordering::SeqCst);
+        self.alloc_bytes.fetch_add(layout.size(), Ordering::SeqCst);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        self.alloc_count.fetch_add(1, Ordering::SeqCst);
+        self.alloc_bytes.fetch_add(new_size, Ordering::SeqCst);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static A: CountingAllocator = CountingAllocator {
+    alloc_count: AtomicUsize::new(0),
+    alloc_bytes: AtomicUsize::new(0),
+};
+
+const ITERATIONS: usize = 10_000;
+
+const OFFER: &str = "v=0\r\no=- 1336763028228163073 3 IN IP4 127.0.0.1\r\ns=-\r\nt=0 0\r\na=group:BUNDLE 0 1\r\na=extmap-allow-mixed\r\na=msid-semantic: WMS\r\nm=audio 9 UDP/TLS/RTP/SAVPF 111 63 9 0 8 13 110 126\r\nc=IN IP4 0.0.0.0\r\na=rtcp:9 IN IP4 0.0.0.0\r\na=ice-ufrag:AOV8\r\na=ice-pwd:JZBw86RpZXvCLCn5E2rya5bI\r\na=ice-options:trickle\r\na=fingerprint:sha-256 96:01:FD:7F:1D:4D:D8:3E:54:9A:1A:E2:0F:E3:9D:60:05:D3:0E:D7:AB:03:98:D3:B8:8D:BE:95:85:19:BC:E3\r\na=setup:actpass\r\na=mid:0\r\na=extmap:1 urn:ietf:params:rtp-hdrext:ssrc-audio-level\r\na=extmap:2 http://www.webrtc.org/experiments/rtp-hdrext/abs-send-time\r\na=extmap:3 http://www.ietf.org/id/draft-holmer-rmcat-transport-wide-cc-extensions-01\r\na=extmap:4 urn:ietf:params:rtp-hdrext:sdes:mid\r\na=sendrecv\r\na=msid:- f279f012-989a-466f-8423-ee1e0cefdd3c\r\na=rtcp-mux\r\na=rtpmap:111 opus/48000/2\r\na=rtcp-fb:111 transport-cc\r\na=fmtp:111 minptime=10;useinbandfec=1\r\na=rtpmap:63 red/48000/2\r\na=fmtp:63 111/111\r\na=rtpmap:9 G722/8000\r\na=rtpmap:0 PCMU/8000\r\na=rtpmap:8 PCMA/8000\r\na=rtpmap:13 CN/8000\r\na=rtpmap:110 telephone-event/48000\r\na=rtpmap:126 telephone-event/8000\r\na=ssrc:3794136405 cname:BlPa1deUgsMx8yL/\r\nm=video 9 UDP/TLS/RTP/SAVPF 96 97 102 121\r\nc=IN IP4 0.0.0.0\r\na=rtcp:9 IN IP4 0.0.0.0\r\na=ice-ufrag:AOV8\r\na=ice-pwd:JZBw86RpZXvCLCn5E2rya5bI\r\na=ice-options:trickle\r\na=fingerprint:sha-256 96:01:FD:7F:1D:4D:D8:3E:54:9A:1A:E2:0F:E3:9D:60:05:D3:0E:D7:AB:03:98:D3:B8:8D:BE:95:85:19:BC:E3\r\na=setup:actpass\r\na=mid:1\r\na=extmap:14 urn:ietf:params:rtp-hdrext:toffset\r\na=extmap:2 http://www.webrtc.org/experiments/rtp-hdrext/abs-send-time\r\na=extmap:13 urn:3gpp:video-orientation\r\na=extmap:3 http://www.ietf.org/id/draft-holmer-rmcat-transport-wide-cc-extensions-01\r\na=sendrecv\r\na=rtcp-mux\r\na=rtcp-rsize\r\na=rtpmap:96 VP8/90000\r\na=rtcp-fb:96 goog-remb\r\na=rtcp-fb:96 transport-cc\r\na=rtcp-fb:96 ccm fir\r\na=rtcp-fb:96 nack\r\na=rtcp-fb:96 nack pli\r\na=rtpmap:97 rtx/90000\r\na=fmtp:97 apt=96\r\na=rtpmap:102 H264/90000\r\na=rtcp-fb:102 goog-remb\r\na=rtcp-fb:102 transport-cc\r\na=rtcp-fb:102 ccm fir\r\na=rtcp-fb:102 nack\r\na=rtcp-fb:102 nack pli\r\na=fmtp:102 level-asymmetry-allowed=1;packetization-mode=1;profile-level-id=42001f\r\na=rtpmap:121 rtx/90000\r\na=fmtp:121 apt=102\r\n";
+
+fn measure<F: FnMut() -> usize>(name: &str, mut f: F) {
+    let count_before = A.alloc_count.load(Ordering::SeqCst);
+    let bytes_before = A.alloc_bytes.load(Ordering::SeqCst);
+    let start = Instant::now();
+
+    let mut len = 0;
+    for _ in 0..ITERATIONS {
+        len += f();
+    }
+
+    let elapsed = start.elapsed();
+    let count = A.alloc_count.load(Ordering::SeqCst) - count_before;
+    let bytes = A.alloc_bytes.load(Ordering::SeqCst) - bytes_before;
+    println!(
+        "{:<10} {:>8.2} allocs/op {:>10.1} bytes allocated/op {:>8.2} us/op ({} bytes out)",
+        name,
+        count as f64 / ITERATIONS as f64,
+        bytes as f64 / ITERATIONS as f64,
+        elapsed.as_secs_f64() * 1e6 / ITERATIONS as f64,
+        len / ITERATIONS,
+    );
+}
+
+fn main() {
+    let sdp = SDP::unmarshal(OFFER.as_bytes()).expect("valid offer");
+
+    measure("marshal", || SDP::marshal(&sdp).len());
+    measure("serialize", || sdp.serialize().len());
+
+    let mut buf = Vec::with_capacity(sdp.marshal_size());
+    measure("write_to", || {
+        buf.clear();
+        sdp.write_to(&mut buf).expect("write to Vec<u8>");
+        buf.len()
+    });
+}
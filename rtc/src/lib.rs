@@ -1,9 +1,13 @@
 #[macro_use]
 extern crate lazy_static;
 
-mod sdp;
+pub mod rtcp;
+pub mod rtp;
+pub mod sdp;
 
 pub use sdp::SDP;
+pub use sdp::{LineEnding, ParseWarning, UnmarshalOptions};
+pub use sdp::extmap;
 
 
 
@@ -0,0 +1,52 @@
+use super::*;
+
+#[test]
+fn test_application_defined() -> Result<()> {
+    let raw = [
+        0x85, 0xcc, 0x00, 0x03, // v=2 subtype=5 app length=3
+        0x90, 0x2f, 0x9e, 0x2e, // ssrc
+        b'T', b'E', b'S', b'T', // name
+        0x01, 0x02, 0x03, 0x04, // data
+    ];
+    let app = ApplicationDefined::unmarshal(&raw)?;
+    assert_eq!(
+        app,
+        ApplicationDefined {
+            subtype: 5,
+            ssrc: 0x902f9e2e,
+            name: *b"TEST",
+            data: vec![1, 2, 3, 4],
+            padding: vec![],
+        }
+    );
+    let mut buf = [0u8; 16];
+    assert_eq!(app.marshal_to(&mut buf)?, 16);
+    assert_eq!(buf, raw);
+
+    Ok(())
+}
+
+#[test]
+fn test_application_defined_errors() {
+    let raw = [0x80, 0xcc, 0x00, 0x01, 0, 0, 0, 1];
+    assert!(ApplicationDefined::unmarshal(&raw).is_err());
+    let raw = [0x80, 0xcc, 0x00, 0x02, 0, 0, 0, 1, 0xff, b'E', b'S', b'T'];
+    assert_eq!(
+        ApplicationDefined::unmarshal(&raw),
+        Err(Error::InvalidAppName)
+    );
+
+    let mut buf = [0u8; 32];
+    let app = ApplicationDefined {
+        name: *b"TEST",
+        data: vec![1, 2, 3],
+        ..Default::default()
+    };
+    assert_eq!(app.marshal_to(&mut buf), Err(Error::DataNotAligned(3)));
+    let app = ApplicationDefined {
+        subtype: 32,
+        name: *b"TEST",
+        ..Default::default()
+    };
+    assert_eq!(app.marshal_to(&mut buf), Err(Error::TooManyItems(32)));
+}
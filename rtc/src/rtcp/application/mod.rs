@@ -0,0 +1,66 @@
+#[cfg(test)]
+mod application_test;
+
+use super::error::{Error, Result};
+use super::header::*;
+
+/// ApplicationDefined is the APP packet for experimental extensions
+/// <https://tools.ietf.org/html/rfc3550#section-6.7>
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ApplicationDefined {
+    /// 5-bit subtype carried in the count field
+    pub subtype: u8,
+    pub ssrc: u32,
+    /// 4 ASCII characters naming the application
+    pub name: [u8; 4],
+    /// application-dependent data, a multiple of 4 bytes
+    pub data: Vec<u8>,
+    /// padding as received, ending with its length
+    pub padding: Vec<u8>,
+}
+
+impl ApplicationDefined {
+    pub fn unmarshal(buf: &[u8]) -> Result<Self> {
+        let (header, body, padding) = unmarshal_packet(buf, PACKET_TYPE_APPLICATION_DEFINED)?;
+        check_length(body, 8)?;
+
+        let name = [body[4], body[5], body[6], body[7]];
+        if !name.is_ascii() {
+            return Err(Error::InvalidAppName);
+        }
+
+        Ok(ApplicationDefined {
+            subtype: header.count,
+            ssrc: read_u32(body, 0),
+            name,
+            data: body[8..].to_vec(),
+            padding: padding.to_vec(),
+        })
+    }
+
+    pub fn marshal_size(&self) -> usize {
+        HEADER_LENGTH + 8 + self.data.len() + self.padding.len()
+    }
+
+    pub fn marshal_to(&self, buf: &mut [u8]) -> Result<usize> {
+        if !self.name.is_ascii() {
+            return Err(Error::InvalidAppName);
+        }
+        if !self.data.len().is_multiple_of(4) {
+            return Err(Error::DataNotAligned(self.data.len()));
+        }
+        let size = self.marshal_size();
+        let offset = marshal_header(
+            buf,
+            self.subtype as usize,
+            PACKET_TYPE_APPLICATION_DEFINED,
+            size,
+            &self.padding,
+        )?;
+
+        buf[offset..offset + 4].copy_from_slice(&self.ssrc.to_be_bytes());
+        buf[offset + 4..offset + 8].copy_from_slice(&self.name);
+        buf[offset + 8..offset + 8 + self.data.len()].copy_from_slice(&self.data);
+        Ok(size)
+    }
+}
@@ -0,0 +1,52 @@
+use thiserror::Error;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    #[error("rtcp: packet needs {needed} bytes, has {actual}")]
+    PacketTooShort { needed: usize, actual: usize },
+    #[error("rtcp: unsupported version {0}")]
+    InvalidVersion(u8),
+    #[error("rtcp: padding length {0} exceeds the packet")]
+    InvalidPadding(u8),
+    #[error("rtcp: length {0} does not match the packet contents")]
+    InvalidLength(usize),
+    #[error("rtcp: unexpected packet type {0}")]
+    UnexpectedPacketType(u8),
+    #[error("rtcp: unexpected feedback message type {0}")]
+    UnexpectedFormat(u8),
+    #[error("rtcp: {0} items, at most 31 are allowed")]
+    TooManyItems(usize),
+    #[error("rtcp: total lost {0} exceeds 24 bits")]
+    TotalLostOverflow(u32),
+    #[error("rtcp: sdes item type {0} is invalid here")]
+    InvalidSdesItemType(u8),
+    #[error("rtcp: text of {0} bytes exceeds 255")]
+    TextTooLong(usize),
+    #[error("rtcp: text is not valid utf-8")]
+    InvalidText,
+    #[error("rtcp: app name must be 4 ascii characters")]
+    InvalidAppName,
+    #[error("rtcp: data length {0} is not a multiple of 4")]
+    DataNotAligned(usize),
+    #[error("rtcp: remb bitrate exponent {0} overflows")]
+    InvalidBitrate(u8),
+    #[error("rtcp: missing REMB identifier")]
+    MissingRembIdentifier,
+    #[error("rtcp: reference time {0} exceeds 24 bits")]
+    InvalidReferenceTime(i32),
+    #[error("rtcp: reserved packet status symbol")]
+    InvalidPacketStatus,
+    #[error("rtcp: receive delta {0} does not fit its status symbol")]
+    InvalidRecvDelta(i16),
+    #[error("rtcp: compound packet is empty")]
+    EmptyCompound,
+    #[error("rtcp: compound packet must start with a sender or receiver report")]
+    FirstPacketNotReport,
+    #[error("rtcp: compound packet has no sdes cname")]
+    MissingCname,
+    #[error("rtcp: buffer needs {needed} bytes, has {available}")]
+    BufferTooSmall { needed: usize, available: usize },
+}
@@ -0,0 +1,218 @@
+use super::*;
+
+#[test]
+fn test_transport_layer_nack() -> Result<()> {
+    let raw = [
+        0x81, 0xcd, 0x00, 0x03, // v=2 fmt=1 rtpfb length=3
+        0x90, 0x2f, 0x9e, 0x2e, // sender ssrc
+        0x90, 0x2f, 0x9e, 0x2e, // media ssrc
+        0x00, 0x0a, 0x00, 0x05, // pid=10 blp=0b101
+    ];
+    let nack = TransportLayerNack::unmarshal(&raw)?;
+    assert_eq!(
+        nack,
+        TransportLayerNack {
+            sender_ssrc: 0x902f9e2e,
+            media_ssrc: 0x902f9e2e,
+            nacks: vec![NackPair {
+                packet_id: 10,
+                lost_packets: 0b101,
+            }],
+            padding: vec![],
+        }
+    );
+    assert_eq!(nack.sequence_numbers(), vec![10, 11, 13]);
+    let mut buf = [0u8; 16];
+    assert_eq!(nack.marshal_to(&mut buf)?, 16);
+    assert_eq!(buf, raw);
+
+    let nack = TransportLayerNack::new(1, 2, &[10, 11, 13, 26, 27, 65535, 0]);
+    assert_eq!(
+        nack.nacks,
+        vec![
+            NackPair {
+                packet_id: 10,
+                lost_packets: 0x8005,
+            },
+            NackPair {
+                packet_id: 27,
+                lost_packets: 0,
+            },
+            NackPair {
+                packet_id: 65535,
+                lost_packets: 1,
+            },
+        ]
+    );
+    assert_eq!(nack.sequence_numbers(), vec![10, 11, 13, 26, 27, 65535, 0]);
+
+    Ok(())
+}
+
+#[test]
+fn test_picture_loss_indication() -> Result<()> {
+    let raw = [
+        0x81, 0xce, 0x00, 0x02, // v=2 fmt=1 psfb length=2
+        0x00, 0x00, 0x00, 0x01, // sender ssrc
+        0x90, 0x2f, 0x9e, 0x2e, // media ssrc
+    ];
+    let pli = PictureLossIndication::unmarshal(&raw)?;
+    assert_eq!(
+        pli,
+        PictureLossIndication {
+            sender_ssrc: 1,
+            media_ssrc: 0x902f9e2e,
+            padding: vec![],
+        }
+    );
+    let mut buf = [0u8; 12];
+    assert_eq!(pli.marshal_to(&mut buf)?, 12);
+    assert_eq!(buf, raw);
+
+    // padding is kept as received
+    let padded = [
+        0xa1, 0xce, 0x00, 0x03, // v=2 p fmt=1 psfb length=3
+        0x00, 0x00, 0x00, 0x01, // sender ssrc
+        0x90, 0x2f, 0x9e, 0x2e, // media ssrc
+        0x00, 0x00, 0x00, 0x04, // padding
+    ];
+    let pli = PictureLossIndication::unmarshal(&padded)?;
+    assert_eq!(pli.padding, vec![0, 0, 0, 4]);
+    let mut buf = [0xffu8; 16];
+    assert_eq!(pli.marshal_to(&mut buf)?, 16);
+    assert_eq!(buf, padded);
+
+    let pli = PictureLossIndication {
+        padding: vec![0, 0, 0, 3],
+        ..pli
+    };
+    assert_eq!(pli.marshal_to(&mut buf), Err(Error::InvalidPadding(3)));
+
+    let mut raw = raw;
+    raw[0] = 0x84;
+    assert_eq!(
+        PictureLossIndication::unmarshal(&raw),
+        Err(Error::UnexpectedFormat(FORMAT_FIR))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_full_intra_request() -> Result<()> {
+    let raw = [
+        0x84, 0xce, 0x00, 0x04, // v=2 fmt=4 psfb length=4
+        0x00, 0x00, 0x00, 0x01, // sender ssrc
+        0x00, 0x00, 0x00, 0x00, // media ssrc
+        0x90, 0x2f, 0x9e, 0x2e, // ssrc
+        0x2a, 0x00, 0x00, 0x00, // sequence number, reserved
+    ];
+    let fir = FullIntraRequest::unmarshal(&raw)?;
+    assert_eq!(
+        fir,
+        FullIntraRequest {
+            sender_ssrc: 1,
+            media_ssrc: 0,
+            entries: vec![FirEntry {
+                ssrc: 0x902f9e2e,
+                sequence_number: 42,
+            }],
+            padding: vec![],
+        }
+    );
+    let mut buf = [0xffu8; 20];
+    assert_eq!(fir.marshal_to(&mut buf)?, 20);
+    assert_eq!(buf, raw);
+
+    assert_eq!(
+        FullIntraRequest::unmarshal(&[0x84, 0xce, 0x00, 0x03, 0, 0, 0, 1, 0, 0, 0, 0, 1, 2, 3, 4]),
+        Err(Error::InvalidLength(16))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_receiver_estimated_maximum_bitrate() -> Result<()> {
+    let raw = [
+        0x8f, 0xce, 0x00, 0x05, // v=2 fmt=15 psfb length=5
+        0x00, 0x00, 0x00, 0x01, // sender ssrc
+        0x00, 0x00, 0x00, 0x00, // media ssrc
+        b'R', b'E', b'M', b'B', // identifier
+        0x01, 0x0b, 0xd0, 0x90, // 1 ssrc, exp=2 mantissa=250000
+        0x90, 0x2f, 0x9e, 0x2e, // ssrc
+    ];
+    let remb = ReceiverEstimatedMaximumBitrate::unmarshal(&raw)?;
+    assert_eq!(
+        remb,
+        ReceiverEstimatedMaximumBitrate {
+            sender_ssrc: 1,
+            media_ssrc: 0,
+            exponent: 2,
+            mantissa: 250000,
+            ssrcs: vec![0x902f9e2e],
+            padding: vec![],
+        }
+    );
+    assert_eq!(remb.bitrate(), 1_000_000);
+    assert_eq!(
+        ReceiverEstimatedMaximumBitrate::new(1, 1_000_000, vec![0x902f9e2e]),
+        remb
+    );
+    let mut buf = [0u8; 24];
+    assert_eq!(remb.marshal_to(&mut buf)?, 24);
+    assert_eq!(buf, raw);
+
+    // precision below the mantissa is dropped
+    let remb = ReceiverEstimatedMaximumBitrate::new(1, 1_000_003, vec![]);
+    assert_eq!(remb.bitrate(), 1_000_000);
+    let remb = ReceiverEstimatedMaximumBitrate::new(1, u64::MAX, vec![]);
+    assert_eq!((remb.exponent, remb.mantissa), (46, 0x3ffff));
+    assert_eq!(remb.bitrate(), 0x3ffff << 46);
+
+    // an encoding with a larger exponent than needed and a media SSRC is
+    // written back as received
+    let mut raw = raw;
+    raw[8..12].copy_from_slice(&[0x90, 0x2f, 0x9e, 0x2e]);
+    raw[17..20].copy_from_slice(&[0x04, 0x00, 0x02]);
+    let remb = ReceiverEstimatedMaximumBitrate::unmarshal(&raw)?;
+    assert_eq!(remb.media_ssrc, 0x902f9e2e);
+    assert_eq!((remb.exponent, remb.mantissa), (1, 2));
+    assert_eq!(remb.bitrate(), 4);
+    assert_eq!(remb.marshal_to(&mut buf)?, 24);
+    assert_eq!(buf, raw);
+
+    let remb = ReceiverEstimatedMaximumBitrate {
+        exponent: 64,
+        ..remb
+    };
+    assert_eq!(remb.marshal_to(&mut buf), Err(Error::InvalidBitrate(64)));
+    let remb = ReceiverEstimatedMaximumBitrate {
+        exponent: 63,
+        mantissa: 2,
+        ..remb
+    };
+    assert_eq!(remb.bitrate(), u64::MAX);
+    assert_eq!(remb.marshal_to(&mut buf), Err(Error::InvalidBitrate(63)));
+
+    let mut invalid = raw;
+    invalid[12] = b'X';
+    assert_eq!(
+        ReceiverEstimatedMaximumBitrate::unmarshal(&invalid),
+        Err(Error::MissingRembIdentifier)
+    );
+    let mut invalid = raw;
+    invalid[16] = 2;
+    assert_eq!(
+        ReceiverEstimatedMaximumBitrate::unmarshal(&invalid),
+        Err(Error::InvalidLength(24))
+    );
+    let mut invalid = raw;
+    invalid[17..20].copy_from_slice(&[0xfc, 0x00, 0x03]);
+    assert_eq!(
+        ReceiverEstimatedMaximumBitrate::unmarshal(&invalid),
+        Err(Error::InvalidBitrate(63))
+    );
+
+    Ok(())
+}
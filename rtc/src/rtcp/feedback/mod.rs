@@ -0,0 +1,399 @@
+#[cfg(test)]
+mod feedback_test;
+
+use super::error::{Error, Result};
+use super::header::*;
+
+/// size of the sender and media SSRCs common to all feedback messages
+pub(crate) const FEEDBACK_HEADER_LENGTH: usize = 8;
+
+/// identifier following the common feedback header of REMB messages
+pub const REMB_IDENTIFIER: [u8; 4] = *b"REMB";
+
+const REMB_MANTISSA_MAX: u64 = (1 << 18) - 1;
+const REMB_EXPONENT_MAX: u8 = (1 << 6) - 1;
+
+/// unmarshal_feedback checks the packet type and FMT of a feedback message
+/// and returns the sender SSRC, media SSRC, FCI and padding
+pub(crate) fn unmarshal_feedback(
+    buf: &[u8],
+    packet_type: u8,
+    format: u8,
+) -> Result<(u32, u32, &[u8], &[u8])> {
+    let (header, body, padding) = unmarshal_packet(buf, packet_type)?;
+    if header.count != format {
+        return Err(Error::UnexpectedFormat(header.count));
+    }
+    check_length(body, FEEDBACK_HEADER_LENGTH)?;
+    Ok((
+        read_u32(body, 0),
+        read_u32(body, 4),
+        &body[FEEDBACK_HEADER_LENGTH..],
+        padding,
+    ))
+}
+
+/// is_remb returns true if the FCI of a packet starts with the REMB
+/// identifier. Other application layer feedback messages share FMT 15.
+pub(crate) fn is_remb(buf: &[u8]) -> bool {
+    let offset = HEADER_LENGTH + FEEDBACK_HEADER_LENGTH;
+    buf.get(offset..offset + REMB_IDENTIFIER.len()) == Some(&REMB_IDENTIFIER[..])
+}
+
+/// marshal_feedback writes the header, SSRCs and padding of a feedback
+/// message of `size` bytes and returns the offset of the FCI
+pub(crate) fn marshal_feedback(
+    buf: &mut [u8],
+    packet_type: u8,
+    format: u8,
+    size: usize,
+    sender_ssrc: u32,
+    media_ssrc: u32,
+    padding: &[u8],
+) -> Result<usize> {
+    let offset = marshal_header(buf, format as usize, packet_type, size, padding)?;
+    buf[offset..offset + 4].copy_from_slice(&sender_ssrc.to_be_bytes());
+    buf[offset + 4..offset + 8].copy_from_slice(&media_ssrc.to_be_bytes());
+    Ok(offset + FEEDBACK_HEADER_LENGTH)
+}
+
+/// NackPair reports a lost packet and a bitmask of the lost packets
+/// following it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NackPair {
+    pub packet_id: u16,
+    /// bit i is set if packet_id + i + 1 was lost as well
+    pub lost_packets: u16,
+}
+
+impl NackPair {
+    /// sequence_numbers returns all sequence numbers reported lost
+    pub fn sequence_numbers(&self) -> Vec<u16> {
+        let mut sequence_numbers = vec![self.packet_id];
+        for i in 0..16 {
+            if self.lost_packets & (1 << i) != 0 {
+                sequence_numbers.push(self.packet_id.wrapping_add(i + 1));
+            }
+        }
+        sequence_numbers
+    }
+}
+
+/// TransportLayerNack is the generic NACK, negotiated with "a=rtcp-fb:* nack"
+/// <https://tools.ietf.org/html/rfc4585#section-6.2.1>
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TransportLayerNack {
+    pub sender_ssrc: u32,
+    pub media_ssrc: u32,
+    pub nacks: Vec<NackPair>,
+    /// padding as received, ending with its length
+    pub padding: Vec<u8>,
+}
+
+impl TransportLayerNack {
+    /// new packs the lost sequence numbers, in order, into NACK pairs
+    pub fn new(sender_ssrc: u32, media_ssrc: u32, lost: &[u16]) -> Self {
+        let mut nacks: Vec<NackPair> = vec![];
+        for &sequence_number in lost {
+            if let Some(last) = nacks.last_mut() {
+                let distance = sequence_number.wrapping_sub(last.packet_id);
+                if (1..=16).contains(&distance) {
+                    last.lost_packets |= 1 << (distance - 1);
+                    continue;
+                }
+            }
+            nacks.push(NackPair {
+                packet_id: sequence_number,
+                lost_packets: 0,
+            });
+        }
+
+        TransportLayerNack {
+            sender_ssrc,
+            media_ssrc,
+            nacks,
+            padding: vec![],
+        }
+    }
+
+    /// sequence_numbers returns all sequence numbers reported lost
+    pub fn sequence_numbers(&self) -> Vec<u16> {
+        self.nacks
+            .iter()
+            .flat_map(|n| n.sequence_numbers())
+            .collect()
+    }
+
+    pub fn unmarshal(buf: &[u8]) -> Result<Self> {
+        let (sender_ssrc, media_ssrc, fci, padding) =
+            unmarshal_feedback(buf, PACKET_TYPE_TRANSPORT_FEEDBACK, FORMAT_NACK)?;
+        if !fci.len().is_multiple_of(4) {
+            return Err(Error::DataNotAligned(fci.len()));
+        }
+
+        Ok(TransportLayerNack {
+            sender_ssrc,
+            media_ssrc,
+            nacks: fci
+                .chunks_exact(4)
+                .map(|c| NackPair {
+                    packet_id: read_u16(c, 0),
+                    lost_packets: read_u16(c, 2),
+                })
+                .collect(),
+            padding: padding.to_vec(),
+        })
+    }
+
+    pub fn marshal_size(&self) -> usize {
+        HEADER_LENGTH + FEEDBACK_HEADER_LENGTH + self.nacks.len() * 4 + self.padding.len()
+    }
+
+    pub fn marshal_to(&self, buf: &mut [u8]) -> Result<usize> {
+        let size = self.marshal_size();
+        let mut offset = marshal_feedback(
+            buf,
+            PACKET_TYPE_TRANSPORT_FEEDBACK,
+            FORMAT_NACK,
+            size,
+            self.sender_ssrc,
+            self.media_ssrc,
+            &self.padding,
+        )?;
+
+        for nack in &self.nacks {
+            buf[offset..offset + 2].copy_from_slice(&nack.packet_id.to_be_bytes());
+            buf[offset + 2..offset + 4].copy_from_slice(&nack.lost_packets.to_be_bytes());
+            offset += 4;
+        }
+        Ok(size)
+    }
+}
+
+/// PictureLossIndication asks the sender for a key frame, negotiated with
+/// "a=rtcp-fb:* nack pli"
+/// <https://tools.ietf.org/html/rfc4585#section-6.3.1>
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PictureLossIndication {
+    pub sender_ssrc: u32,
+    pub media_ssrc: u32,
+    /// padding as received, ending with its length
+    pub padding: Vec<u8>,
+}
+
+impl PictureLossIndication {
+    pub fn unmarshal(buf: &[u8]) -> Result<Self> {
+        let (sender_ssrc, media_ssrc, fci, padding) =
+            unmarshal_feedback(buf, PACKET_TYPE_PAYLOAD_FEEDBACK, FORMAT_PLI)?;
+        if !fci.is_empty() {
+            return Err(Error::InvalidLength(buf.len()));
+        }
+
+        Ok(PictureLossIndication {
+            sender_ssrc,
+            media_ssrc,
+            padding: padding.to_vec(),
+        })
+    }
+
+    pub fn marshal_size(&self) -> usize {
+        HEADER_LENGTH + FEEDBACK_HEADER_LENGTH + self.padding.len()
+    }
+
+    pub fn marshal_to(&self, buf: &mut [u8]) -> Result<usize> {
+        let size = self.marshal_size();
+        marshal_feedback(
+            buf,
+            PACKET_TYPE_PAYLOAD_FEEDBACK,
+            FORMAT_PLI,
+            size,
+            self.sender_ssrc,
+            self.media_ssrc,
+            &self.padding,
+        )?;
+        Ok(size)
+    }
+}
+
+/// FirEntry requests a decoder refresh point from one media sender
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FirEntry {
+    pub ssrc: u32,
+    /// command sequence number, incremented for each new request
+    pub sequence_number: u8,
+}
+
+/// FullIntraRequest asks senders for a decoder refresh point, negotiated
+/// with "a=rtcp-fb:* ccm fir"
+/// <https://tools.ietf.org/html/rfc5104#section-4.3.1>
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FullIntraRequest {
+    pub sender_ssrc: u32,
+    /// unused, 0 in requests following RFC 5104
+    pub media_ssrc: u32,
+    pub entries: Vec<FirEntry>,
+    /// padding as received, ending with its length
+    pub padding: Vec<u8>,
+}
+
+impl FullIntraRequest {
+    pub fn unmarshal(buf: &[u8]) -> Result<Self> {
+        let (sender_ssrc, media_ssrc, fci, padding) =
+            unmarshal_feedback(buf, PACKET_TYPE_PAYLOAD_FEEDBACK, FORMAT_FIR)?;
+        if !fci.len().is_multiple_of(8) {
+            return Err(Error::InvalidLength(buf.len()));
+        }
+
+        Ok(FullIntraRequest {
+            sender_ssrc,
+            media_ssrc,
+            entries: fci
+                .chunks_exact(8)
+                .map(|c| FirEntry {
+                    ssrc: read_u32(c, 0),
+                    sequence_number: c[4],
+                })
+                .collect(),
+            padding: padding.to_vec(),
+        })
+    }
+
+    pub fn marshal_size(&self) -> usize {
+        HEADER_LENGTH + FEEDBACK_HEADER_LENGTH + self.entries.len() * 8 + self.padding.len()
+    }
+
+    pub fn marshal_to(&self, buf: &mut [u8]) -> Result<usize> {
+        let size = self.marshal_size();
+        let mut offset = marshal_feedback(
+            buf,
+            PACKET_TYPE_PAYLOAD_FEEDBACK,
+            FORMAT_FIR,
+            size,
+            self.sender_ssrc,
+            self.media_ssrc,
+            &self.padding,
+        )?;
+
+        for entry in &self.entries {
+            buf[offset..offset + 4].copy_from_slice(&entry.ssrc.to_be_bytes());
+            buf[offset + 4] = entry.sequence_number;
+            buf[offset + 5..offset + 8].fill(0);
+            offset += 8;
+        }
+        Ok(size)
+    }
+}
+
+/// ReceiverEstimatedMaximumBitrate reports the estimated available bandwidth,
+/// negotiated with "a=rtcp-fb:* goog-remb"
+/// <https://tools.ietf.org/html/draft-alvestrand-rmcat-remb-03#section-2.2>
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReceiverEstimatedMaximumBitrate {
+    pub sender_ssrc: u32,
+    /// unused, 0 in estimates following the draft
+    pub media_ssrc: u32,
+    /// 6-bit exponent of the bitrate
+    pub exponent: u8,
+    /// 18-bit mantissa of the bitrate, kept as sent so that encodings with
+    /// a larger exponent than needed survive a round trip
+    pub mantissa: u32,
+    /// the SSRCs the estimate applies to
+    pub ssrcs: Vec<u32>,
+    /// padding as received, ending with its length
+    pub padding: Vec<u8>,
+}
+
+impl ReceiverEstimatedMaximumBitrate {
+    /// new encodes `bitrate` in bits per second with the smallest exponent,
+    /// which keeps the most precision; low bits of large values are dropped
+    pub fn new(sender_ssrc: u32, bitrate: u64, ssrcs: Vec<u32>) -> Self {
+        let mut exponent = 0;
+        while bitrate >> exponent > REMB_MANTISSA_MAX {
+            exponent += 1;
+        }
+
+        ReceiverEstimatedMaximumBitrate {
+            sender_ssrc,
+            exponent,
+            mantissa: (bitrate >> exponent) as u32,
+            ssrcs,
+            ..Default::default()
+        }
+    }
+
+    /// bitrate returns the estimate in bits per second, u64::MAX if the
+    /// encoding overflows
+    pub fn bitrate(&self) -> u64 {
+        checked_bitrate(self.exponent, self.mantissa).unwrap_or(u64::MAX)
+    }
+
+    pub fn unmarshal(buf: &[u8]) -> Result<Self> {
+        let (sender_ssrc, media_ssrc, fci, padding) =
+            unmarshal_feedback(buf, PACKET_TYPE_PAYLOAD_FEEDBACK, FORMAT_REMB)?;
+        if fci.len() < 8 || fci[..4] != REMB_IDENTIFIER {
+            return Err(Error::MissingRembIdentifier);
+        }
+
+        let count = fci[4] as usize;
+        if fci.len() != 8 + count * 4 {
+            return Err(Error::InvalidLength(buf.len()));
+        }
+        let exponent = fci[5] >> 2;
+        let mantissa = read_u32(fci, 4) & REMB_MANTISSA_MAX as u32;
+        checked_bitrate(exponent, mantissa).ok_or(Error::InvalidBitrate(exponent))?;
+
+        Ok(ReceiverEstimatedMaximumBitrate {
+            sender_ssrc,
+            media_ssrc,
+            exponent,
+            mantissa,
+            ssrcs: fci[8..].chunks_exact(4).map(|c| read_u32(c, 0)).collect(),
+            padding: padding.to_vec(),
+        })
+    }
+
+    pub fn marshal_size(&self) -> usize {
+        HEADER_LENGTH + FEEDBACK_HEADER_LENGTH + 8 + self.ssrcs.len() * 4 + self.padding.len()
+    }
+
+    pub fn marshal_to(&self, buf: &mut [u8]) -> Result<usize> {
+        if self.ssrcs.len() > u8::MAX as usize {
+            return Err(Error::TooManyItems(self.ssrcs.len()));
+        }
+        if self.exponent > REMB_EXPONENT_MAX
+            || self.mantissa as u64 > REMB_MANTISSA_MAX
+            || checked_bitrate(self.exponent, self.mantissa).is_none()
+        {
+            return Err(Error::InvalidBitrate(self.exponent));
+        }
+        let size = self.marshal_size();
+        let mut offset = marshal_feedback(
+            buf,
+            PACKET_TYPE_PAYLOAD_FEEDBACK,
+            FORMAT_REMB,
+            size,
+            self.sender_ssrc,
+            self.media_ssrc,
+            &self.padding,
+        )?;
+
+        let encoded = (self.exponent as u32) << 18 | self.mantissa;
+        buf[offset..offset + 4].copy_from_slice(&REMB_IDENTIFIER);
+        buf[offset + 4..offset + 8].copy_from_slice(&encoded.to_be_bytes());
+        buf[offset + 4] = self.ssrcs.len() as u8;
+        offset += 8;
+        for ssrc in &self.ssrcs {
+            buf[offset..offset + 4].copy_from_slice(&ssrc.to_be_bytes());
+            offset += 4;
+        }
+        Ok(size)
+    }
+}
+
+/// checked_bitrate decodes a REMB bitrate, None if it overflows
+fn checked_bitrate(exponent: u8, mantissa: u32) -> Option<u64> {
+    let mantissa = mantissa as u64;
+    mantissa
+        .checked_shl(exponent as u32)
+        .filter(|bitrate| bitrate >> exponent == mantissa)
+}
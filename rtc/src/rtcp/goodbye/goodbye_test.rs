@@ -0,0 +1,82 @@
+use super::*;
+
+#[test]
+fn test_goodbye() -> Result<()> {
+    let raw = [
+        0x82, 0xcb, 0x00, 0x03, // v=2 sc=2 bye length=3
+        0x90, 0x2f, 0x9e, 0x2e, // ssrc
+        0xbc, 0x5e, 0x9a, 0x40, // ssrc
+        0x03, b'F', b'O', b'O', // reason
+    ];
+    let bye = Goodbye::unmarshal(&raw)?;
+    assert_eq!(
+        bye,
+        Goodbye {
+            sources: vec![0x902f9e2e, 0xbc5e9a40],
+            reason: Some("FOO".to_owned()),
+            padding: vec![],
+        }
+    );
+    let mut buf = [0u8; 16];
+    assert_eq!(bye.marshal_to(&mut buf)?, 16);
+    assert_eq!(buf, raw);
+
+    // padding beyond the 32-bit boundary of the reason
+    let mut padded = raw.to_vec();
+    padded.extend([0, 0, 0, 0]);
+    padded[3] = 0x04;
+    assert_eq!(Goodbye::unmarshal(&padded), Err(Error::InvalidLength(20)));
+    // the same bytes as padding are kept and written back
+    padded[0] |= 0x20;
+    padded[19] = 4;
+    let bye = Goodbye::unmarshal(&padded)?;
+    assert_eq!(bye.padding, vec![0, 0, 0, 4]);
+    let mut buf = [0u8; 20];
+    assert_eq!(bye.marshal_to(&mut buf)?, 20);
+    assert_eq!(buf.to_vec(), padded);
+
+    let bye = Goodbye {
+        sources: vec![1],
+        reason: Some("gone".to_owned()),
+        ..Default::default()
+    };
+    let mut buf = [0xffu8; 16];
+    assert_eq!(bye.marshal_to(&mut buf)?, 16);
+    assert_eq!(&buf[8..], &[4, b'g', b'o', b'n', b'e', 0, 0, 0]);
+    assert_eq!(Goodbye::unmarshal(&buf)?, bye);
+
+    let bye = Goodbye {
+        sources: vec![1],
+        reason: None,
+        ..Default::default()
+    };
+    let mut buf = [0u8; 8];
+    assert_eq!(bye.marshal_to(&mut buf)?, 8);
+    assert_eq!(buf, [0x81, 0xcb, 0x00, 0x01, 0, 0, 0, 1]);
+    assert_eq!(Goodbye::unmarshal(&buf)?, bye);
+
+    Ok(())
+}
+
+#[test]
+fn test_goodbye_errors() {
+    // reason length beyond the packet
+    let raw = [0x81, 0xcb, 0x00, 0x02, 0, 0, 0, 1, 0x08, b'a', b'b', b'c'];
+    assert!(Goodbye::unmarshal(&raw).is_err());
+    // source count beyond the packet
+    let raw = [0x82, 0xcb, 0x00, 0x01, 0, 0, 0, 1];
+    assert!(Goodbye::unmarshal(&raw).is_err());
+    // the reason is filled up with something other than null octets
+    let raw = [0x81, 0xcb, 0x00, 0x02, 0, 0, 0, 1, 0x01, b'a', 0, b'b'];
+    assert_eq!(Goodbye::unmarshal(&raw), Err(Error::InvalidLength(12)));
+
+    let bye = Goodbye {
+        sources: vec![],
+        reason: Some("x".repeat(256)),
+        ..Default::default()
+    };
+    assert_eq!(
+        bye.marshal_to(&mut [0u8; 512]),
+        Err(Error::TextTooLong(256))
+    );
+}
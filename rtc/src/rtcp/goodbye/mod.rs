@@ -0,0 +1,90 @@
+#[cfg(test)]
+mod goodbye_test;
+
+use super::error::{Error, Result};
+use super::header::*;
+
+/// Goodbye is the BYE packet, sent when sources leave the session
+/// <https://tools.ietf.org/html/rfc3550#section-6.6>
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Goodbye {
+    pub sources: Vec<u32>,
+    pub reason: Option<String>,
+    /// padding as received, ending with its length
+    pub padding: Vec<u8>,
+}
+
+impl Goodbye {
+    pub fn unmarshal(buf: &[u8]) -> Result<Self> {
+        let (header, body, padding) = unmarshal_packet(buf, PACKET_TYPE_GOODBYE)?;
+        let sources_len = header.count as usize * 4;
+        check_length(body, sources_len)?;
+
+        let sources = body[..sources_len]
+            .chunks_exact(4)
+            .map(|c| read_u32(c, 0))
+            .collect();
+
+        let rest = &body[sources_len..];
+        let reason = match rest.first() {
+            Some(len) => {
+                let len = *len as usize;
+                check_length(rest, 1 + len)?;
+                // the reason is padded with null octets to 32 bits
+                if (1 + len).div_ceil(4) * 4 != rest.len()
+                    || rest[1 + len..].iter().any(|b| *b != 0)
+                {
+                    return Err(Error::InvalidLength(buf.len()));
+                }
+                let reason =
+                    std::str::from_utf8(&rest[1..1 + len]).map_err(|_| Error::InvalidText)?;
+                Some(reason.to_owned())
+            }
+            None => None,
+        };
+
+        Ok(Goodbye {
+            sources,
+            reason,
+            padding: padding.to_vec(),
+        })
+    }
+
+    pub fn marshal_size(&self) -> usize {
+        let reason = match &self.reason {
+            Some(reason) => (1 + reason.len()).div_ceil(4) * 4,
+            None => 0,
+        };
+        HEADER_LENGTH + self.sources.len() * 4 + reason + self.padding.len()
+    }
+
+    pub fn marshal_to(&self, buf: &mut [u8]) -> Result<usize> {
+        if let Some(reason) = &self.reason {
+            if reason.len() > u8::MAX as usize {
+                return Err(Error::TextTooLong(reason.len()));
+            }
+        }
+        let size = self.marshal_size();
+        let mut offset = marshal_header(
+            buf,
+            self.sources.len(),
+            PACKET_TYPE_GOODBYE,
+            size,
+            &self.padding,
+        )?;
+
+        for source in &self.sources {
+            buf[offset..offset + 4].copy_from_slice(&source.to_be_bytes());
+            offset += 4;
+        }
+        if let Some(reason) = &self.reason {
+            buf[offset] = reason.len() as u8;
+            offset += 1;
+            buf[offset..offset + reason.len()].copy_from_slice(reason.as_bytes());
+            offset += reason.len();
+            buf[offset..size - self.padding.len()].fill(0);
+        }
+
+        Ok(size)
+    }
+}
@@ -0,0 +1,198 @@
+use super::error::{Error, Result};
+
+/// RTCP protocol version, the only one in use
+pub const VERSION: u8 = 2;
+
+/// size of the common header
+pub const HEADER_LENGTH: usize = 4;
+
+/// maximum of the 5-bit count field
+pub const MAX_COUNT: usize = 31;
+
+pub const PACKET_TYPE_SENDER_REPORT: u8 = 200;
+pub const PACKET_TYPE_RECEIVER_REPORT: u8 = 201;
+pub const PACKET_TYPE_SOURCE_DESCRIPTION: u8 = 202;
+pub const PACKET_TYPE_GOODBYE: u8 = 203;
+pub const PACKET_TYPE_APPLICATION_DEFINED: u8 = 204;
+/// transport layer feedback
+/// <https://tools.ietf.org/html/rfc4585#section-6.1>
+pub const PACKET_TYPE_TRANSPORT_FEEDBACK: u8 = 205;
+/// payload specific feedback
+/// <https://tools.ietf.org/html/rfc4585#section-6.1>
+pub const PACKET_TYPE_PAYLOAD_FEEDBACK: u8 = 206;
+
+/// FMT of generic NACK in transport layer feedback
+pub const FORMAT_NACK: u8 = 1;
+/// FMT of transport-wide congestion control in transport layer feedback
+pub const FORMAT_TRANSPORT_CC: u8 = 15;
+/// FMT of picture loss indication in payload specific feedback
+pub const FORMAT_PLI: u8 = 1;
+/// FMT of full intra request in payload specific feedback
+pub const FORMAT_FIR: u8 = 4;
+/// FMT of application layer feedback, used by REMB
+pub const FORMAT_REMB: u8 = 15;
+
+const VERSION_SHIFT: u8 = 6;
+const PADDING_BIT: u8 = 0b0010_0000;
+const COUNT_MASK: u8 = 0b0001_1111;
+
+/// Header is the common header of every RTCP packet
+/// <https://tools.ietf.org/html/rfc3550#section-6.4.1>
+///
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |V=2|P|  count  |      PT       |             length            |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Header {
+    /// set if the packet ends with padding, the last byte of which counts
+    /// the padding bytes
+    pub padding: bool,
+    /// report or source count, or the FMT of feedback messages
+    pub count: u8,
+    pub packet_type: u8,
+    /// length of the packet in 32-bit words minus one
+    pub length: u16,
+}
+
+impl Header {
+    pub fn unmarshal(buf: &[u8]) -> Result<Self> {
+        if buf.len() < HEADER_LENGTH {
+            return Err(Error::PacketTooShort {
+                needed: HEADER_LENGTH,
+                actual: buf.len(),
+            });
+        }
+
+        let version = buf[0] >> VERSION_SHIFT;
+        if version != VERSION {
+            return Err(Error::InvalidVersion(version));
+        }
+
+        Ok(Header {
+            padding: buf[0] & PADDING_BIT != 0,
+            count: buf[0] & COUNT_MASK,
+            packet_type: buf[1],
+            length: u16::from_be_bytes([buf[2], buf[3]]),
+        })
+    }
+
+    pub fn marshal_to(&self, buf: &mut [u8]) -> Result<usize> {
+        if self.count as usize > MAX_COUNT {
+            return Err(Error::TooManyItems(self.count as usize));
+        }
+        check_buffer(buf, HEADER_LENGTH)?;
+
+        buf[0] = (VERSION << VERSION_SHIFT) | self.count;
+        if self.padding {
+            buf[0] |= PADDING_BIT;
+        }
+        buf[1] = self.packet_type;
+        buf[2..4].copy_from_slice(&self.length.to_be_bytes());
+        Ok(HEADER_LENGTH)
+    }
+
+    /// packet_size returns the size of the packet in bytes, including the
+    /// header and padding
+    pub fn packet_size(&self) -> usize {
+        (self.length as usize + 1) * 4
+    }
+}
+
+/// unmarshal_packet checks that `buf` holds exactly one packet of the given
+/// type and returns its header, its body and the padding following the body
+pub(crate) fn unmarshal_packet(buf: &[u8], packet_type: u8) -> Result<(Header, &[u8], &[u8])> {
+    let header = Header::unmarshal(buf)?;
+    if header.packet_type != packet_type {
+        return Err(Error::UnexpectedPacketType(header.packet_type));
+    }
+    if header.packet_size() != buf.len() {
+        return Err(Error::InvalidLength(buf.len()));
+    }
+
+    let mut end = buf.len();
+    if header.padding {
+        let padding = buf[end - 1];
+        if padding == 0 || padding as usize > end - HEADER_LENGTH {
+            return Err(Error::InvalidPadding(padding));
+        }
+        end -= padding as usize;
+    }
+
+    Ok((header, &buf[HEADER_LENGTH..end], &buf[end..]))
+}
+
+/// marshal_header writes the header of a packet of `size` bytes, which must
+/// be a multiple of 4, and the `padding` the packet ends with. Padding sets
+/// the padding bit and has to end with its own length.
+pub(crate) fn marshal_header(
+    buf: &mut [u8],
+    count: usize,
+    packet_type: u8,
+    size: usize,
+    padding: &[u8],
+) -> Result<usize> {
+    if count > MAX_COUNT {
+        return Err(Error::TooManyItems(count));
+    }
+    check_padding(padding)?;
+    if !size.is_multiple_of(4) {
+        return Err(Error::DataNotAligned(size));
+    }
+    check_buffer(buf, size)?;
+
+    buf[size - padding.len()..size].copy_from_slice(padding);
+    Header {
+        padding: !padding.is_empty(),
+        count: count as u8,
+        packet_type,
+        length: (size / 4 - 1) as u16,
+    }
+    .marshal_to(buf)
+}
+
+/// check_padding fails unless `padding` is empty or ends with its length
+pub(crate) fn check_padding(padding: &[u8]) -> Result<()> {
+    match padding.last() {
+        Some(&count) if count as usize != padding.len() => Err(Error::InvalidPadding(count)),
+        _ => Ok(()),
+    }
+}
+
+/// check_buffer fails if `buf` cannot hold `size` bytes
+pub(crate) fn check_buffer(buf: &[u8], size: usize) -> Result<()> {
+    if buf.len() < size {
+        return Err(Error::BufferTooSmall {
+            needed: size,
+            available: buf.len(),
+        });
+    }
+    Ok(())
+}
+
+/// check_length fails if `body` is shorter than `needed` bytes
+pub(crate) fn check_length(body: &[u8], needed: usize) -> Result<()> {
+    if body.len() < needed {
+        return Err(Error::PacketTooShort {
+            needed: needed + HEADER_LENGTH,
+            actual: body.len() + HEADER_LENGTH,
+        });
+    }
+    Ok(())
+}
+
+pub(crate) fn read_u16(buf: &[u8], offset: usize) -> u16 {
+    u16::from_be_bytes([buf[offset], buf[offset + 1]])
+}
+
+pub(crate) fn read_u32(buf: &[u8], offset: usize) -> u32 {
+    u32::from_be_bytes([
+        buf[offset],
+        buf[offset + 1],
+        buf[offset + 2],
+        buf[offset + 3],
+    ])
+}
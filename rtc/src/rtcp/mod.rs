@@ -0,0 +1,239 @@
+//! RTCP packets as described by RFC 3550, with the feedback messages of
+//! RFC 4585 and RFC 5104 and reduced-size mode of RFC 5506
+//! <https://tools.ietf.org/html/rfc3550#section-6>
+
+#[cfg(test)]
+mod rtcp_test;
+
+pub mod application;
+pub mod error;
+pub mod feedback;
+pub mod goodbye;
+pub mod header;
+pub mod report;
+pub mod source_description;
+pub mod transport_cc;
+
+pub use application::ApplicationDefined;
+pub use error::{Error, Result};
+pub use feedback::{
+    FullIntraRequest, PictureLossIndication, ReceiverEstimatedMaximumBitrate, TransportLayerNack,
+};
+pub use goodbye::Goodbye;
+pub use header::Header;
+pub use report::{ReceiverReport, ReceptionReport, SenderReport};
+pub use source_description::SourceDescription;
+pub use transport_cc::TransportLayerCc;
+
+use crate::sdp::jsep::ATTR_KEY_RTCP_RSIZE;
+use crate::sdp::media::MediaDescription;
+use header::*;
+use source_description::SDES_CNAME;
+
+/// "a=rtcp-fb" values negotiating the feedback messages
+pub const RTCP_FB_NACK: &str = "nack";
+pub const RTCP_FB_PLI: &str = "nack pli";
+pub const RTCP_FB_FIR: &str = "ccm fir";
+pub const RTCP_FB_REMB: &str = "goog-remb";
+pub const RTCP_FB_TRANSPORT_CC: &str = "transport-cc";
+
+/// RtcpPacket is a single packet of a compound packet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RtcpPacket {
+    SenderReport(SenderReport),
+    ReceiverReport(ReceiverReport),
+    SourceDescription(SourceDescription),
+    Goodbye(Goodbye),
+    ApplicationDefined(ApplicationDefined),
+    TransportLayerNack(TransportLayerNack),
+    PictureLossIndication(PictureLossIndication),
+    FullIntraRequest(FullIntraRequest),
+    ReceiverEstimatedMaximumBitrate(ReceiverEstimatedMaximumBitrate),
+    TransportLayerCc(TransportLayerCc),
+    /// packet types and feedback messages without a typed form, e.g. XR,
+    /// kept as received including the header
+    Raw(Vec<u8>),
+}
+
+impl RtcpPacket {
+    /// unmarshal parses exactly one packet
+    pub fn unmarshal(buf: &[u8]) -> Result<Self> {
+        let header = Header::unmarshal(buf)?;
+        Ok(match (header.packet_type, header.count) {
+            (PACKET_TYPE_SENDER_REPORT, _) => {
+                RtcpPacket::SenderReport(SenderReport::unmarshal(buf)?)
+            }
+            (PACKET_TYPE_RECEIVER_REPORT, _) => {
+                RtcpPacket::ReceiverReport(ReceiverReport::unmarshal(buf)?)
+            }
+            (PACKET_TYPE_SOURCE_DESCRIPTION, _) => {
+                RtcpPacket::SourceDescription(SourceDescription::unmarshal(buf)?)
+            }
+            (PACKET_TYPE_GOODBYE, _) => RtcpPacket::Goodbye(Goodbye::unmarshal(buf)?),
+            (PACKET_TYPE_APPLICATION_DEFINED, _) => {
+                RtcpPacket::ApplicationDefined(ApplicationDefined::unmarshal(buf)?)
+            }
+            (PACKET_TYPE_TRANSPORT_FEEDBACK, FORMAT_NACK) => {
+                RtcpPacket::TransportLayerNack(TransportLayerNack::unmarshal(buf)?)
+            }
+            (PACKET_TYPE_TRANSPORT_FEEDBACK, FORMAT_TRANSPORT_CC) => {
+                RtcpPacket::TransportLayerCc(TransportLayerCc::unmarshal(buf)?)
+            }
+            (PACKET_TYPE_PAYLOAD_FEEDBACK, FORMAT_PLI) => {
+                RtcpPacket::PictureLossIndication(PictureLossIndication::unmarshal(buf)?)
+            }
+            (PACKET_TYPE_PAYLOAD_FEEDBACK, FORMAT_FIR) => {
+                RtcpPacket::FullIntraRequest(FullIntraRequest::unmarshal(buf)?)
+            }
+            (PACKET_TYPE_PAYLOAD_FEEDBACK, FORMAT_REMB) if feedback::is_remb(buf) => {
+                RtcpPacket::ReceiverEstimatedMaximumBitrate(
+                    ReceiverEstimatedMaximumBitrate::unmarshal(buf)?,
+                )
+            }
+            _ => {
+                if header.packet_size() != buf.len() {
+                    return Err(Error::InvalidLength(buf.len()));
+                }
+                RtcpPacket::Raw(buf.to_vec())
+            }
+        })
+    }
+
+    pub fn marshal_size(&self) -> usize {
+        match self {
+            RtcpPacket::SenderReport(p) => p.marshal_size(),
+            RtcpPacket::ReceiverReport(p) => p.marshal_size(),
+            RtcpPacket::SourceDescription(p) => p.marshal_size(),
+            RtcpPacket::Goodbye(p) => p.marshal_size(),
+            RtcpPacket::ApplicationDefined(p) => p.marshal_size(),
+            RtcpPacket::TransportLayerNack(p) => p.marshal_size(),
+            RtcpPacket::PictureLossIndication(p) => p.marshal_size(),
+            RtcpPacket::FullIntraRequest(p) => p.marshal_size(),
+            RtcpPacket::ReceiverEstimatedMaximumBitrate(p) => p.marshal_size(),
+            RtcpPacket::TransportLayerCc(p) => p.marshal_size(),
+            RtcpPacket::Raw(raw) => raw.len(),
+        }
+    }
+
+    pub fn marshal_to(&self, buf: &mut [u8]) -> Result<usize> {
+        match self {
+            RtcpPacket::SenderReport(p) => p.marshal_to(buf),
+            RtcpPacket::ReceiverReport(p) => p.marshal_to(buf),
+            RtcpPacket::SourceDescription(p) => p.marshal_to(buf),
+            RtcpPacket::Goodbye(p) => p.marshal_to(buf),
+            RtcpPacket::ApplicationDefined(p) => p.marshal_to(buf),
+            RtcpPacket::TransportLayerNack(p) => p.marshal_to(buf),
+            RtcpPacket::PictureLossIndication(p) => p.marshal_to(buf),
+            RtcpPacket::FullIntraRequest(p) => p.marshal_to(buf),
+            RtcpPacket::ReceiverEstimatedMaximumBitrate(p) => p.marshal_to(buf),
+            RtcpPacket::TransportLayerCc(p) => p.marshal_to(buf),
+            RtcpPacket::Raw(raw) => {
+                check_buffer(buf, raw.len())?;
+                buf[..raw.len()].copy_from_slice(raw);
+                Ok(raw.len())
+            }
+        }
+    }
+
+    /// rtcp_feedback returns the "a=rtcp-fb" value that negotiates this
+    /// feedback message, None for other packets
+    pub fn rtcp_feedback(&self) -> Option<&'static str> {
+        match self {
+            RtcpPacket::TransportLayerNack(_) => Some(RTCP_FB_NACK),
+            RtcpPacket::PictureLossIndication(_) => Some(RTCP_FB_PLI),
+            RtcpPacket::FullIntraRequest(_) => Some(RTCP_FB_FIR),
+            RtcpPacket::ReceiverEstimatedMaximumBitrate(_) => Some(RTCP_FB_REMB),
+            RtcpPacket::TransportLayerCc(_) => Some(RTCP_FB_TRANSPORT_CC),
+            _ => None,
+        }
+    }
+}
+
+/// unmarshal parses a compound packet. Unless `reduced_size` was negotiated
+/// with "a=rtcp-rsize", it must follow the rules of [`validate_compound`].
+pub fn unmarshal(buf: &[u8], reduced_size: bool) -> Result<Vec<RtcpPacket>> {
+    let mut packets = vec![];
+    let mut offset = 0;
+    while offset < buf.len() {
+        let size = Header::unmarshal(&buf[offset..])?.packet_size();
+        if offset + size > buf.len() {
+            return Err(Error::PacketTooShort {
+                needed: offset + size,
+                actual: buf.len(),
+            });
+        }
+        packets.push(RtcpPacket::unmarshal(&buf[offset..offset + size])?);
+        offset += size;
+    }
+
+    validate_compound(&packets, reduced_size)?;
+    Ok(packets)
+}
+
+/// marshal_size returns the size of the compound packet
+pub fn marshal_size(packets: &[RtcpPacket]) -> usize {
+    packets.iter().map(|p| p.marshal_size()).sum()
+}
+
+/// marshal_to writes a compound packet into `buf` and returns the number of
+/// bytes written
+pub fn marshal_to(packets: &[RtcpPacket], reduced_size: bool, buf: &mut [u8]) -> Result<usize> {
+    validate_compound(packets, reduced_size)?;
+    check_buffer(buf, marshal_size(packets))?;
+
+    let mut offset = 0;
+    for packet in packets {
+        offset += packet.marshal_to(&mut buf[offset..])?;
+    }
+    Ok(offset)
+}
+
+/// marshal serializes a compound packet into a new buffer
+pub fn marshal(packets: &[RtcpPacket], reduced_size: bool) -> Result<Vec<u8>> {
+    let mut buf = vec![0u8; marshal_size(packets)];
+    let n = marshal_to(packets, reduced_size, &mut buf)?;
+    buf.truncate(n);
+    Ok(buf)
+}
+
+/// validate_compound checks that a compound packet starts with a sender or
+/// receiver report and carries a CNAME
+/// <https://tools.ietf.org/html/rfc3550#section-6.1>
+///
+/// In reduced-size mode any non-empty sequence of packets is valid
+/// <https://tools.ietf.org/html/rfc5506#section-3.1>
+pub fn validate_compound(packets: &[RtcpPacket], reduced_size: bool) -> Result<()> {
+    if packets.is_empty() {
+        return Err(Error::EmptyCompound);
+    }
+    if reduced_size {
+        return Ok(());
+    }
+
+    match packets[0] {
+        RtcpPacket::SenderReport(_) | RtcpPacket::ReceiverReport(_) => {}
+        _ => return Err(Error::FirstPacketNotReport),
+    }
+
+    let has_cname = packets.iter().any(|p| match p {
+        RtcpPacket::SourceDescription(sdes) => sdes
+            .chunks
+            .iter()
+            .flat_map(|c| &c.items)
+            .any(|i| i.item_type == SDES_CNAME),
+        _ => false,
+    });
+    if !has_cname {
+        return Err(Error::MissingCname);
+    }
+
+    Ok(())
+}
+
+impl MediaDescription {
+    /// rtcp_rsize returns true if reduced-size RTCP is announced with
+    /// "a=rtcp-rsize"
+    pub fn rtcp_rsize(&self) -> bool {
+        self.attribute(ATTR_KEY_RTCP_RSIZE).is_some()
+    }
+}
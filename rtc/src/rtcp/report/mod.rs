@@ -0,0 +1,215 @@
+#[cfg(test)]
+mod report_test;
+
+use super::error::{Error, Result};
+use super::header::*;
+
+/// size of a reception report block
+pub const RECEPTION_REPORT_LENGTH: usize = 24;
+
+/// size of the sender info following the SSRC of a sender report
+const SENDER_INFO_LENGTH: usize = 20;
+
+/// ReceptionReport is a report block of a sender or receiver report
+/// <https://tools.ietf.org/html/rfc3550#section-6.4.1>
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReceptionReport {
+    pub ssrc: u32,
+    /// fraction of packets lost since the last report, in 1/256
+    pub fraction_lost: u8,
+    /// 24-bit cumulative number of packets lost
+    pub total_lost: u32,
+    pub last_sequence_number: u32,
+    pub jitter: u32,
+    /// middle 32 bits of the NTP timestamp of the last sender report
+    pub last_sender_report: u32,
+    /// delay since the last sender report, in 1/65536 seconds
+    pub delay: u32,
+}
+
+impl ReceptionReport {
+    pub fn unmarshal(buf: &[u8]) -> Result<Self> {
+        if buf.len() < RECEPTION_REPORT_LENGTH {
+            return Err(Error::PacketTooShort {
+                needed: RECEPTION_REPORT_LENGTH,
+                actual: buf.len(),
+            });
+        }
+
+        Ok(ReceptionReport {
+            ssrc: read_u32(buf, 0),
+            fraction_lost: buf[4],
+            total_lost: read_u32(buf, 4) & 0x00FF_FFFF,
+            last_sequence_number: read_u32(buf, 8),
+            jitter: read_u32(buf, 12),
+            last_sender_report: read_u32(buf, 16),
+            delay: read_u32(buf, 20),
+        })
+    }
+
+    pub fn marshal_to(&self, buf: &mut [u8]) -> Result<usize> {
+        check_buffer(buf, RECEPTION_REPORT_LENGTH)?;
+        if self.total_lost > 0x00FF_FFFF {
+            return Err(Error::TotalLostOverflow(self.total_lost));
+        }
+
+        buf[0..4].copy_from_slice(&self.ssrc.to_be_bytes());
+        buf[4..8].copy_from_slice(&self.total_lost.to_be_bytes());
+        buf[4] = self.fraction_lost;
+        buf[8..12].copy_from_slice(&self.last_sequence_number.to_be_bytes());
+        buf[12..16].copy_from_slice(&self.jitter.to_be_bytes());
+        buf[16..20].copy_from_slice(&self.last_sender_report.to_be_bytes());
+        buf[20..24].copy_from_slice(&self.delay.to_be_bytes());
+        Ok(RECEPTION_REPORT_LENGTH)
+    }
+}
+
+/// SenderReport is sent by active senders
+/// <https://tools.ietf.org/html/rfc3550#section-6.4.1>
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SenderReport {
+    pub ssrc: u32,
+    pub ntp_time: u64,
+    pub rtp_time: u32,
+    pub packet_count: u32,
+    pub octet_count: u32,
+    pub reports: Vec<ReceptionReport>,
+    /// profile-specific extension following the report blocks
+    pub profile_extensions: Vec<u8>,
+    /// padding as received, ending with its length
+    pub padding: Vec<u8>,
+}
+
+impl SenderReport {
+    pub fn unmarshal(buf: &[u8]) -> Result<Self> {
+        let (header, body, padding) = unmarshal_packet(buf, PACKET_TYPE_SENDER_REPORT)?;
+        check_length(body, 4 + SENDER_INFO_LENGTH)?;
+
+        let (reports, profile_extensions) =
+            unmarshal_reports(&body[4 + SENDER_INFO_LENGTH..], header.count)?;
+        Ok(SenderReport {
+            ssrc: read_u32(body, 0),
+            ntp_time: (read_u32(body, 4) as u64) << 32 | read_u32(body, 8) as u64,
+            rtp_time: read_u32(body, 12),
+            packet_count: read_u32(body, 16),
+            octet_count: read_u32(body, 20),
+            reports,
+            profile_extensions,
+            padding: padding.to_vec(),
+        })
+    }
+
+    pub fn marshal_size(&self) -> usize {
+        HEADER_LENGTH
+            + 4
+            + SENDER_INFO_LENGTH
+            + self.reports.len() * RECEPTION_REPORT_LENGTH
+            + self.profile_extensions.len()
+            + self.padding.len()
+    }
+
+    pub fn marshal_to(&self, buf: &mut [u8]) -> Result<usize> {
+        if !self.profile_extensions.len().is_multiple_of(4) {
+            return Err(Error::DataNotAligned(self.profile_extensions.len()));
+        }
+        let size = self.marshal_size();
+        let mut offset = marshal_header(
+            buf,
+            self.reports.len(),
+            PACKET_TYPE_SENDER_REPORT,
+            size,
+            &self.padding,
+        )?;
+
+        buf[offset..offset + 4].copy_from_slice(&self.ssrc.to_be_bytes());
+        buf[offset + 4..offset + 12].copy_from_slice(&self.ntp_time.to_be_bytes());
+        buf[offset + 12..offset + 16].copy_from_slice(&self.rtp_time.to_be_bytes());
+        buf[offset + 16..offset + 20].copy_from_slice(&self.packet_count.to_be_bytes());
+        buf[offset + 20..offset + 24].copy_from_slice(&self.octet_count.to_be_bytes());
+        offset += 4 + SENDER_INFO_LENGTH;
+
+        marshal_reports(&mut buf[offset..], &self.reports, &self.profile_extensions)?;
+        Ok(size)
+    }
+}
+
+/// ReceiverReport is sent by participants that are not active senders
+/// <https://tools.ietf.org/html/rfc3550#section-6.4.2>
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReceiverReport {
+    pub ssrc: u32,
+    pub reports: Vec<ReceptionReport>,
+    /// profile-specific extension following the report blocks
+    pub profile_extensions: Vec<u8>,
+    /// padding as received, ending with its length
+    pub padding: Vec<u8>,
+}
+
+impl ReceiverReport {
+    pub fn unmarshal(buf: &[u8]) -> Result<Self> {
+        let (header, body, padding) = unmarshal_packet(buf, PACKET_TYPE_RECEIVER_REPORT)?;
+        check_length(body, 4)?;
+
+        let (reports, profile_extensions) = unmarshal_reports(&body[4..], header.count)?;
+        Ok(ReceiverReport {
+            ssrc: read_u32(body, 0),
+            reports,
+            profile_extensions,
+            padding: padding.to_vec(),
+        })
+    }
+
+    pub fn marshal_size(&self) -> usize {
+        HEADER_LENGTH
+            + 4
+            + self.reports.len() * RECEPTION_REPORT_LENGTH
+            + self.profile_extensions.len()
+            + self.padding.len()
+    }
+
+    pub fn marshal_to(&self, buf: &mut [u8]) -> Result<usize> {
+        if !self.profile_extensions.len().is_multiple_of(4) {
+            return Err(Error::DataNotAligned(self.profile_extensions.len()));
+        }
+        let size = self.marshal_size();
+        let offset = marshal_header(
+            buf,
+            self.reports.len(),
+            PACKET_TYPE_RECEIVER_REPORT,
+            size,
+            &self.padding,
+        )?;
+
+        buf[offset..offset + 4].copy_from_slice(&self.ssrc.to_be_bytes());
+        marshal_reports(
+            &mut buf[offset + 4..],
+            &self.reports,
+            &self.profile_extensions,
+        )?;
+        Ok(size)
+    }
+}
+
+fn unmarshal_reports(buf: &[u8], count: u8) -> Result<(Vec<ReceptionReport>, Vec<u8>)> {
+    let reports_len = count as usize * RECEPTION_REPORT_LENGTH;
+    check_length(buf, reports_len)?;
+
+    let reports = buf[..reports_len]
+        .chunks_exact(RECEPTION_REPORT_LENGTH)
+        .map(ReceptionReport::unmarshal)
+        .collect::<Result<_>>()?;
+    Ok((reports, buf[reports_len..].to_vec()))
+}
+
+fn marshal_reports(
+    buf: &mut [u8],
+    reports: &[ReceptionReport],
+    profile_extensions: &[u8],
+) -> Result<()> {
+    let mut offset = 0;
+    for report in reports {
+        offset += report.marshal_to(&mut buf[offset..])?;
+    }
+    buf[offset..offset + profile_extensions.len()].copy_from_slice(profile_extensions);
+    Ok(())
+}
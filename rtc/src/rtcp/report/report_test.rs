@@ -0,0 +1,138 @@
+use super::*;
+
+const SENDER_REPORT: [u8; 52] = [
+    0x81, 0xc8, 0x00, 0x0c, // v=2 rc=1 sr length=12
+    0x90, 0x2f, 0x9e, 0x2e, // ssrc
+    0xda, 0x8b, 0xd1, 0xfc, 0xdd, 0xdd, 0xdd, 0xdd, // ntp time
+    0x22, 0x27, 0xa2, 0xb0, // rtp time
+    0x00, 0x00, 0x00, 0x0a, // packet count
+    0x00, 0x00, 0x03, 0xe8, // octet count
+    0xbc, 0x5e, 0x9a, 0x40, // report ssrc
+    0x10, 0x00, 0x00, 0x05, // fraction lost, total lost
+    0x00, 0x00, 0x46, 0xe1, // last sequence number
+    0x00, 0x00, 0x01, 0x11, // jitter
+    0x09, 0xf3, 0x64, 0x32, // last sender report
+    0x00, 0x02, 0x4a, 0x79, // delay
+];
+
+fn reception_report() -> ReceptionReport {
+    ReceptionReport {
+        ssrc: 0xbc5e9a40,
+        fraction_lost: 0x10,
+        total_lost: 5,
+        last_sequence_number: 0x46e1,
+        jitter: 0x111,
+        last_sender_report: 0x09f36432,
+        delay: 0x24a79,
+    }
+}
+
+#[test]
+fn test_sender_report() -> Result<()> {
+    let expected = SenderReport {
+        ssrc: 0x902f9e2e,
+        ntp_time: 0xda8bd1fcdddddddd,
+        rtp_time: 0x2227a2b0,
+        packet_count: 10,
+        octet_count: 1000,
+        reports: vec![reception_report()],
+        profile_extensions: vec![],
+        padding: vec![],
+    };
+    let sr = SenderReport::unmarshal(&SENDER_REPORT)?;
+    assert_eq!(sr, expected);
+
+    let mut buf = [0u8; 52];
+    assert_eq!(sr.marshal_size(), 52);
+    assert_eq!(sr.marshal_to(&mut buf)?, 52);
+    assert_eq!(buf, SENDER_REPORT);
+
+    assert_eq!(
+        sr.marshal_to(&mut buf[..51]),
+        Err(Error::BufferTooSmall {
+            needed: 52,
+            available: 51
+        })
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_receiver_report() -> Result<()> {
+    let raw = [
+        0x80, 0xc9, 0x00, 0x02, // v=2 rc=0 rr length=2
+        0x90, 0x2f, 0x9e, 0x2e, // ssrc
+        0x01, 0x02, 0x03, 0x04, // profile extension
+    ];
+    let rr = ReceiverReport::unmarshal(&raw)?;
+    assert_eq!(
+        rr,
+        ReceiverReport {
+            ssrc: 0x902f9e2e,
+            reports: vec![],
+            profile_extensions: vec![1, 2, 3, 4],
+            padding: vec![],
+        }
+    );
+    let mut buf = [0u8; 12];
+    assert_eq!(rr.marshal_to(&mut buf)?, 12);
+    assert_eq!(buf, raw);
+
+    let rr = ReceiverReport {
+        ssrc: 1,
+        reports: vec![reception_report(), reception_report()],
+        profile_extensions: vec![],
+        padding: vec![],
+    };
+    let mut buf = [0u8; 56];
+    assert_eq!(rr.marshal_to(&mut buf)?, 56);
+    assert_eq!(buf[0], 0x82);
+    assert_eq!(ReceiverReport::unmarshal(&buf)?, rr);
+
+    Ok(())
+}
+
+#[test]
+fn test_report_errors() {
+    // report count of 2 with a single block
+    let mut raw = SENDER_REPORT;
+    raw[0] = 0x82;
+    assert!(SenderReport::unmarshal(&raw).is_err());
+
+    // length field does not match the buffer
+    assert_eq!(
+        SenderReport::unmarshal(&SENDER_REPORT[..48]),
+        Err(Error::InvalidLength(48))
+    );
+    assert_eq!(
+        ReceiverReport::unmarshal(&SENDER_REPORT),
+        Err(Error::UnexpectedPacketType(PACKET_TYPE_SENDER_REPORT))
+    );
+    let mut raw = SENDER_REPORT;
+    raw[0] = 0x41;
+    assert_eq!(SenderReport::unmarshal(&raw), Err(Error::InvalidVersion(1)));
+
+    let mut buf = [0u8; 64];
+    let report = ReceptionReport {
+        total_lost: 0x0100_0000,
+        ..Default::default()
+    };
+    assert_eq!(
+        report.marshal_to(&mut buf),
+        Err(Error::TotalLostOverflow(0x0100_0000))
+    );
+    let rr = ReceiverReport {
+        profile_extensions: vec![1, 2],
+        ..Default::default()
+    };
+    assert_eq!(rr.marshal_to(&mut buf), Err(Error::DataNotAligned(2)));
+    let rr = ReceiverReport {
+        reports: vec![ReceptionReport::default(); 32],
+        ..Default::default()
+    };
+    assert_eq!(
+        rr.marshal_to(&mut [0u8; 1024]),
+        Err(Error::TooManyItems(32))
+    );
+}
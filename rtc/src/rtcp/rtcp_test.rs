@@ -0,0 +1,322 @@
+use super::source_description::*;
+use super::*;
+use crate::sdp::SDP;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+// receiver report, sdes cname and pli as sent by browsers
+const COMPOUND: [u8; 56] = [
+    0x81, 0xc9, 0x00, 0x07, // v=2 rc=1 rr length=7
+    0x90, 0x2f, 0x9e, 0x2e, // ssrc
+    0xbc, 0x5e, 0x9a, 0x40, // report ssrc
+    0x00, 0x00, 0x00, 0x00, // fraction lost, total lost
+    0x00, 0x00, 0x46, 0xe1, // last sequence number
+    0x00, 0x00, 0x01, 0x11, // jitter
+    0x09, 0xf3, 0x64, 0x32, // last sender report
+    0x00, 0x02, 0x4a, 0x79, // delay
+    0x81, 0xca, 0x00, 0x02, // v=2 sc=1 sdes length=2
+    0x90, 0x2f, 0x9e, 0x2e, // ssrc
+    0x01, 0x01, b'x', 0x00, // cname "x", end
+    0x81, 0xce, 0x00, 0x02, // v=2 fmt=1 psfb length=2
+    0x90, 0x2f, 0x9e, 0x2e, // sender ssrc
+    0xbc, 0x5e, 0x9a, 0x40, // media ssrc
+];
+
+#[test]
+fn test_compound() -> Result<()> {
+    let raw = COMPOUND;
+    let packets = unmarshal(&raw, false)?;
+    assert_eq!(packets.len(), 3);
+    assert!(matches!(packets[0], RtcpPacket::ReceiverReport(_)));
+    assert_eq!(
+        packets[1],
+        RtcpPacket::SourceDescription(SourceDescription::new_cname(0x902f9e2e, "x"))
+    );
+    assert_eq!(
+        packets[2],
+        RtcpPacket::PictureLossIndication(PictureLossIndication {
+            sender_ssrc: 0x902f9e2e,
+            media_ssrc: 0xbc5e9a40,
+            padding: vec![],
+        })
+    );
+    assert_eq!(packets[2].rtcp_feedback(), Some(RTCP_FB_PLI));
+    assert_eq!(packets[0].rtcp_feedback(), None);
+
+    assert_eq!(marshal_size(&packets), raw.len());
+    assert_eq!(marshal(&packets, false)?, raw);
+
+    // the last packet claims more bytes than available
+    assert_eq!(
+        unmarshal(&raw[..50], false),
+        Err(Error::PacketTooShort {
+            needed: 56,
+            actual: 50
+        })
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_compound_rules() -> Result<()> {
+    let raw = COMPOUND;
+    let pli = &raw[44..];
+
+    // a lone feedback message is only valid in reduced-size mode
+    assert_eq!(unmarshal(pli, false), Err(Error::FirstPacketNotReport));
+    let packets = unmarshal(pli, true)?;
+    assert_eq!(marshal(&packets, true)?, pli);
+    assert_eq!(marshal(&packets, false), Err(Error::FirstPacketNotReport));
+
+    let mut without_cname = raw[..32].to_vec();
+    without_cname.extend(pli);
+    assert_eq!(unmarshal(&without_cname, false), Err(Error::MissingCname));
+    assert!(unmarshal(&without_cname, true).is_ok());
+
+    assert_eq!(unmarshal(&[], true), Err(Error::EmptyCompound));
+    assert_eq!(marshal(&[], true), Err(Error::EmptyCompound));
+
+    let packets = unmarshal(&raw, false)?;
+    let mut buf = [0u8; 47];
+    assert_eq!(
+        marshal_to(&packets, false, &mut buf),
+        Err(Error::BufferTooSmall {
+            needed: 56,
+            available: 47
+        })
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_compound_raw_packets() -> Result<()> {
+    // an extended report and a feedback message without typed form are
+    // kept as received
+    let mut raw = COMPOUND[..44].to_vec();
+    let xr = [0x80, 0xcf, 0x00, 0x02, 0, 0, 0, 1, 0x04, 0x00, 0x00, 0x00];
+    let tmmbr = [0x83, 0xcd, 0x00, 0x02, 0, 0, 0, 1, 0, 0, 0, 2];
+    raw.extend(xr);
+    raw.extend(tmmbr);
+
+    let packets = unmarshal(&raw, false)?;
+    assert_eq!(packets[2], RtcpPacket::Raw(xr.to_vec()));
+    assert_eq!(packets[3], RtcpPacket::Raw(tmmbr.to_vec()));
+    assert_eq!(marshal(&packets, false)?, raw);
+
+    Ok(())
+}
+
+#[test]
+fn test_compound_application_layer_feedback() -> Result<()> {
+    // application layer feedback other than REMB uses the same FMT 15
+    let mut raw = COMPOUND[..44].to_vec();
+    let afb = [
+        0x8f, 0xce, 0x00, 0x04, // v=2 fmt=15 psfb length=4
+        0x90, 0x2f, 0x9e, 0x2e, // sender ssrc
+        0x00, 0x00, 0x00, 0x00, // media ssrc
+        b'X', b'Y', b'Z', b'W', // identifier
+        0x01, 0x02, 0x03, 0x04,
+    ];
+    raw.extend(afb);
+
+    let packets = unmarshal(&raw, false)?;
+    assert_eq!(packets.len(), 3);
+    assert!(matches!(packets[0], RtcpPacket::ReceiverReport(_)));
+    assert_eq!(packets[2], RtcpPacket::Raw(afb.to_vec()));
+    assert_eq!(marshal(&packets, false)?, raw);
+
+    // a REMB in the same position is typed
+    let mut remb = afb;
+    remb[12..16].copy_from_slice(b"REMB");
+    remb[16] = 0;
+    raw.truncate(44);
+    raw.extend(remb);
+    assert!(matches!(
+        unmarshal(&raw, false)?[2],
+        RtcpPacket::ReceiverEstimatedMaximumBitrate(_)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_all_packet_types() -> Result<()> {
+    let packets = vec![
+        RtcpPacket::SenderReport(SenderReport {
+            ssrc: 1,
+            ntp_time: 2,
+            rtp_time: 3,
+            packet_count: 4,
+            octet_count: 5,
+            reports: vec![ReceptionReport {
+                ssrc: 6,
+                ..Default::default()
+            }],
+            profile_extensions: vec![],
+            padding: vec![0, 0, 0, 0, 0, 0, 0, 8],
+        }),
+        RtcpPacket::SourceDescription(SourceDescription::new_cname(1, "cname")),
+        RtcpPacket::Goodbye(Goodbye {
+            sources: vec![1],
+            reason: Some("bye".to_owned()),
+            padding: vec![],
+        }),
+        RtcpPacket::ApplicationDefined(ApplicationDefined {
+            subtype: 1,
+            ssrc: 1,
+            name: *b"NAME",
+            data: vec![0; 8],
+            padding: vec![0, 0, 0, 4],
+        }),
+        RtcpPacket::TransportLayerNack(TransportLayerNack::new(1, 6, &[1, 2, 3])),
+        RtcpPacket::PictureLossIndication(PictureLossIndication {
+            sender_ssrc: 1,
+            media_ssrc: 6,
+            padding: vec![],
+        }),
+        RtcpPacket::FullIntraRequest(FullIntraRequest {
+            sender_ssrc: 1,
+            media_ssrc: 0,
+            entries: vec![feedback::FirEntry {
+                ssrc: 6,
+                sequence_number: 1,
+            }],
+            padding: vec![],
+        }),
+        RtcpPacket::ReceiverEstimatedMaximumBitrate(ReceiverEstimatedMaximumBitrate::new(
+            1,
+            2_500_000,
+            vec![6],
+        )),
+        // not normalized, as some senders do
+        RtcpPacket::ReceiverEstimatedMaximumBitrate(ReceiverEstimatedMaximumBitrate {
+            sender_ssrc: 1,
+            media_ssrc: 6,
+            exponent: 10,
+            mantissa: 2_500,
+            ..Default::default()
+        }),
+        RtcpPacket::TransportLayerCc(TransportLayerCc {
+            sender_ssrc: 1,
+            media_ssrc: 6,
+            packet_status_count: 1,
+            packet_chunks: vec![transport_cc::PacketStatusChunk::RunLength {
+                symbol: transport_cc::StatusSymbol::SmallDelta,
+                run_length: 1,
+            }],
+            recv_deltas: vec![4],
+            padding: vec![0],
+            ..Default::default()
+        }),
+    ];
+
+    let raw = marshal(&packets, false)?;
+    assert_eq!(unmarshal(&raw, false)?, packets);
+    assert_eq!(marshal(&unmarshal(&raw, false)?, false)?, raw);
+
+    Ok(())
+}
+
+#[test]
+fn test_captures_round_trip() -> Result<()> {
+    let captures: [&[u8]; 4] = [
+        // pli with padding
+        &[
+            0xa1, 0xce, 0x00, 0x03, 0x90, 0x2f, 0x9e, 0x2e, 0xbc, 0x5e, 0x9a, 0x40, 0x00, 0x00,
+            0x00, 0x04,
+        ],
+        // remb from Chrome
+        &[
+            0x8f, 0xce, 0x00, 0x05, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, b'R', b'E',
+            b'M', b'B', 0x01, 0x1a, 0x20, 0xdf, 0x48, 0x74, 0xed, 0x16,
+        ],
+        // remb with exp=1 mantissa=2 and a media ssrc
+        &[
+            0x8f, 0xce, 0x00, 0x05, 0x00, 0x00, 0x00, 0x01, 0x48, 0x74, 0xed, 0x16, b'R', b'E',
+            b'M', b'B', 0x01, 0x04, 0x00, 0x02, 0x48, 0x74, 0xed, 0x16,
+        ],
+        // transport-cc aligned without the padding bit
+        &[
+            0x8f, 0xcd, 0x00, 0x05, 0x90, 0x2f, 0x9e, 0x2e, 0xbc, 0x5e, 0x9a, 0x40, 0x00, 0x01,
+            0x00, 0x01, 0x00, 0x00, 0x2a, 0x00, 0x20, 0x01, 0x04, 0x00,
+        ],
+    ];
+
+    for capture in captures {
+        let packets = unmarshal(capture, true)?;
+        assert!(!matches!(packets[0], RtcpPacket::Raw(_)));
+        assert_eq!(marshal(&packets, true)?, capture);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_rtcp_feedback_negotiation() {
+    let sdp = SDP::unmarshal(
+        "v=0\r\n\
+o=- 0 0 IN IP4 127.0.0.1\r\n\
+s=-\r\n\
+t=0 0\r\n\
+m=video 9 UDP/TLS/RTP/SAVPF 96\r\n\
+a=rtcp-rsize\r\n\
+a=rtpmap:96 VP8/90000\r\n\
+a=rtcp-fb:96 nack\r\n\
+a=rtcp-fb:96 nack pli\r\n\
+a=rtcp-fb:96 goog-remb\r\n"
+            .as_bytes(),
+    )
+    .unwrap();
+    let media = &sdp.media_descriptions[0];
+    assert!(media.rtcp_rsize());
+    let codec = media.codec_for_payload_type(96).unwrap();
+
+    let pli = RtcpPacket::PictureLossIndication(PictureLossIndication::default());
+    let fir = RtcpPacket::FullIntraRequest(FullIntraRequest::default());
+    let negotiated = |packet: &RtcpPacket| {
+        packet
+            .rtcp_feedback()
+            .map(|fb| codec.rtcp_feedback.iter().any(|f| f == fb))
+    };
+    assert_eq!(negotiated(&pli), Some(true));
+    assert_eq!(negotiated(&fir), Some(false));
+
+    let audio = MediaDescription::new_jsep_media_description("audio".to_owned(), vec![]);
+    assert!(!audio.rtcp_rsize());
+}
+
+#[test]
+fn test_fuzz_unmarshal() {
+    let mut rng = StdRng::seed_from_u64(0x5eed);
+    let seeds = [COMPOUND.to_vec(), COMPOUND[..44].to_vec()];
+    for _ in 0..20000 {
+        let mut buf = seeds[rng.gen_range(0..seeds.len())].clone();
+        for _ in 0..rng.gen_range(1..4) {
+            let i = rng.gen_range(0..buf.len());
+            buf[i] = rng.gen();
+        }
+        buf.truncate(rng.gen_range(0..=buf.len()));
+
+        let packets = match unmarshal(&buf, true) {
+            Ok(packets) => packets,
+            Err(_) => continue,
+        };
+        // whatever parsed is written back byte for byte
+        assert_eq!(marshal(&packets, true), Ok(buf));
+    }
+
+    for _ in 0..10000 {
+        let len = rng.gen_range(0..64);
+        let mut buf: Vec<u8> = (0..len).map(|_| rng.gen()).collect();
+        if len > 3 {
+            buf[0] = (buf[0] & 0x3f) | 0x80;
+            buf[1] = rng.gen_range(200..=207);
+            buf[2] = 0;
+            buf[3] = (len / 4 - 1) as u8;
+        }
+        let _ = unmarshal(&buf, true);
+    }
+}
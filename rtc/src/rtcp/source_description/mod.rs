@@ -0,0 +1,172 @@
+#[cfg(test)]
+mod source_description_test;
+
+use super::error::{Error, Result};
+use super::header::*;
+
+pub const SDES_END: u8 = 0;
+pub const SDES_CNAME: u8 = 1;
+pub const SDES_NAME: u8 = 2;
+pub const SDES_EMAIL: u8 = 3;
+pub const SDES_PHONE: u8 = 4;
+pub const SDES_LOCATION: u8 = 5;
+pub const SDES_TOOL: u8 = 6;
+pub const SDES_NOTE: u8 = 7;
+pub const SDES_PRIVATE: u8 = 8;
+
+/// SourceDescriptionItem is a typed text describing a source
+/// <https://tools.ietf.org/html/rfc3550#section-6.5>
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourceDescriptionItem {
+    pub item_type: u8,
+    pub text: String,
+}
+
+impl SourceDescriptionItem {
+    pub fn new(item_type: u8, text: &str) -> Self {
+        SourceDescriptionItem {
+            item_type,
+            text: text.to_owned(),
+        }
+    }
+}
+
+/// SourceDescriptionChunk lists the items of one source
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourceDescriptionChunk {
+    pub source: u32,
+    pub items: Vec<SourceDescriptionItem>,
+}
+
+impl SourceDescriptionChunk {
+    /// marshal_size returns the size of the chunk including the terminating
+    /// null item and padding to 32 bits
+    pub fn marshal_size(&self) -> usize {
+        let items: usize = self.items.iter().map(|i| 2 + i.text.len()).sum();
+        (4 + items + 1).div_ceil(4) * 4
+    }
+}
+
+/// SourceDescription is the SDES packet, announcing e.g. the CNAME of the
+/// sources of a participant
+/// <https://tools.ietf.org/html/rfc3550#section-6.5>
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourceDescription {
+    pub chunks: Vec<SourceDescriptionChunk>,
+    /// padding as received, ending with its length
+    pub padding: Vec<u8>,
+}
+
+impl SourceDescription {
+    /// new_cname creates an SDES packet with a single CNAME item
+    pub fn new_cname(source: u32, cname: &str) -> Self {
+        SourceDescription {
+            chunks: vec![SourceDescriptionChunk {
+                source,
+                items: vec![SourceDescriptionItem::new(SDES_CNAME, cname)],
+            }],
+            padding: vec![],
+        }
+    }
+
+    /// cname returns the CNAME announced for a source
+    pub fn cname(&self, source: u32) -> Option<&str> {
+        self.chunks
+            .iter()
+            .filter(|c| c.source == source)
+            .flat_map(|c| &c.items)
+            .find(|i| i.item_type == SDES_CNAME)
+            .map(|i| i.text.as_str())
+    }
+
+    pub fn unmarshal(buf: &[u8]) -> Result<Self> {
+        let (header, body, padding) = unmarshal_packet(buf, PACKET_TYPE_SOURCE_DESCRIPTION)?;
+
+        let mut chunks = vec![];
+        let mut offset = 0;
+        for _ in 0..header.count {
+            check_length(body, offset + 4)?;
+            let mut chunk = SourceDescriptionChunk {
+                source: read_u32(body, offset),
+                items: vec![],
+            };
+            offset += 4;
+
+            loop {
+                check_length(body, offset + 1)?;
+                let item_type = body[offset];
+                if item_type == SDES_END {
+                    // the null item is followed by null octets up to 32 bits
+                    let end = (offset + 1).div_ceil(4) * 4;
+                    check_length(body, end)?;
+                    if body[offset..end].iter().any(|b| *b != SDES_END) {
+                        return Err(Error::InvalidLength(buf.len()));
+                    }
+                    offset = end;
+                    break;
+                }
+
+                check_length(body, offset + 2)?;
+                let len = body[offset + 1] as usize;
+                check_length(body, offset + 2 + len)?;
+                let text = std::str::from_utf8(&body[offset + 2..offset + 2 + len])
+                    .map_err(|_| Error::InvalidText)?;
+                chunk
+                    .items
+                    .push(SourceDescriptionItem::new(item_type, text));
+                offset += 2 + len;
+            }
+            chunks.push(chunk);
+        }
+        if offset != body.len() {
+            return Err(Error::InvalidLength(buf.len()));
+        }
+
+        Ok(SourceDescription {
+            chunks,
+            padding: padding.to_vec(),
+        })
+    }
+
+    pub fn marshal_size(&self) -> usize {
+        HEADER_LENGTH
+            + self.chunks.iter().map(|c| c.marshal_size()).sum::<usize>()
+            + self.padding.len()
+    }
+
+    pub fn marshal_to(&self, buf: &mut [u8]) -> Result<usize> {
+        for item in self.chunks.iter().flat_map(|c| &c.items) {
+            if item.item_type == SDES_END {
+                return Err(Error::InvalidSdesItemType(item.item_type));
+            }
+            if item.text.len() > u8::MAX as usize {
+                return Err(Error::TextTooLong(item.text.len()));
+            }
+        }
+        let size = self.marshal_size();
+        let mut offset = marshal_header(
+            buf,
+            self.chunks.len(),
+            PACKET_TYPE_SOURCE_DESCRIPTION,
+            size,
+            &self.padding,
+        )?;
+
+        for chunk in &self.chunks {
+            let end = offset + chunk.marshal_size();
+            buf[offset..offset + 4].copy_from_slice(&chunk.source.to_be_bytes());
+            offset += 4;
+            for item in &chunk.items {
+                buf[offset] = item.item_type;
+                buf[offset + 1] = item.text.len() as u8;
+                offset += 2;
+                buf[offset..offset + item.text.len()].copy_from_slice(item.text.as_bytes());
+                offset += item.text.len();
+            }
+            buf[offset..end].fill(SDES_END);
+            offset = end;
+        }
+
+        Ok(size)
+    }
+}
@@ -0,0 +1,89 @@
+use super::*;
+
+#[test]
+fn test_source_description() -> Result<()> {
+    let raw = [
+        0x82, 0xca, 0x00, 0x06, // v=2 sc=2 sdes length=6
+        0x10, 0x00, 0x00, 0x00, // ssrc
+        0x01, 0x02, b'a', b'b', // cname "ab"
+        0x00, 0x00, 0x00, 0x00, // end, padding
+        0x20, 0x00, 0x00, 0x00, // ssrc
+        0x01, 0x01, b'c', 0x06, // cname "c"
+        0x01, b'd', 0x00, 0x00, // tool "d", end, padding
+    ];
+    let sdes = SourceDescription::unmarshal(&raw)?;
+    assert_eq!(
+        sdes,
+        SourceDescription {
+            chunks: vec![
+                SourceDescriptionChunk {
+                    source: 0x10000000,
+                    items: vec![SourceDescriptionItem::new(SDES_CNAME, "ab")],
+                },
+                SourceDescriptionChunk {
+                    source: 0x20000000,
+                    items: vec![
+                        SourceDescriptionItem::new(SDES_CNAME, "c"),
+                        SourceDescriptionItem::new(SDES_TOOL, "d"),
+                    ],
+                },
+            ],
+            padding: vec![],
+        }
+    );
+    assert_eq!(sdes.cname(0x20000000), Some("c"));
+    assert_eq!(sdes.cname(0x30000000), None);
+
+    let mut buf = [0xffu8; 28];
+    assert_eq!(sdes.marshal_size(), 28);
+    assert_eq!(sdes.marshal_to(&mut buf)?, 28);
+    assert_eq!(buf, raw);
+
+    // a chunk whose items end on a 32-bit boundary needs a full null word
+    let sdes = SourceDescription::new_cname(1, "abcdef");
+    let mut buf = [0u8; 20];
+    assert_eq!(sdes.marshal_to(&mut buf)?, 20);
+    assert_eq!(&buf[12..], &[b'c', b'd', b'e', b'f', 0, 0, 0, 0]);
+    assert_eq!(SourceDescription::unmarshal(&buf)?, sdes);
+
+    Ok(())
+}
+
+#[test]
+fn test_source_description_errors() {
+    // item runs past the packet
+    let raw = [0x81, 0xca, 0x00, 0x02, 0, 0, 0, 1, 0x01, 0x09, b'a', b'b'];
+    assert!(SourceDescription::unmarshal(&raw).is_err());
+    // missing terminating null item
+    let raw = [0x81, 0xca, 0x00, 0x02, 0, 0, 0, 1, 0x01, 0x02, b'a', b'b'];
+    assert!(SourceDescription::unmarshal(&raw).is_err());
+    // trailing data after the last chunk
+    let raw = [0x80, 0xca, 0x00, 0x01, 0, 0, 0, 1];
+    assert_eq!(
+        SourceDescription::unmarshal(&raw),
+        Err(Error::InvalidLength(8))
+    );
+    let raw = [0x81, 0xca, 0x00, 0x02, 0, 0, 0, 1, 0x01, 0x01, 0xff, 0x00];
+    assert_eq!(SourceDescription::unmarshal(&raw), Err(Error::InvalidText));
+    // the null item is followed by something other than null octets
+    let raw = [0x81, 0xca, 0x00, 0x02, 0, 0, 0, 1, 0x01, 0x00, 0x00, 0x01];
+    assert_eq!(
+        SourceDescription::unmarshal(&raw),
+        Err(Error::InvalidLength(12))
+    );
+
+    let mut buf = [0u8; 512];
+    let sdes = SourceDescription::new_cname(1, &"x".repeat(256));
+    assert_eq!(sdes.marshal_to(&mut buf), Err(Error::TextTooLong(256)));
+    let sdes = SourceDescription {
+        chunks: vec![SourceDescriptionChunk {
+            source: 1,
+            items: vec![SourceDescriptionItem::new(SDES_END, "")],
+        }],
+        padding: vec![],
+    };
+    assert_eq!(
+        sdes.marshal_to(&mut buf),
+        Err(Error::InvalidSdesItemType(SDES_END))
+    );
+}
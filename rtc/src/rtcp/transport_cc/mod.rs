@@ -0,0 +1,326 @@
+#[cfg(test)]
+mod transport_cc_test;
+
+use super::error::{Error, Result};
+use super::feedback::{marshal_feedback, unmarshal_feedback, FEEDBACK_HEADER_LENGTH};
+use super::header::*;
+
+/// size of the fixed fields between the feedback header and the chunks
+const TRANSPORT_CC_FIXED_LENGTH: usize = 8;
+
+/// duration of a receive delta tick in microseconds
+pub const DELTA_SCALE_MICROS: i64 = 250;
+
+/// duration of a reference time tick in milliseconds
+pub const REFERENCE_TIME_SCALE_MILLIS: i64 = 64;
+
+const RUN_LENGTH_MAX: u16 = (1 << 13) - 1;
+
+/// StatusSymbol is the reception status of a single packet
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StatusSymbol {
+    #[default]
+    NotReceived,
+    /// received with a receive delta of one byte, 0 to 63.75 ms
+    SmallDelta,
+    /// received with a two byte signed receive delta
+    LargeDelta,
+}
+
+impl StatusSymbol {
+    fn from_bits(bits: u16) -> Result<Self> {
+        match bits {
+            0 => Ok(StatusSymbol::NotReceived),
+            1 => Ok(StatusSymbol::SmallDelta),
+            2 => Ok(StatusSymbol::LargeDelta),
+            _ => Err(Error::InvalidPacketStatus),
+        }
+    }
+
+    fn bits(&self) -> u16 {
+        match self {
+            StatusSymbol::NotReceived => 0,
+            StatusSymbol::SmallDelta => 1,
+            StatusSymbol::LargeDelta => 2,
+        }
+    }
+
+    /// delta_size returns the size of the receive delta of this status
+    pub fn delta_size(&self) -> usize {
+        match self {
+            StatusSymbol::NotReceived => 0,
+            StatusSymbol::SmallDelta => 1,
+            StatusSymbol::LargeDelta => 2,
+        }
+    }
+}
+
+/// PacketStatusChunk encodes the status of consecutive packets
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PacketStatusChunk {
+    /// `run_length` packets sharing the same status
+    RunLength {
+        symbol: StatusSymbol,
+        run_length: u16,
+    },
+    /// 14 one-bit symbols, NotReceived or SmallDelta, or 7 two-bit symbols.
+    /// The vector is kept whole even where it extends past the packet
+    /// status count.
+    StatusVector {
+        two_bit: bool,
+        symbols: Vec<StatusSymbol>,
+    },
+}
+
+impl PacketStatusChunk {
+    fn unmarshal(chunk: u16) -> Result<Self> {
+        if chunk & 0x8000 == 0 {
+            return Ok(PacketStatusChunk::RunLength {
+                symbol: StatusSymbol::from_bits(chunk >> 13 & 0x03)?,
+                run_length: chunk & RUN_LENGTH_MAX,
+            });
+        }
+
+        let two_bit = chunk & 0x4000 != 0;
+        let symbols = if two_bit {
+            (0..7)
+                .map(|i| StatusSymbol::from_bits(chunk >> (12 - 2 * i) & 0x03))
+                .collect::<Result<_>>()?
+        } else {
+            (0..14)
+                .map(|i| StatusSymbol::from_bits(chunk >> (13 - i) & 0x01))
+                .collect::<Result<_>>()?
+        };
+        Ok(PacketStatusChunk::StatusVector { two_bit, symbols })
+    }
+
+    fn marshal(&self) -> Result<u16> {
+        match self {
+            PacketStatusChunk::RunLength { symbol, run_length } => {
+                if *run_length > RUN_LENGTH_MAX {
+                    return Err(Error::InvalidPacketStatus);
+                }
+                Ok(symbol.bits() << 13 | run_length)
+            }
+            PacketStatusChunk::StatusVector { two_bit, symbols } => {
+                let (count, width) = if *two_bit { (7, 2) } else { (14, 1) };
+                if symbols.len() != count
+                    || (!two_bit && symbols.contains(&StatusSymbol::LargeDelta))
+                {
+                    return Err(Error::InvalidPacketStatus);
+                }
+                let mut chunk = 0x8000 | (*two_bit as u16) << 14;
+                for (i, symbol) in symbols.iter().enumerate() {
+                    chunk |= symbol.bits() << (14 - width * (i + 1));
+                }
+                Ok(chunk)
+            }
+        }
+    }
+
+    fn symbols(&self) -> Vec<StatusSymbol> {
+        match self {
+            PacketStatusChunk::RunLength { symbol, run_length } => {
+                vec![*symbol; *run_length as usize]
+            }
+            PacketStatusChunk::StatusVector { symbols, .. } => symbols.clone(),
+        }
+    }
+}
+
+/// TransportLayerCc is the transport-wide congestion control feedback,
+/// negotiated with "a=rtcp-fb:* transport-cc"
+/// <https://tools.ietf.org/html/draft-holmer-rmcat-transport-wide-cc-extensions-01#section-3.1>
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TransportLayerCc {
+    pub sender_ssrc: u32,
+    pub media_ssrc: u32,
+    pub base_sequence_number: u16,
+    pub packet_status_count: u16,
+    /// 24-bit signed reference time in multiples of 64 ms
+    pub reference_time: i32,
+    pub feedback_packet_count: u8,
+    pub packet_chunks: Vec<PacketStatusChunk>,
+    /// one delta per received packet, in multiples of 250 µs
+    pub recv_deltas: Vec<i16>,
+    /// padding as received: either ending with its length and signalled
+    /// with the padding bit, or up to 3 null octets aligning the packet
+    /// without it. Empty pads to 32 bits with the padding bit set like
+    /// libwebrtc does.
+    pub padding: Vec<u8>,
+}
+
+impl TransportLayerCc {
+    /// statuses returns the status of every reported packet, before the
+    /// padding of the last chunk
+    pub fn statuses(&self) -> Vec<StatusSymbol> {
+        let mut statuses: Vec<StatusSymbol> = self
+            .packet_chunks
+            .iter()
+            .flat_map(|c| c.symbols())
+            .collect();
+        statuses.truncate(self.packet_status_count as usize);
+        statuses
+    }
+
+    /// arrivals returns the sequence number of each reported packet and its
+    /// arrival time in microseconds relative to the reference time, None for
+    /// packets that were not received
+    pub fn arrivals(&self) -> Vec<(u16, Option<i64>)> {
+        let mut time = self.reference_time as i64 * REFERENCE_TIME_SCALE_MILLIS * 1000;
+        let mut deltas = self.recv_deltas.iter();
+        self.statuses()
+            .into_iter()
+            .enumerate()
+            .map(|(i, status)| {
+                let sequence_number = self.base_sequence_number.wrapping_add(i as u16);
+                if status == StatusSymbol::NotReceived {
+                    return (sequence_number, None);
+                }
+                time += *deltas.next().unwrap_or(&0) as i64 * DELTA_SCALE_MICROS;
+                (sequence_number, Some(time))
+            })
+            .collect()
+    }
+
+    pub fn unmarshal(buf: &[u8]) -> Result<Self> {
+        let (sender_ssrc, media_ssrc, fci, padding) =
+            unmarshal_feedback(buf, PACKET_TYPE_TRANSPORT_FEEDBACK, FORMAT_TRANSPORT_CC)?;
+        if fci.len() < TRANSPORT_CC_FIXED_LENGTH {
+            return Err(Error::InvalidLength(buf.len()));
+        }
+
+        let mut packet = TransportLayerCc {
+            sender_ssrc,
+            media_ssrc,
+            base_sequence_number: read_u16(fci, 0),
+            packet_status_count: read_u16(fci, 2),
+            // sign extend the 24-bit value
+            reference_time: (read_u32(fci, 4) as i32) >> 8,
+            feedback_packet_count: fci[7],
+            ..Default::default()
+        };
+
+        let mut offset = TRANSPORT_CC_FIXED_LENGTH;
+        let mut statuses = vec![];
+        while statuses.len() < packet.packet_status_count as usize {
+            if offset + 2 > fci.len() {
+                return Err(Error::InvalidLength(buf.len()));
+            }
+            let chunk = PacketStatusChunk::unmarshal(read_u16(fci, offset))?;
+            statuses.extend(chunk.symbols());
+            packet.packet_chunks.push(chunk);
+            offset += 2;
+        }
+        statuses.truncate(packet.packet_status_count as usize);
+
+        for status in statuses {
+            let delta = match status {
+                StatusSymbol::NotReceived => continue,
+                StatusSymbol::SmallDelta => fci.get(offset).map(|d| *d as i16),
+                StatusSymbol::LargeDelta => fci
+                    .get(offset..offset + 2)
+                    .map(|d| i16::from_be_bytes([d[0], d[1]])),
+            };
+            packet
+                .recv_deltas
+                .push(delta.ok_or(Error::InvalidLength(buf.len()))?);
+            offset += status.delta_size();
+        }
+
+        // without the padding bit only null octets aligning the packet to 32
+        // bits may follow the deltas
+        let rest = &fci[offset..];
+        if !padding.is_empty() && !rest.is_empty() || rest.len() > 3 || rest.iter().any(|b| *b != 0)
+        {
+            return Err(Error::InvalidLength(buf.len()));
+        }
+        packet.padding = if padding.is_empty() { rest } else { padding }.to_vec();
+
+        Ok(packet)
+    }
+
+    /// unpadded_size returns the size of the packet before padding it to 32
+    /// bits
+    fn unpadded_size(&self) -> usize {
+        let deltas: usize = self.statuses().iter().map(|s| s.delta_size()).sum();
+        HEADER_LENGTH
+            + FEEDBACK_HEADER_LENGTH
+            + TRANSPORT_CC_FIXED_LENGTH
+            + self.packet_chunks.len() * 2
+            + deltas
+    }
+
+    pub fn marshal_size(&self) -> usize {
+        if self.padding.is_empty() {
+            self.unpadded_size().div_ceil(4) * 4
+        } else {
+            self.unpadded_size() + self.padding.len()
+        }
+    }
+
+    /// marshal_to writes the packet followed by its padding
+    pub fn marshal_to(&self, buf: &mut [u8]) -> Result<usize> {
+        if !(-0x0080_0000..=0x007F_FFFF).contains(&self.reference_time) {
+            return Err(Error::InvalidReferenceTime(self.reference_time));
+        }
+        let statuses = self.statuses();
+        if statuses.len() != self.packet_status_count as usize {
+            return Err(Error::InvalidPacketStatus);
+        }
+        let received = statuses
+            .iter()
+            .filter(|s| **s != StatusSymbol::NotReceived)
+            .count();
+        if received != self.recv_deltas.len() {
+            return Err(Error::InvalidPacketStatus);
+        }
+
+        let size = self.marshal_size();
+        let mut padding = self.padding.clone();
+        if padding.is_empty() && self.unpadded_size() < size {
+            padding = vec![0; size - self.unpadded_size()];
+            padding[size - self.unpadded_size() - 1] = padding.len() as u8;
+        }
+        // null octets aligning the packet go without the padding bit
+        if padding.iter().all(|b| *b == 0) {
+            padding.clear();
+        }
+        let mut offset = marshal_feedback(
+            buf,
+            PACKET_TYPE_TRANSPORT_FEEDBACK,
+            FORMAT_TRANSPORT_CC,
+            size,
+            self.sender_ssrc,
+            self.media_ssrc,
+            &padding,
+        )?;
+
+        buf[offset..offset + 2].copy_from_slice(&self.base_sequence_number.to_be_bytes());
+        buf[offset + 2..offset + 4].copy_from_slice(&self.packet_status_count.to_be_bytes());
+        buf[offset + 4..offset + 8].copy_from_slice(&(self.reference_time << 8).to_be_bytes());
+        buf[offset + 7] = self.feedback_packet_count;
+        offset += TRANSPORT_CC_FIXED_LENGTH;
+
+        for chunk in &self.packet_chunks {
+            buf[offset..offset + 2].copy_from_slice(&chunk.marshal()?.to_be_bytes());
+            offset += 2;
+        }
+
+        let symbols = statuses.iter().filter(|s| **s != StatusSymbol::NotReceived);
+        for (status, delta) in symbols.zip(&self.recv_deltas) {
+            if *status == StatusSymbol::SmallDelta {
+                if !(0..=u8::MAX as i16).contains(delta) {
+                    return Err(Error::InvalidRecvDelta(*delta));
+                }
+                buf[offset] = *delta as u8;
+            } else {
+                buf[offset..offset + 2].copy_from_slice(&delta.to_be_bytes());
+            }
+            offset += status.delta_size();
+        }
+
+        buf[offset..size - padding.len()].fill(0);
+        Ok(size)
+    }
+}
@@ -0,0 +1,232 @@
+use super::*;
+
+use StatusSymbol::*;
+
+const TRANSPORT_CC: [u8; 28] = [
+    0xaf, 0xcd, 0x00, 0x06, // v=2 p fmt=15 rtpfb length=6
+    0x00, 0x00, 0x00, 0x01, // sender ssrc
+    0x00, 0x00, 0x00, 0x02, // media ssrc
+    0x00, 0x64, 0x00, 0x05, // base sequence number 100, 5 packets
+    0x00, 0x00, 0x01, 0x03, // reference time 1, feedback packet count 3
+    0xd8, 0x50, // two-bit status vector
+    0x04, 0xff, 0xf8, 0x10, 0x20, // receive deltas
+    0x01, // padding
+];
+
+fn transport_cc() -> TransportLayerCc {
+    TransportLayerCc {
+        sender_ssrc: 1,
+        media_ssrc: 2,
+        base_sequence_number: 100,
+        packet_status_count: 5,
+        reference_time: 1,
+        feedback_packet_count: 3,
+        packet_chunks: vec![PacketStatusChunk::StatusVector {
+            two_bit: true,
+            symbols: vec![
+                SmallDelta,
+                LargeDelta,
+                NotReceived,
+                SmallDelta,
+                SmallDelta,
+                NotReceived,
+                NotReceived,
+            ],
+        }],
+        recv_deltas: vec![4, -8, 16, 32],
+        padding: vec![1],
+    }
+}
+
+#[test]
+fn test_transport_cc() -> Result<()> {
+    let twcc = TransportLayerCc::unmarshal(&TRANSPORT_CC)?;
+    assert_eq!(twcc, transport_cc());
+    assert_eq!(
+        twcc.statuses(),
+        vec![SmallDelta, LargeDelta, NotReceived, SmallDelta, SmallDelta]
+    );
+    assert_eq!(
+        twcc.arrivals(),
+        vec![
+            (100, Some(65000)),
+            (101, Some(63000)),
+            (102, None),
+            (103, Some(67000)),
+            (104, Some(75000)),
+        ]
+    );
+
+    let mut buf = [0xffu8; 28];
+    assert_eq!(twcc.marshal_size(), 28);
+    assert_eq!(twcc.marshal_to(&mut buf)?, 28);
+    assert_eq!(buf, TRANSPORT_CC);
+
+    Ok(())
+}
+
+#[test]
+fn test_transport_cc_chunks() -> Result<()> {
+    let twcc = TransportLayerCc {
+        base_sequence_number: 65535,
+        packet_status_count: 20,
+        reference_time: -1,
+        packet_chunks: vec![
+            PacketStatusChunk::RunLength {
+                symbol: SmallDelta,
+                run_length: 3,
+            },
+            PacketStatusChunk::StatusVector {
+                two_bit: false,
+                symbols: [SmallDelta, NotReceived]
+                    .iter()
+                    .copied()
+                    .cycle()
+                    .take(14)
+                    .collect(),
+            },
+            PacketStatusChunk::RunLength {
+                symbol: LargeDelta,
+                run_length: 3,
+            },
+        ],
+        recv_deltas: vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, -1, -2, -3],
+        ..Default::default()
+    };
+    let buf = {
+        let mut buf = vec![0u8; twcc.marshal_size()];
+        twcc.marshal_to(&mut buf)?;
+        buf
+    };
+    // 4 + 8 + 8 + 6 + 10 small + 3 large deltas, padded to 44
+    assert_eq!(buf.len(), 44);
+    assert_eq!(buf[0], 0xaf);
+    assert_eq!(&buf[20..26], &[0x20, 0x03, 0xaa, 0xaa, 0x40, 0x03]);
+    assert_eq!(&buf[16..19], &[0xff, 0xff, 0xff]);
+    assert_eq!(
+        TransportLayerCc::unmarshal(&buf)?,
+        TransportLayerCc {
+            padding: vec![0, 2],
+            ..twcc.clone()
+        }
+    );
+    assert_eq!(twcc.arrivals()[1], (0, Some(-64000 + 750)));
+
+    // already aligned packets carry no padding
+    let twcc = TransportLayerCc {
+        packet_status_count: 2,
+        packet_chunks: vec![PacketStatusChunk::RunLength {
+            symbol: SmallDelta,
+            run_length: 2,
+        }],
+        recv_deltas: vec![1, 2],
+        ..Default::default()
+    };
+    let mut buf = [0u8; 24];
+    assert_eq!(twcc.marshal_to(&mut buf)?, 24);
+    assert_eq!(buf[0], 0x8f);
+    assert_eq!(TransportLayerCc::unmarshal(&buf)?, twcc);
+
+    Ok(())
+}
+
+#[test]
+fn test_transport_cc_padding() -> Result<()> {
+    // aligned with null octets instead of padding
+    let mut raw = TRANSPORT_CC;
+    raw[0] = 0x8f;
+    raw[27] = 0;
+    let twcc = TransportLayerCc::unmarshal(&raw)?;
+    assert_eq!(twcc.padding, vec![0]);
+    assert_eq!(twcc.recv_deltas, transport_cc().recv_deltas);
+    let mut buf = [0xffu8; 28];
+    assert_eq!(twcc.marshal_to(&mut buf)?, 28);
+    assert_eq!(buf, raw);
+
+    // padding past the 32-bit boundary
+    let mut raw = TRANSPORT_CC.to_vec();
+    raw[3] = 7;
+    raw[27] = 0;
+    raw.extend([0, 0, 0, 5]);
+    let twcc = TransportLayerCc::unmarshal(&raw)?;
+    assert_eq!(twcc.padding, vec![0, 0, 0, 0, 5]);
+    let mut buf = [0xffu8; 32];
+    assert_eq!(twcc.marshal_to(&mut buf)?, 32);
+    assert_eq!(buf.to_vec(), raw);
+
+    // only null octets may follow the deltas
+    let mut raw = TRANSPORT_CC;
+    raw[0] = 0x8f;
+    assert_eq!(
+        TransportLayerCc::unmarshal(&raw),
+        Err(Error::InvalidLength(28))
+    );
+    // the padding bit with data between the deltas and the padding
+    let mut raw = TRANSPORT_CC.to_vec();
+    raw[3] = 7;
+    raw.extend([0, 0, 0, 4]);
+    assert_eq!(
+        TransportLayerCc::unmarshal(&raw),
+        Err(Error::InvalidLength(32))
+    );
+
+    let twcc = TransportLayerCc {
+        padding: vec![1, 1],
+        ..transport_cc()
+    };
+    assert_eq!(
+        twcc.marshal_to(&mut [0u8; 64]),
+        Err(Error::InvalidPadding(1))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_transport_cc_errors() {
+    // reserved symbol in a run length chunk
+    let mut raw = TRANSPORT_CC;
+    raw[20..22].copy_from_slice(&[0x60, 0x05]);
+    assert_eq!(
+        TransportLayerCc::unmarshal(&raw),
+        Err(Error::InvalidPacketStatus)
+    );
+
+    // more packets than chunks
+    let mut raw = TRANSPORT_CC;
+    raw[14..16].copy_from_slice(&[0x00, 0x08]);
+    assert_eq!(
+        TransportLayerCc::unmarshal(&raw),
+        Err(Error::InvalidLength(28))
+    );
+
+    // deltas missing
+    let raw = [
+        0x8f, 0xcd, 0x00, 0x05, 0, 0, 0, 1, 0, 0, 0, 2, 0, 0, 0, 3, 0, 0, 0, 0, 0x20, 0x03, 0x01,
+        0x00,
+    ];
+    assert_eq!(
+        TransportLayerCc::unmarshal(&raw),
+        Err(Error::InvalidLength(24))
+    );
+
+    let mut buf = [0u8; 64];
+    let mut twcc = transport_cc();
+    twcc.recv_deltas[0] = 256;
+    assert_eq!(twcc.marshal_to(&mut buf), Err(Error::InvalidRecvDelta(256)));
+    twcc.recv_deltas.pop();
+    assert_eq!(twcc.marshal_to(&mut buf), Err(Error::InvalidPacketStatus));
+    let twcc = TransportLayerCc {
+        reference_time: 0x0080_0000,
+        ..transport_cc()
+    };
+    assert_eq!(
+        twcc.marshal_to(&mut buf),
+        Err(Error::InvalidReferenceTime(0x0080_0000))
+    );
+    let twcc = TransportLayerCc {
+        packet_status_count: 8,
+        ..transport_cc()
+    };
+    assert_eq!(twcc.marshal_to(&mut buf), Err(Error::InvalidPacketStatus));
+}
@@ -0,0 +1,34 @@
+use thiserror::Error;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    #[error("rtp: header needs {needed} bytes, packet has {actual}")]
+    HeaderTooShort { needed: usize, actual: usize },
+    #[error("rtp: unsupported version {0}")]
+    InvalidVersion(u8),
+    #[error("rtp: padding length {0} exceeds the payload")]
+    InvalidPadding(u8),
+    #[error("rtp: payload type {0} out of range")]
+    InvalidPayloadType(u8),
+    #[error("rtp: {0} csrcs, at most 15 are allowed")]
+    TooManyCsrcs(usize),
+    #[error("rtp: extension length {0} is not a multiple of 4 or too long")]
+    InvalidExtensionLength(usize),
+    #[error("rtp: unsupported header extension profile {0:#06x}")]
+    UnsupportedExtensionProfile(u16),
+    #[error("rtp: header extension element exceeds the extension block")]
+    MalformedHeaderExtension,
+    #[error("rtp: invalid header extension id {0}")]
+    InvalidHeaderExtensionId(u8),
+    #[error("rtp: header extension length {0} exceeds 255")]
+    HeaderExtensionTooLong(usize),
+    #[error("rtp: invalid value for header extension {0}")]
+    InvalidHeaderExtensionValue(String),
+    #[error("rtp: header extension {0} was not negotiated")]
+    UnmappedHeaderExtension(String),
+    #[error("rtp: buffer needs {needed} bytes, has {available}")]
+    BufferTooSmall { needed: usize, available: usize },
+}
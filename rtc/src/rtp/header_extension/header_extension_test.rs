@@ -0,0 +1,414 @@
+use super::*;
+use crate::rtp::packet::RtpPacketBuilder;
+use crate::sdp::SDP;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+const EXTMAP_SDP: &str = "v=0\r\n\
+o=- 4611731400430051336 2 IN IP4 127.0.0.1\r\n\
+s=-\r\n\
+t=0 0\r\n\
+a=extmap-allow-mixed\r\n\
+m=audio 9 UDP/TLS/RTP/SAVPF 111\r\n\
+c=IN IP4 0.0.0.0\r\n\
+a=mid:0\r\n\
+a=extmap:1 urn:ietf:params:rtp-hdrext:ssrc-audio-level\r\n\
+a=extmap:3 http://www.ietf.org/id/draft-holmer-rmcat-transport-wide-cc-extensions-01\r\n\
+a=extmap:4 urn:ietf:params:rtp-hdrext:sdes:mid\r\n\
+a=extmap:16 http://www.webrtc.org/experiments/rtp-hdrext/abs-send-time\r\n\
+a=rtpmap:111 opus/48000/2\r\n";
+
+#[test]
+fn test_unmarshal_one_byte() -> Result<()> {
+    // id 1 len 1, padding, id 2 len 3, reserved id 15 stops parsing
+    let data = [0x10, 0xaa, 0x00, 0x22, 1, 2, 3, 0xf0, 0x30, 0xbb];
+    let extensions = unmarshal_header_extensions(ONE_BYTE_PROFILE, &data)?;
+    assert_eq!(
+        extensions,
+        vec![
+            HeaderExtension {
+                id: 1,
+                data: &[0xaa]
+            },
+            HeaderExtension {
+                id: 2,
+                data: &[1, 2, 3]
+            },
+        ]
+    );
+
+    assert_eq!(
+        unmarshal_header_extensions(ONE_BYTE_PROFILE, &[0x13, 1, 2]),
+        Err(Error::MalformedHeaderExtension)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_unmarshal_two_byte() -> Result<()> {
+    // id 1 len 0, padding, id 200 len 2, appbits set in the profile
+    let data = [1, 0, 0, 200, 2, 7, 8, 0];
+    let extensions = unmarshal_header_extensions(TWO_BYTE_PROFILE | 0x5, &data)?;
+    assert_eq!(
+        extensions,
+        vec![
+            HeaderExtension { id: 1, data: &[] },
+            HeaderExtension {
+                id: 200,
+                data: &[7, 8]
+            },
+        ]
+    );
+
+    assert_eq!(
+        unmarshal_header_extensions(TWO_BYTE_PROFILE, &[1]),
+        Err(Error::MalformedHeaderExtension)
+    );
+    assert_eq!(
+        unmarshal_header_extensions(TWO_BYTE_PROFILE, &[1, 3, 0]),
+        Err(Error::MalformedHeaderExtension)
+    );
+    assert_eq!(
+        unmarshal_header_extensions(0x1234, &data),
+        Err(Error::UnsupportedExtensionProfile(0x1234))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_marshal_header_extensions() -> Result<()> {
+    let one_byte = [
+        HeaderExtension {
+            id: 1,
+            data: &[0xaa],
+        },
+        HeaderExtension {
+            id: 14,
+            data: &[0; 16],
+        },
+    ];
+    let mut buf = [0xffu8; 32];
+    assert_eq!(
+        marshal_header_extensions_to(&one_byte, &mut buf)?,
+        (ONE_BYTE_PROFILE, 20)
+    );
+    assert_eq!(&buf[..3], &[0x10, 0xaa, 0xef]);
+    assert_eq!(&buf[19..21], &[0, 0xff]);
+    assert_eq!(
+        unmarshal_header_extensions(ONE_BYTE_PROFILE, &buf[..20])?,
+        one_byte
+    );
+
+    // an empty element, an id above 14 or more than 16 bytes need two bytes
+    for extension in [
+        HeaderExtension { id: 1, data: &[] },
+        HeaderExtension { id: 15, data: &[1] },
+        HeaderExtension {
+            id: 1,
+            data: &[0; 17],
+        },
+    ] {
+        let (profile, n) = marshal_header_extensions_to(&[extension], &mut buf)?;
+        assert_eq!(profile, TWO_BYTE_PROFILE);
+        assert_eq!(
+            unmarshal_header_extensions(profile, &buf[..n])?,
+            vec![extension]
+        );
+    }
+
+    assert_eq!(
+        marshal_header_extensions_to(&[HeaderExtension { id: 0, data: &[1] }], &mut buf),
+        Err(Error::InvalidHeaderExtensionId(0))
+    );
+    assert_eq!(
+        marshal_header_extensions_to(
+            &[HeaderExtension {
+                id: 1,
+                data: &[0; 256]
+            }],
+            &mut buf
+        ),
+        Err(Error::HeaderExtensionTooLong(256))
+    );
+    assert_eq!(
+        marshal_header_extensions_to(&one_byte, &mut buf[..19]),
+        Err(Error::BufferTooSmall {
+            needed: 20,
+            available: 19
+        })
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_header_extension_values() -> Result<()> {
+    let passingtests = [
+        (
+            HeaderExtensionValue::AbsSendTime(0x123456),
+            vec![0x12, 0x34, 0x56],
+        ),
+        (HeaderExtensionValue::TransportCc(0xabcd), vec![0xab, 0xcd]),
+        (
+            HeaderExtensionValue::PlayoutDelay {
+                min: 0x123,
+                max: 0x456,
+            },
+            vec![0x12, 0x34, 0x56],
+        ),
+        (HeaderExtensionValue::VideoContentType(1), vec![1]),
+        (
+            HeaderExtensionValue::SdesMid("audio".to_owned()),
+            b"audio".to_vec(),
+        ),
+        (
+            HeaderExtensionValue::SdesRid("hi".to_owned()),
+            b"hi".to_vec(),
+        ),
+        (
+            HeaderExtensionValue::SdesRepairedRid("lo".to_owned()),
+            b"lo".to_vec(),
+        ),
+        (
+            HeaderExtensionValue::AudioLevel {
+                voice_activity: true,
+                level: 30,
+            },
+            vec![0x9e],
+        ),
+        (
+            HeaderExtensionValue::VideoOrientation {
+                camera: true,
+                flip: false,
+                rotation: 270,
+            },
+            vec![0x0b],
+        ),
+        (
+            HeaderExtensionValue::TransmissionOffset(-2),
+            vec![0xff, 0xff, 0xfe],
+        ),
+        (
+            HeaderExtensionValue::VideoTiming(VideoTiming {
+                flags: 1,
+                encode_start_delta: 2,
+                encode_finish_delta: 0x0304,
+                packetization_finish_delta: 5,
+                pacer_exit_delta: 6,
+                network_timestamp_delta: 0,
+                network2_timestamp_delta: 0xffff,
+            }),
+            vec![1, 0, 2, 3, 4, 0, 5, 0, 6, 0, 0, 0xff, 0xff],
+        ),
+        (
+            HeaderExtensionValue::ColorSpace(ColorSpace {
+                primaries: 1,
+                transfer: 16,
+                matrix: 9,
+                range: 2,
+                chroma_siting_horizontal: 1,
+                chroma_siting_vertical: 2,
+                hdr_metadata: None,
+            }),
+            vec![1, 16, 9, 0x26],
+        ),
+        (
+            HeaderExtensionValue::ColorSpace(ColorSpace {
+                primaries: 9,
+                transfer: 16,
+                matrix: 9,
+                range: 1,
+                chroma_siting_horizontal: 0,
+                chroma_siting_vertical: 0,
+                hdr_metadata: Some(HdrMetadata {
+                    primary_r: (35400, 14600),
+                    primary_g: (8500, 39850),
+                    primary_b: (6550, 2300),
+                    white_point: (15635, 16450),
+                    luminance_max: 1000,
+                    luminance_min: 50,
+                    max_content_light_level: 1000,
+                    max_frame_average_light_level: 400,
+                }),
+            }),
+            vec![
+                9, 16, 9, 0x10, 0x8a, 0x48, 0x39, 0x08, 0x21, 0x34, 0x9b, 0xaa, 0x19, 0x96, 0x08,
+                0xfc, 0x3d, 0x13, 0x40, 0x42, 0x03, 0xe8, 0x00, 0x32, 0x03, 0xe8, 0x01, 0x90,
+            ],
+        ),
+        (
+            HeaderExtensionValue::Other {
+                uri: "urn:example:params:rtp-hdrext:other".to_owned(),
+                data: vec![1, 2],
+            },
+            vec![1, 2],
+        ),
+    ];
+    for (value, data) in passingtests {
+        assert_eq!(value.marshal()?, data, "{:?}", value);
+        assert_eq!(HeaderExtensionValue::unmarshal(value.uri(), &data)?, value);
+    }
+
+    let failingtests = [
+        (ABS_SEND_TIME_EXT, vec![1, 2]),
+        (TRANSPORT_CC_EXT, vec![1, 2, 3]),
+        (PLAYOUT_DELAY_EXT, vec![]),
+        (SDES_MID_EXT, vec![]),
+        (SDES_RID_EXT, vec![0xff, 0xfe]),
+        (AUDIO_LEVEL_EXT, vec![1, 2]),
+        (VIDEO_ORIENTATION_EXT, vec![]),
+        (TOFFSET_EXT, vec![1]),
+        (VIDEO_TIMING_EXT, vec![0; 12]),
+        (COLOR_SPACE_EXT, vec![1, 1, 1]),
+        (COLOR_SPACE_EXT, vec![1, 1, 1, 0x40]),
+    ];
+    for (uri, data) in failingtests {
+        assert_eq!(
+            HeaderExtensionValue::unmarshal(uri, &data),
+            Err(Error::InvalidHeaderExtensionValue(uri.to_owned()))
+        );
+    }
+
+    for value in [
+        HeaderExtensionValue::AbsSendTime(0x0100_0000),
+        HeaderExtensionValue::PlayoutDelay {
+            min: 0x1000,
+            max: 0,
+        },
+        HeaderExtensionValue::SdesMid(String::new()),
+        HeaderExtensionValue::AudioLevel {
+            voice_activity: false,
+            level: 128,
+        },
+        HeaderExtensionValue::VideoOrientation {
+            camera: false,
+            flip: false,
+            rotation: 45,
+        },
+        HeaderExtensionValue::TransmissionOffset(0x0080_0000),
+        HeaderExtensionValue::ColorSpace(ColorSpace {
+            range: 4,
+            ..Default::default()
+        }),
+    ] {
+        assert!(value.marshal().is_err(), "{:?}", value);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_header_extension_map() -> Result<()> {
+    let sdp = SDP::unmarshal(EXTMAP_SDP.as_bytes()).unwrap();
+    let map = HeaderExtensionMap::from_media_description(&sdp.media_descriptions[0]).unwrap();
+    assert_eq!(map.uri(1), Some(AUDIO_LEVEL_EXT));
+    assert_eq!(map.uri(2), None);
+    assert_eq!(map.id(TRANSPORT_CC_EXT), Some(3));
+    assert_eq!(map.id(VIDEO_ORIENTATION_EXT), None);
+
+    let values = vec![
+        HeaderExtensionValue::AudioLevel {
+            voice_activity: false,
+            level: 127,
+        },
+        HeaderExtensionValue::TransportCc(7),
+        HeaderExtensionValue::SdesMid("0".to_owned()),
+    ];
+    let (profile, data) = map.marshal(&values)?;
+    assert_eq!(profile, ONE_BYTE_PROFILE);
+    let buf = RtpPacketBuilder::new(111, 1, 960, 0x1234)
+        .with_extension(profile, &data)
+        .with_payload(&[0xfc])
+        .marshal()?;
+    let packet = RtpPacket::parse(&buf)?;
+    assert_eq!(map.unmarshal(&packet)?, values);
+    assert_eq!(
+        map.value(&packet, TRANSPORT_CC_EXT)?,
+        Some(HeaderExtensionValue::TransportCc(7))
+    );
+    assert_eq!(map.value(&packet, ABS_SEND_TIME_EXT)?, None);
+    assert_eq!(packet.payload(), &[0xfc]);
+
+    // the two-byte id 16 switches the whole block to two-byte elements
+    let (profile, data) = map.marshal(&[HeaderExtensionValue::AbsSendTime(1)])?;
+    assert_eq!(profile, TWO_BYTE_PROFILE);
+    assert_eq!(data, vec![16, 3, 0, 0, 1, 0, 0, 0]);
+
+    assert_eq!(
+        map.marshal(&[HeaderExtensionValue::VideoContentType(0)]),
+        Err(Error::UnmappedHeaderExtension(
+            VIDEO_CONTENT_TYPE_EXT.to_owned()
+        ))
+    );
+
+    // ids that were not negotiated are ignored
+    let buf = RtpPacketBuilder::new(111, 1, 960, 0x1234)
+        .with_extension(ONE_BYTE_PROFILE, &[0x20, 0xff, 0, 0])
+        .marshal()?;
+    assert_eq!(map.unmarshal(&RtpPacket::parse(&buf)?)?, vec![]);
+
+    Ok(())
+}
+
+#[test]
+fn test_header_extension_map_from_effective_media() -> Result<()> {
+    let with_session_extmap = EXTMAP_SDP.replace(
+        "a=extmap-allow-mixed\r\n",
+        "a=extmap-allow-mixed\r\na=extmap:5 urn:ietf:params:rtp-hdrext:toffset\r\n",
+    );
+    let sdp = SDP::unmarshal(with_session_extmap.as_bytes()).unwrap();
+
+    let media = HeaderExtensionMap::from_media_description(&sdp.media_descriptions[0]).unwrap();
+    assert_eq!(media.uri(5), None);
+
+    let map = HeaderExtensionMap::from_effective_media(&sdp.effective_media(0).unwrap());
+    assert_eq!(map.uri(5), Some(TOFFSET_EXT));
+    assert_eq!(map.id(AUDIO_LEVEL_EXT), Some(1));
+
+    let (profile, data) = map.marshal(&[HeaderExtensionValue::TransmissionOffset(1)])?;
+    let buf = RtpPacketBuilder::new(111, 1, 960, 0x1234)
+        .with_extension(profile, &data)
+        .marshal()?;
+    assert_eq!(
+        map.value(&RtpPacket::parse(&buf)?, TOFFSET_EXT)?,
+        Some(HeaderExtensionValue::TransmissionOffset(1))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_fuzz_header_extensions() {
+    let mut rng = StdRng::seed_from_u64(0x5eed);
+    for _ in 0..10000 {
+        let len = rng.gen_range(0..32);
+        let data: Vec<u8> = (0..len).map(|_| rng.gen()).collect();
+        let profile = if rng.gen_bool(0.5) {
+            ONE_BYTE_PROFILE
+        } else {
+            TWO_BYTE_PROFILE
+        };
+
+        let extensions = match unmarshal_header_extensions(profile, &data) {
+            Ok(extensions) => extensions,
+            Err(_) => continue,
+        };
+        for extension in &extensions {
+            for uri in ext_idx_url_map {
+                let _ = HeaderExtensionValue::unmarshal(uri, extension.data);
+            }
+        }
+
+        // whatever parsed re-encodes into the same elements
+        let mut buf = [0u8; 512];
+        if let Ok((profile, n)) = marshal_header_extensions_to(&extensions, &mut buf) {
+            assert_eq!(
+                unmarshal_header_extensions(profile, &buf[..n]),
+                Ok(extensions)
+            );
+        }
+    }
+}
@@ -0,0 +1,603 @@
+#[cfg(test)]
+mod header_extension_test;
+
+use super::error::{Error, Result};
+use super::packet::RtpPacket;
+use crate::sdp::effective::EffectiveMedia;
+use crate::sdp::extmap::*;
+use crate::sdp::media::MediaDescription;
+
+/// "defined by profile" value of one-byte header extensions
+/// <https://tools.ietf.org/html/rfc8285#section-4.2>
+pub const ONE_BYTE_PROFILE: u16 = 0xBEDE;
+
+/// "defined by profile" value of two-byte header extensions, the low 4
+/// bits are application defined
+/// <https://tools.ietf.org/html/rfc8285#section-4.3>
+pub const TWO_BYTE_PROFILE: u16 = 0x1000;
+
+const TWO_BYTE_PROFILE_MASK: u16 = 0xFFF0;
+
+/// one-byte id that stops the parsing of the remaining extensions
+const ONE_BYTE_ID_RESERVED: u8 = 15;
+
+/// maximum element length in the one-byte form
+const ONE_BYTE_MAX_LENGTH: usize = 16;
+
+/// size of the video-timing extension, the legacy form without flags is
+/// not supported
+const VIDEO_TIMING_SIZE: usize = 13;
+
+/// sizes of the color-space extension without and with HDR metadata
+const COLOR_SPACE_SIZE: usize = 4;
+const COLOR_SPACE_HDR_SIZE: usize = 28;
+
+/// HeaderExtension is a single element of an RTP header extension block,
+/// borrowing its data from the packet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeaderExtension<'a> {
+    pub id: u8,
+    pub data: &'a [u8],
+}
+
+/// unmarshal_header_extensions splits the extension block of a packet into
+/// its elements, skipping padding
+pub fn unmarshal_header_extensions(profile: u16, data: &[u8]) -> Result<Vec<HeaderExtension<'_>>> {
+    let two_byte = if profile == ONE_BYTE_PROFILE {
+        false
+    } else if profile & TWO_BYTE_PROFILE_MASK == TWO_BYTE_PROFILE {
+        true
+    } else {
+        return Err(Error::UnsupportedExtensionProfile(profile));
+    };
+
+    let mut extensions = vec![];
+    let mut offset = 0;
+    while offset < data.len() {
+        let (id, len, header) = if two_byte {
+            let id = data[offset];
+            if id == 0 {
+                offset += 1;
+                continue;
+            }
+            let len = *data
+                .get(offset + 1)
+                .ok_or(Error::MalformedHeaderExtension)?;
+            (id, len as usize, 2)
+        } else {
+            let id = data[offset] >> 4;
+            if id == 0 {
+                offset += 1;
+                continue;
+            }
+            if id == ONE_BYTE_ID_RESERVED {
+                break;
+            }
+            (id, (data[offset] & 0x0F) as usize + 1, 1)
+        };
+
+        let start = offset + header;
+        let end = start + len;
+        if end > data.len() {
+            return Err(Error::MalformedHeaderExtension);
+        }
+        extensions.push(HeaderExtension {
+            id,
+            data: &data[start..end],
+        });
+        offset = end;
+    }
+
+    Ok(extensions)
+}
+
+/// header_extensions_profile returns ONE_BYTE_PROFILE if every element fits
+/// the one-byte form, else TWO_BYTE_PROFILE
+pub fn header_extensions_profile(extensions: &[HeaderExtension<'_>]) -> Result<u16> {
+    let mut profile = ONE_BYTE_PROFILE;
+    for extension in extensions {
+        if extension.id == 0 {
+            return Err(Error::InvalidHeaderExtensionId(extension.id));
+        }
+        if extension.data.len() > u8::MAX as usize {
+            return Err(Error::HeaderExtensionTooLong(extension.data.len()));
+        }
+        if extension.id >= ONE_BYTE_ID_RESERVED
+            || extension.data.is_empty()
+            || extension.data.len() > ONE_BYTE_MAX_LENGTH
+        {
+            profile = TWO_BYTE_PROFILE;
+        }
+    }
+    Ok(profile)
+}
+
+/// header_extensions_marshal_size returns the size of the extension block
+/// including padding to a multiple of 4, without the 4 byte extension header
+pub fn header_extensions_marshal_size(extensions: &[HeaderExtension<'_>]) -> Result<usize> {
+    let header = match header_extensions_profile(extensions)? {
+        ONE_BYTE_PROFILE => 1,
+        _ => 2,
+    };
+    let size: usize = extensions.iter().map(|e| header + e.data.len()).sum();
+    Ok(size.div_ceil(4) * 4)
+}
+
+/// marshal_header_extensions_to writes the elements into `buf`, in the
+/// one-byte form when possible, and returns the profile and the number of
+/// bytes written. The result is meant for [`RtpPacketBuilder::with_extension`].
+///
+/// [`RtpPacketBuilder::with_extension`]: super::packet::RtpPacketBuilder::with_extension
+pub fn marshal_header_extensions_to(
+    extensions: &[HeaderExtension<'_>],
+    buf: &mut [u8],
+) -> Result<(u16, usize)> {
+    let profile = header_extensions_profile(extensions)?;
+    let size = header_extensions_marshal_size(extensions)?;
+    if buf.len() < size {
+        return Err(Error::BufferTooSmall {
+            needed: size,
+            available: buf.len(),
+        });
+    }
+
+    let mut offset = 0;
+    for extension in extensions {
+        let len = extension.data.len();
+        if profile == ONE_BYTE_PROFILE {
+            buf[offset] = (extension.id << 4) | (len - 1) as u8;
+            offset += 1;
+        } else {
+            buf[offset] = extension.id;
+            buf[offset + 1] = len as u8;
+            offset += 2;
+        }
+        buf[offset..offset + len].copy_from_slice(extension.data);
+        offset += len;
+    }
+    buf[offset..size].fill(0);
+
+    Ok((profile, size))
+}
+
+impl<'a> RtpPacket<'a> {
+    /// header_extensions returns the RFC 8285 elements of the extension
+    /// block, empty if the packet has none
+    pub fn header_extensions(&self) -> Result<Vec<HeaderExtension<'a>>> {
+        match (self.extension_profile(), self.extension_data()) {
+            (Some(profile), Some(data)) => unmarshal_header_extensions(profile, data),
+            _ => Ok(vec![]),
+        }
+    }
+
+    /// header_extension returns the data of the element with the given id
+    pub fn header_extension(&self, id: u8) -> Result<Option<&'a [u8]>> {
+        Ok(self
+            .header_extensions()?
+            .into_iter()
+            .find(|e| e.id == id)
+            .map(|e| e.data))
+    }
+}
+
+/// HeaderExtensionValue is the decoded payload of a header extension,
+/// identified by its URI
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HeaderExtensionValue {
+    /// 24-bit send time in 6.18 fixed point seconds
+    /// <https://webrtc.googlesource.com/src/+/refs/heads/main/docs/native-code/rtp-hdrext/abs-send-time>
+    AbsSendTime(u32),
+    /// transport-wide sequence number
+    TransportCc(u16),
+    /// minimum and maximum playout delay in units of 10 ms, 12 bits each
+    PlayoutDelay {
+        min: u16,
+        max: u16,
+    },
+    VideoContentType(u8),
+    /// <https://tools.ietf.org/html/rfc8843#section-15.1>
+    SdesMid(String),
+    /// <https://tools.ietf.org/html/rfc8852#section-3.1>
+    SdesRid(String),
+    SdesRepairedRid(String),
+    /// level in -dBov, 0 to 127, and the voice activity flag
+    /// <https://tools.ietf.org/html/rfc6464#section-3>
+    AudioLevel {
+        voice_activity: bool,
+        level: u8,
+    },
+    /// coordination of video orientation, rotation in degrees
+    /// <https://www.etsi.org/deliver/etsi_ts/126100_126199/126114/16.07.00_60/ts_126114v160700p.pdf>
+    VideoOrientation {
+        camera: bool,
+        flip: bool,
+        rotation: u16,
+    },
+    /// 24-bit signed transmission time offset
+    /// <https://tools.ietf.org/html/rfc5450#section-2>
+    TransmissionOffset(i32),
+    /// <https://webrtc.googlesource.com/src/+/refs/heads/main/docs/native-code/rtp-hdrext/video-timing>
+    VideoTiming(VideoTiming),
+    /// <https://webrtc.googlesource.com/src/+/refs/heads/main/docs/native-code/rtp-hdrext/color-space>
+    ColorSpace(ColorSpace),
+    /// extensions without a typed codec, e.g. unregistered URIs
+    Other {
+        uri: String,
+        data: Vec<u8>,
+    },
+}
+
+/// VideoTiming carries the delays of a video frame in the sender, in ms
+/// relative to the capture time
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VideoTiming {
+    pub flags: u8,
+    pub encode_start_delta: u16,
+    pub encode_finish_delta: u16,
+    pub packetization_finish_delta: u16,
+    pub pacer_exit_delta: u16,
+    pub network_timestamp_delta: u16,
+    pub network2_timestamp_delta: u16,
+}
+
+/// ColorSpace carries the color space of a video frame, the values are
+/// the codes of ITU-T H.273
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ColorSpace {
+    pub primaries: u8,
+    pub transfer: u8,
+    pub matrix: u8,
+    /// 2-bit range and chroma siting fields
+    pub range: u8,
+    pub chroma_siting_horizontal: u8,
+    pub chroma_siting_vertical: u8,
+    pub hdr_metadata: Option<HdrMetadata>,
+}
+
+/// HdrMetadata is the SMPTE ST 2086 mastering display and the CTA-861.3
+/// content light level. Chromaticity coordinates (x, y) are in units of
+/// 0.00002, luminance_max in nits and luminance_min in units of 0.0001 nits.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HdrMetadata {
+    pub primary_r: (u16, u16),
+    pub primary_g: (u16, u16),
+    pub primary_b: (u16, u16),
+    pub white_point: (u16, u16),
+    pub luminance_max: u16,
+    pub luminance_min: u16,
+    pub max_content_light_level: u16,
+    pub max_frame_average_light_level: u16,
+}
+
+impl HeaderExtensionValue {
+    /// unmarshal decodes the data of the extension identified by `uri`
+    pub fn unmarshal(uri: &str, data: &[u8]) -> Result<Self> {
+        let invalid = || Error::InvalidHeaderExtensionValue(uri.to_owned());
+        let fixed = |len: usize| {
+            if data.len() == len {
+                Ok(data)
+            } else {
+                Err(invalid())
+            }
+        };
+        let string = || {
+            if data.is_empty() {
+                return Err(invalid());
+            }
+            String::from_utf8(data.to_vec()).map_err(|_| invalid())
+        };
+
+        Ok(match get_idx_by_ext_uri(uri) {
+            ABS_SEND_TIME_EXT_IDX => {
+                let b = fixed(3)?;
+                HeaderExtensionValue::AbsSendTime(u32::from_be_bytes([0, b[0], b[1], b[2]]))
+            }
+            TRANSPORT_CC_EXT_IDX => {
+                let b = fixed(2)?;
+                HeaderExtensionValue::TransportCc(u16::from_be_bytes([b[0], b[1]]))
+            }
+            PLAYOUT_DELAY_EXT_IDX => {
+                let b = fixed(3)?;
+                HeaderExtensionValue::PlayoutDelay {
+                    min: (b[0] as u16) << 4 | (b[1] >> 4) as u16,
+                    max: ((b[1] & 0x0F) as u16) << 8 | b[2] as u16,
+                }
+            }
+            VIDEO_CONTENT_TYPE_EXT_IDX => HeaderExtensionValue::VideoContentType(fixed(1)?[0]),
+            SDES_MID_EXT_IDX => HeaderExtensionValue::SdesMid(string()?),
+            SDES_RID_EXT_IDX => HeaderExtensionValue::SdesRid(string()?),
+            SDES_RRID_EXT_IDX => HeaderExtensionValue::SdesRepairedRid(string()?),
+            AUDIO_LEVEL_EXT_IDX => {
+                let b = fixed(1)?[0];
+                HeaderExtensionValue::AudioLevel {
+                    voice_activity: b & 0x80 != 0,
+                    level: b & 0x7F,
+                }
+            }
+            VIDEO_ORIENTATION_EXT_IDX => {
+                let b = fixed(1)?[0];
+                HeaderExtensionValue::VideoOrientation {
+                    camera: b & 0x08 != 0,
+                    flip: b & 0x04 != 0,
+                    rotation: (b & 0x03) as u16 * 90,
+                }
+            }
+            TOFFSET_EXT_IDX => {
+                let b = fixed(3)?;
+                // sign extend the 24-bit value
+                let offset = i32::from_be_bytes([b[0], b[1], b[2], 0]) >> 8;
+                HeaderExtensionValue::TransmissionOffset(offset)
+            }
+            VIDEO_TIMING_EXT_IDX => {
+                let b = fixed(VIDEO_TIMING_SIZE)?;
+                let delta = |i: usize| u16::from_be_bytes([b[i], b[i + 1]]);
+                HeaderExtensionValue::VideoTiming(VideoTiming {
+                    flags: b[0],
+                    encode_start_delta: delta(1),
+                    encode_finish_delta: delta(3),
+                    packetization_finish_delta: delta(5),
+                    pacer_exit_delta: delta(7),
+                    network_timestamp_delta: delta(9),
+                    network2_timestamp_delta: delta(11),
+                })
+            }
+            COLOR_SPACE_EXT_IDX => {
+                if data.len() != COLOR_SPACE_SIZE && data.len() != COLOR_SPACE_HDR_SIZE {
+                    return Err(invalid());
+                }
+                if data[3] & 0xC0 != 0 {
+                    return Err(invalid());
+                }
+                let u16_at = |i: usize| u16::from_be_bytes([data[i], data[i + 1]]);
+                let xy_at = |i: usize| (u16_at(i), u16_at(i + 2));
+                let hdr_metadata = if data.len() == COLOR_SPACE_HDR_SIZE {
+                    Some(HdrMetadata {
+                        primary_r: xy_at(4),
+                        primary_g: xy_at(8),
+                        primary_b: xy_at(12),
+                        white_point: xy_at(16),
+                        luminance_max: u16_at(20),
+                        luminance_min: u16_at(22),
+                        max_content_light_level: u16_at(24),
+                        max_frame_average_light_level: u16_at(26),
+                    })
+                } else {
+                    None
+                };
+                HeaderExtensionValue::ColorSpace(ColorSpace {
+                    primaries: data[0],
+                    transfer: data[1],
+                    matrix: data[2],
+                    range: (data[3] >> 4) & 0x03,
+                    chroma_siting_horizontal: (data[3] >> 2) & 0x03,
+                    chroma_siting_vertical: data[3] & 0x03,
+                    hdr_metadata,
+                })
+            }
+            _ => HeaderExtensionValue::Other {
+                uri: uri.to_owned(),
+                data: data.to_vec(),
+            },
+        })
+    }
+
+    /// uri returns the URI identifying this extension
+    pub fn uri(&self) -> &str {
+        match self {
+            HeaderExtensionValue::AbsSendTime(_) => ABS_SEND_TIME_EXT,
+            HeaderExtensionValue::TransportCc(_) => TRANSPORT_CC_EXT,
+            HeaderExtensionValue::PlayoutDelay { .. } => PLAYOUT_DELAY_EXT,
+            HeaderExtensionValue::VideoContentType(_) => VIDEO_CONTENT_TYPE_EXT,
+            HeaderExtensionValue::SdesMid(_) => SDES_MID_EXT,
+            HeaderExtensionValue::SdesRid(_) => SDES_RID_EXT,
+            HeaderExtensionValue::SdesRepairedRid(_) => SDES_RRID_EXT,
+            HeaderExtensionValue::AudioLevel { .. } => AUDIO_LEVEL_EXT,
+            HeaderExtensionValue::VideoOrientation { .. } => VIDEO_ORIENTATION_EXT,
+            HeaderExtensionValue::TransmissionOffset(_) => TOFFSET_EXT,
+            HeaderExtensionValue::VideoTiming(_) => VIDEO_TIMING_EXT,
+            HeaderExtensionValue::ColorSpace(_) => COLOR_SPACE_EXT,
+            HeaderExtensionValue::Other { uri, .. } => uri,
+        }
+    }
+
+    /// marshal encodes the value, out of range fields are rejected
+    pub fn marshal(&self) -> Result<Vec<u8>> {
+        let invalid = || Error::InvalidHeaderExtensionValue(self.uri().to_owned());
+
+        Ok(match self {
+            HeaderExtensionValue::AbsSendTime(time) => {
+                if *time > 0x00FF_FFFF {
+                    return Err(invalid());
+                }
+                time.to_be_bytes()[1..].to_vec()
+            }
+            HeaderExtensionValue::TransportCc(seq) => seq.to_be_bytes().to_vec(),
+            HeaderExtensionValue::PlayoutDelay { min, max } => {
+                if *min > 0x0FFF || *max > 0x0FFF {
+                    return Err(invalid());
+                }
+                vec![
+                    (min >> 4) as u8,
+                    ((min & 0x0F) << 4) as u8 | (max >> 8) as u8,
+                    *max as u8,
+                ]
+            }
+            HeaderExtensionValue::VideoContentType(content_type) => vec![*content_type],
+            HeaderExtensionValue::SdesMid(value)
+            | HeaderExtensionValue::SdesRid(value)
+            | HeaderExtensionValue::SdesRepairedRid(value) => {
+                if value.is_empty() {
+                    return Err(invalid());
+                }
+                value.as_bytes().to_vec()
+            }
+            HeaderExtensionValue::AudioLevel {
+                voice_activity,
+                level,
+            } => {
+                if *level > 0x7F {
+                    return Err(invalid());
+                }
+                vec![(*voice_activity as u8) << 7 | level]
+            }
+            HeaderExtensionValue::VideoOrientation {
+                camera,
+                flip,
+                rotation,
+            } => {
+                if rotation % 90 != 0 || *rotation >= 360 {
+                    return Err(invalid());
+                }
+                vec![(*camera as u8) << 3 | (*flip as u8) << 2 | (rotation / 90) as u8]
+            }
+            HeaderExtensionValue::TransmissionOffset(offset) => {
+                if !(-0x0080_0000..=0x007F_FFFF).contains(offset) {
+                    return Err(invalid());
+                }
+                offset.to_be_bytes()[1..].to_vec()
+            }
+            HeaderExtensionValue::VideoTiming(timing) => {
+                let mut buf = vec![timing.flags];
+                for delta in [
+                    timing.encode_start_delta,
+                    timing.encode_finish_delta,
+                    timing.packetization_finish_delta,
+                    timing.pacer_exit_delta,
+                    timing.network_timestamp_delta,
+                    timing.network2_timestamp_delta,
+                ] {
+                    buf.extend_from_slice(&delta.to_be_bytes());
+                }
+                buf
+            }
+            HeaderExtensionValue::ColorSpace(color_space) => {
+                if color_space.range > 0x03
+                    || color_space.chroma_siting_horizontal > 0x03
+                    || color_space.chroma_siting_vertical > 0x03
+                {
+                    return Err(invalid());
+                }
+                let mut buf = vec![
+                    color_space.primaries,
+                    color_space.transfer,
+                    color_space.matrix,
+                    color_space.range << 4
+                        | color_space.chroma_siting_horizontal << 2
+                        | color_space.chroma_siting_vertical,
+                ];
+                if let Some(hdr) = &color_space.hdr_metadata {
+                    for value in [
+                        hdr.primary_r.0,
+                        hdr.primary_r.1,
+                        hdr.primary_g.0,
+                        hdr.primary_g.1,
+                        hdr.primary_b.0,
+                        hdr.primary_b.1,
+                        hdr.white_point.0,
+                        hdr.white_point.1,
+                        hdr.luminance_max,
+                        hdr.luminance_min,
+                        hdr.max_content_light_level,
+                        hdr.max_frame_average_light_level,
+                    ] {
+                        buf.extend_from_slice(&value.to_be_bytes());
+                    }
+                }
+                buf
+            }
+            HeaderExtensionValue::Other { data, .. } => data.clone(),
+        })
+    }
+}
+
+/// HeaderExtensionMap maps the ids negotiated with "a=extmap" to URIs
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HeaderExtensionMap {
+    extmaps: Vec<ExtMap>,
+}
+
+impl HeaderExtensionMap {
+    pub fn new(extmaps: &[ExtMap]) -> Self {
+        HeaderExtensionMap {
+            extmaps: extmaps.to_vec(),
+        }
+    }
+
+    /// from_media_description builds the map of the "a=extmap" attributes
+    /// of a media section, session-level extmaps are not included. Use
+    /// `from_effective_media` for a section of a parsed SDP.
+    pub fn from_media_description(media: &MediaDescription) -> crate::sdp::error::Result<Self> {
+        Ok(HeaderExtensionMap {
+            extmaps: media.extmaps()?,
+        })
+    }
+
+    /// from_effective_media builds the map of the extmaps that apply to a
+    /// media section, including the ones inherited from the session level
+    pub fn from_effective_media(media: &EffectiveMedia<'_>) -> Self {
+        HeaderExtensionMap::new(&media.extmaps)
+    }
+
+    /// uri returns the URI negotiated for an id
+    pub fn uri(&self, id: u8) -> Option<&str> {
+        self.extmaps
+            .iter()
+            .find(|e| e.value == id as isize)
+            .map(|e| e.uri())
+    }
+
+    /// id returns the id negotiated for a URI
+    pub fn id(&self, uri: &str) -> Option<u8> {
+        self.extmaps
+            .iter()
+            .find(|e| e.uri() == uri)
+            .and_then(|e| u8::try_from(e.value).ok())
+    }
+
+    /// unmarshal decodes the header extensions of a packet, elements with
+    /// ids that were not negotiated are ignored
+    pub fn unmarshal(&self, packet: &RtpPacket<'_>) -> Result<Vec<HeaderExtensionValue>> {
+        let mut values = vec![];
+        for extension in packet.header_extensions()? {
+            if let Some(uri) = self.uri(extension.id) {
+                values.push(HeaderExtensionValue::unmarshal(uri, extension.data)?);
+            }
+        }
+        Ok(values)
+    }
+
+    /// value decodes the header extension of a packet with the given URI
+    pub fn value(&self, packet: &RtpPacket<'_>, uri: &str) -> Result<Option<HeaderExtensionValue>> {
+        let id = match self.id(uri) {
+            Some(id) => id,
+            None => return Ok(None),
+        };
+        packet
+            .header_extension(id)?
+            .map(|data| HeaderExtensionValue::unmarshal(uri, data))
+            .transpose()
+    }
+
+    /// marshal encodes the values with their negotiated ids and returns the
+    /// profile and extension data for [`RtpPacketBuilder::with_extension`]
+    ///
+    /// [`RtpPacketBuilder::with_extension`]: super::packet::RtpPacketBuilder::with_extension
+    pub fn marshal(&self, values: &[HeaderExtensionValue]) -> Result<(u16, Vec<u8>)> {
+        let mut encoded = vec![];
+        for value in values {
+            let id = self
+                .id(value.uri())
+                .ok_or_else(|| Error::UnmappedHeaderExtension(value.uri().to_owned()))?;
+            encoded.push((id, value.marshal()?));
+        }
+
+        let extensions: Vec<HeaderExtension<'_>> = encoded
+            .iter()
+            .map(|(id, data)| HeaderExtension { id: *id, data })
+            .collect();
+        let mut buf = vec![0u8; header_extensions_marshal_size(&extensions)?];
+        let (profile, n) = marshal_header_extensions_to(&extensions, &mut buf)?;
+        buf.truncate(n);
+        Ok((profile, buf))
+    }
+}
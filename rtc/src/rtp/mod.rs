@@ -0,0 +1,12 @@
+//! RTP packets as described by RFC 3550
+//! <https://tools.ietf.org/html/rfc3550#section-5.1>
+
+pub mod error;
+pub mod header_extension;
+pub mod packet;
+
+pub use error::{Error, Result};
+pub use header_extension::{
+    ColorSpace, HdrMetadata, HeaderExtension, HeaderExtensionMap, HeaderExtensionValue, VideoTiming,
+};
+pub use packet::{RtpPacket, RtpPacketBuilder};
@@ -0,0 +1,340 @@
+#[cfg(test)]
+mod packet_test;
+
+use super::error::{Error, Result};
+use crate::sdp::codec::Codec;
+use crate::sdp::media::MediaDescription;
+
+/// RTP protocol version, the only one in use
+pub const VERSION: u8 = 2;
+
+/// size of the fixed header, without CSRCs or extension
+pub const HEADER_LENGTH: usize = 12;
+
+/// size of the extension header preceding the extension data
+pub const EXTENSION_HEADER_LENGTH: usize = 4;
+
+/// maximum number of CSRCs, limited by the 4-bit CC field
+pub const MAX_CSRC_COUNT: usize = 15;
+
+const VERSION_SHIFT: u8 = 6;
+const PADDING_BIT: u8 = 0b0010_0000;
+const EXTENSION_BIT: u8 = 0b0001_0000;
+const CSRC_COUNT_MASK: u8 = 0b0000_1111;
+const MARKER_BIT: u8 = 0b1000_0000;
+const PAYLOAD_TYPE_MASK: u8 = 0b0111_1111;
+
+/// RtpPacket is a zero-copy view over a validated RTP packet
+/// <https://tools.ietf.org/html/rfc3550#section-5.1>
+///
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |V=2|P|X|  CC   |M|     PT      |       sequence number         |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                           timestamp                           |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |           synchronization source (SSRC) identifier            |
+/// +=+=+=+=+=+=+=+=+=+=+=+=+=+=+=+=+=+=+=+=+=+=+=+=+=+=+=+=+=+=+=+=+
+/// |            contributing source (CSRC) identifiers             |
+/// |                             ....                              |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RtpPacket<'a> {
+    buf: &'a [u8],
+    /// offset of the extension header, if the X bit is set
+    extension_offset: Option<usize>,
+    payload_offset: usize,
+    payload_end: usize,
+}
+
+impl<'a> RtpPacket<'a> {
+    /// parse validates the header of `buf` and returns a view over it. All
+    /// lengths are checked here, so none of the accessors can panic.
+    pub fn parse(buf: &'a [u8]) -> Result<Self> {
+        if buf.len() < HEADER_LENGTH {
+            return Err(Error::HeaderTooShort {
+                needed: HEADER_LENGTH,
+                actual: buf.len(),
+            });
+        }
+
+        let version = buf[0] >> VERSION_SHIFT;
+        if version != VERSION {
+            return Err(Error::InvalidVersion(version));
+        }
+
+        let csrc_count = (buf[0] & CSRC_COUNT_MASK) as usize;
+        let mut offset = HEADER_LENGTH + csrc_count * 4;
+        if buf.len() < offset {
+            return Err(Error::HeaderTooShort {
+                needed: offset,
+                actual: buf.len(),
+            });
+        }
+
+        let extension_offset = if buf[0] & EXTENSION_BIT != 0 {
+            let extension_offset = offset;
+            if buf.len() < offset + EXTENSION_HEADER_LENGTH {
+                return Err(Error::HeaderTooShort {
+                    needed: offset + EXTENSION_HEADER_LENGTH,
+                    actual: buf.len(),
+                });
+            }
+            let words = u16::from_be_bytes([buf[offset + 2], buf[offset + 3]]) as usize;
+            offset += EXTENSION_HEADER_LENGTH + words * 4;
+            if buf.len() < offset {
+                return Err(Error::HeaderTooShort {
+                    needed: offset,
+                    actual: buf.len(),
+                });
+            }
+            Some(extension_offset)
+        } else {
+            None
+        };
+
+        let mut payload_end = buf.len();
+        if buf[0] & PADDING_BIT != 0 {
+            let padding = buf[buf.len() - 1];
+            if padding == 0 || padding as usize > payload_end - offset {
+                return Err(Error::InvalidPadding(padding));
+            }
+            payload_end -= padding as usize;
+        }
+
+        Ok(RtpPacket {
+            buf,
+            extension_offset,
+            payload_offset: offset,
+            payload_end,
+        })
+    }
+
+    pub fn version(&self) -> u8 {
+        self.buf[0] >> VERSION_SHIFT
+    }
+
+    pub fn padding(&self) -> bool {
+        self.buf[0] & PADDING_BIT != 0
+    }
+
+    /// padding_len returns the number of padding bytes, including the
+    /// trailing count byte
+    pub fn padding_len(&self) -> usize {
+        self.buf.len() - self.payload_end
+    }
+
+    pub fn extension(&self) -> bool {
+        self.extension_offset.is_some()
+    }
+
+    pub fn csrc_count(&self) -> usize {
+        (self.buf[0] & CSRC_COUNT_MASK) as usize
+    }
+
+    pub fn marker(&self) -> bool {
+        self.buf[1] & MARKER_BIT != 0
+    }
+
+    pub fn payload_type(&self) -> u8 {
+        self.buf[1] & PAYLOAD_TYPE_MASK
+    }
+
+    pub fn sequence_number(&self) -> u16 {
+        u16::from_be_bytes([self.buf[2], self.buf[3]])
+    }
+
+    pub fn timestamp(&self) -> u32 {
+        u32::from_be_bytes([self.buf[4], self.buf[5], self.buf[6], self.buf[7]])
+    }
+
+    pub fn ssrc(&self) -> u32 {
+        u32::from_be_bytes([self.buf[8], self.buf[9], self.buf[10], self.buf[11]])
+    }
+
+    /// csrcs iterates over the contributing sources
+    pub fn csrcs(&self) -> impl Iterator<Item = u32> + 'a {
+        self.buf[HEADER_LENGTH..HEADER_LENGTH + self.csrc_count() * 4]
+            .chunks_exact(4)
+            .map(|c| u32::from_be_bytes([c[0], c[1], c[2], c[3]]))
+    }
+
+    /// extension_profile returns the "defined by profile" field of the
+    /// header extension, e.g. 0xBEDE for one-byte extensions
+    pub fn extension_profile(&self) -> Option<u16> {
+        self.extension_offset
+            .map(|offset| u16::from_be_bytes([self.buf[offset], self.buf[offset + 1]]))
+    }
+
+    /// extension_data returns the header extension without its 4 byte
+    /// header
+    pub fn extension_data(&self) -> Option<&'a [u8]> {
+        self.extension_offset
+            .map(|offset| &self.buf[offset + EXTENSION_HEADER_LENGTH..self.payload_offset])
+    }
+
+    /// header_len returns the size of the header, including CSRCs and
+    /// extension
+    pub fn header_len(&self) -> usize {
+        self.payload_offset
+    }
+
+    /// payload returns the payload without padding
+    pub fn payload(&self) -> &'a [u8] {
+        &self.buf[self.payload_offset..self.payload_end]
+    }
+
+    /// as_bytes returns the whole packet
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.buf
+    }
+
+    /// codec looks up the codec of this packet's payload type in the media
+    /// section it was negotiated in
+    pub fn codec(&self, media: &MediaDescription) -> Option<Codec> {
+        media.codec_for_payload_type(self.payload_type()).ok()
+    }
+}
+
+/// RtpPacketBuilder serializes an RTP packet into a caller-provided buffer
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RtpPacketBuilder<'a> {
+    pub marker: bool,
+    pub payload_type: u8,
+    pub sequence_number: u16,
+    pub timestamp: u32,
+    pub ssrc: u32,
+    pub csrcs: Vec<u32>,
+    /// profile and data of the header extension, the data length must be a
+    /// multiple of 4
+    pub extension: Option<(u16, &'a [u8])>,
+    pub payload: &'a [u8],
+    /// number of padding bytes to append, including the count byte
+    pub padding: u8,
+}
+
+impl<'a> RtpPacketBuilder<'a> {
+    pub fn new(payload_type: u8, sequence_number: u16, timestamp: u32, ssrc: u32) -> Self {
+        RtpPacketBuilder {
+            payload_type,
+            sequence_number,
+            timestamp,
+            ssrc,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_marker(mut self, marker: bool) -> Self {
+        self.marker = marker;
+        self
+    }
+
+    pub fn with_csrc(mut self, csrc: u32) -> Self {
+        self.csrcs.push(csrc);
+        self
+    }
+
+    pub fn with_extension(mut self, profile: u16, data: &'a [u8]) -> Self {
+        self.extension = Some((profile, data));
+        self
+    }
+
+    pub fn with_payload(mut self, payload: &'a [u8]) -> Self {
+        self.payload = payload;
+        self
+    }
+
+    pub fn with_padding(mut self, padding: u8) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// marshal_size returns the size of the serialized packet
+    pub fn marshal_size(&self) -> usize {
+        let extension = match self.extension {
+            Some((_, data)) => EXTENSION_HEADER_LENGTH + data.len(),
+            None => 0,
+        };
+        HEADER_LENGTH
+            + self.csrcs.len() * 4
+            + extension
+            + self.payload.len()
+            + self.padding as usize
+    }
+
+    /// marshal_to writes the packet into `buf` and returns the number of
+    /// bytes written
+    pub fn marshal_to(&self, buf: &mut [u8]) -> Result<usize> {
+        if self.payload_type > PAYLOAD_TYPE_MASK {
+            return Err(Error::InvalidPayloadType(self.payload_type));
+        }
+        if self.csrcs.len() > MAX_CSRC_COUNT {
+            return Err(Error::TooManyCsrcs(self.csrcs.len()));
+        }
+        if let Some((_, data)) = self.extension {
+            if data.len() % 4 != 0 || data.len() / 4 > u16::MAX as usize {
+                return Err(Error::InvalidExtensionLength(data.len()));
+            }
+        }
+        let size = self.marshal_size();
+        if buf.len() < size {
+            return Err(Error::BufferTooSmall {
+                needed: size,
+                available: buf.len(),
+            });
+        }
+
+        let mut b0 = (VERSION << VERSION_SHIFT) | self.csrcs.len() as u8;
+        if self.padding > 0 {
+            b0 |= PADDING_BIT;
+        }
+        if self.extension.is_some() {
+            b0 |= EXTENSION_BIT;
+        }
+        buf[0] = b0;
+        buf[1] = self.payload_type;
+        if self.marker {
+            buf[1] |= MARKER_BIT;
+        }
+        buf[2..4].copy_from_slice(&self.sequence_number.to_be_bytes());
+        buf[4..8].copy_from_slice(&self.timestamp.to_be_bytes());
+        buf[8..12].copy_from_slice(&self.ssrc.to_be_bytes());
+
+        let mut offset = HEADER_LENGTH;
+        for csrc in &self.csrcs {
+            buf[offset..offset + 4].copy_from_slice(&csrc.to_be_bytes());
+            offset += 4;
+        }
+
+        if let Some((profile, data)) = self.extension {
+            buf[offset..offset + 2].copy_from_slice(&profile.to_be_bytes());
+            buf[offset + 2..offset + 4].copy_from_slice(&((data.len() / 4) as u16).to_be_bytes());
+            offset += EXTENSION_HEADER_LENGTH;
+            buf[offset..offset + data.len()].copy_from_slice(data);
+            offset += data.len();
+        }
+
+        buf[offset..offset + self.payload.len()].copy_from_slice(self.payload);
+        offset += self.payload.len();
+
+        if self.padding > 0 {
+            let end = offset + self.padding as usize;
+            buf[offset..end - 1].fill(0);
+            buf[end - 1] = self.padding;
+            offset = end;
+        }
+
+        Ok(offset)
+    }
+
+    /// marshal serializes the packet into a new buffer
+    pub fn marshal(&self) -> Result<Vec<u8>> {
+        let mut buf = vec![0u8; self.marshal_size()];
+        let n = self.marshal_to(&mut buf)?;
+        buf.truncate(n);
+        Ok(buf)
+    }
+}
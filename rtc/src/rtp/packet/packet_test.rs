@@ -0,0 +1,219 @@
+use super::*;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+// V=2 P X CC=1, M PT=96, seq 27023, ts 3653407706, ssrc 476325762, one
+// csrc, a one-byte extension and a padded payload
+const PACKET: [u8; 32] = [
+    0xb1, 0xe0, 0x69, 0x8f, 0xd9, 0xc2, 0x93, 0xda, 0x1c, 0x64, 0x27, 0x82, 0x00, 0x00, 0x11, 0x11,
+    0xbe, 0xde, 0x00, 0x01, 0x50, 0xaa, 0x00, 0x00, 0x98, 0x36, 0xbe, 0x88, 0x9e, 0x00, 0x00, 0x03,
+];
+
+#[test]
+fn test_parse() -> Result<()> {
+    let packet = RtpPacket::parse(&PACKET)?;
+    assert_eq!(packet.version(), 2);
+    assert!(packet.padding());
+    assert_eq!(packet.padding_len(), 3);
+    assert!(packet.extension());
+    assert_eq!(packet.csrc_count(), 1);
+    assert_eq!(packet.csrcs().collect::<Vec<u32>>(), vec![0x1111]);
+    assert!(packet.marker());
+    assert_eq!(packet.payload_type(), 96);
+    assert_eq!(packet.sequence_number(), 27023);
+    assert_eq!(packet.timestamp(), 3653407706);
+    assert_eq!(packet.ssrc(), 476325762);
+    assert_eq!(packet.extension_profile(), Some(0xBEDE));
+    assert_eq!(packet.extension_data(), Some(&[0x50, 0xaa, 0x00, 0x00][..]));
+    assert_eq!(packet.header_len(), 24);
+    assert_eq!(packet.payload(), &[0x98, 0x36, 0xbe, 0x88, 0x9e]);
+    assert_eq!(packet.as_bytes(), &PACKET);
+
+    let mut minimal = [0u8; HEADER_LENGTH];
+    minimal[0] = 0x80;
+    let packet = RtpPacket::parse(&minimal)?;
+    assert!(!packet.extension());
+    assert_eq!(packet.csrcs().count(), 0);
+    assert!(packet.payload().is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_errors() {
+    assert_eq!(
+        RtpPacket::parse(&PACKET[..11]),
+        Err(Error::HeaderTooShort {
+            needed: 12,
+            actual: 11
+        })
+    );
+    assert_eq!(
+        RtpPacket::parse(&PACKET[..14]),
+        Err(Error::HeaderTooShort {
+            needed: 16,
+            actual: 14
+        })
+    );
+    assert_eq!(
+        RtpPacket::parse(&PACKET[..18]),
+        Err(Error::HeaderTooShort {
+            needed: 20,
+            actual: 18
+        })
+    );
+    assert_eq!(
+        RtpPacket::parse(&PACKET[..22]),
+        Err(Error::HeaderTooShort {
+            needed: 24,
+            actual: 22
+        })
+    );
+
+    let mut version = PACKET;
+    version[0] = 0x71;
+    assert_eq!(RtpPacket::parse(&version), Err(Error::InvalidVersion(1)));
+
+    let mut padding = PACKET;
+    padding[31] = 9;
+    assert_eq!(RtpPacket::parse(&padding), Err(Error::InvalidPadding(9)));
+    padding[31] = 0;
+    assert_eq!(RtpPacket::parse(&padding), Err(Error::InvalidPadding(0)));
+    padding[31] = 8;
+    assert_eq!(RtpPacket::parse(&padding).map(|p| p.payload().len()), Ok(0));
+}
+
+#[test]
+fn test_builder_round_trip() -> Result<()> {
+    let builder = RtpPacketBuilder::new(96, 27023, 3653407706, 476325762)
+        .with_marker(true)
+        .with_csrc(0x1111)
+        .with_extension(0xBEDE, &[0x50, 0xaa, 0x00, 0x00])
+        .with_payload(&[0x98, 0x36, 0xbe, 0x88, 0x9e])
+        .with_padding(3);
+    assert_eq!(builder.marshal_size(), PACKET.len());
+    assert_eq!(builder.marshal()?, PACKET);
+
+    // trailing space in the caller buffer is left untouched
+    let mut buf = [0xffu8; 40];
+    assert_eq!(builder.marshal_to(&mut buf)?, PACKET.len());
+    assert_eq!(&buf[..PACKET.len()], &PACKET);
+    assert!(buf[PACKET.len()..].iter().all(|b| *b == 0xff));
+
+    let mut small = [0u8; 31];
+    assert_eq!(
+        builder.marshal_to(&mut small),
+        Err(Error::BufferTooSmall {
+            needed: 32,
+            available: 31
+        })
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_builder_errors() {
+    let mut buf = [0u8; 1500];
+    assert_eq!(
+        RtpPacketBuilder::new(128, 0, 0, 0).marshal_to(&mut buf),
+        Err(Error::InvalidPayloadType(128))
+    );
+    let builder = (0..16).fold(RtpPacketBuilder::new(0, 0, 0, 0), |b, i| b.with_csrc(i));
+    assert_eq!(builder.marshal_to(&mut buf), Err(Error::TooManyCsrcs(16)));
+    assert_eq!(
+        RtpPacketBuilder::new(0, 0, 0, 0)
+            .with_extension(0xBEDE, &[1, 2, 3])
+            .marshal_to(&mut buf),
+        Err(Error::InvalidExtensionLength(3))
+    );
+}
+
+#[test]
+fn test_codec() -> Result<()> {
+    let media = MediaDescription::new_jsep_media_description("video".to_owned(), vec![])
+        .with_codec(96, "VP8".to_owned(), 90000, 0, String::new());
+    let buf = RtpPacketBuilder::new(96, 0, 0, 0).marshal()?;
+    let packet = RtpPacket::parse(&buf)?;
+    assert_eq!(packet.codec(&media).map(|c| c.name), Some("VP8".to_owned()));
+
+    let buf = RtpPacketBuilder::new(0, 0, 0, 0).marshal()?;
+    let packet = RtpPacket::parse(&buf)?;
+    // payload types not negotiated in the media section have no codec
+    assert_eq!(packet.codec(&media), None);
+
+    Ok(())
+}
+
+#[test]
+fn test_fuzz_truncated() -> Result<()> {
+    // every strict prefix of a packet without padding is rejected or parses
+    // into a view that stays within the prefix
+    let packet = RtpPacketBuilder::new(111, 1, 2, 3)
+        .with_csrc(4)
+        .with_csrc(5)
+        .with_extension(0x1000, &[1, 2, 3, 4, 5, 6, 7, 8])
+        .with_payload(&[9; 20])
+        .marshal()?;
+    let header_len = RtpPacket::parse(&packet)?.header_len();
+    for n in 0..packet.len() {
+        match RtpPacket::parse(&packet[..n]) {
+            Ok(p) => {
+                assert!(n >= header_len, "{}", n);
+                assert_eq!(p.payload(), &packet[header_len..n]);
+            }
+            Err(_) => assert!(n < header_len, "{}", n),
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_fuzz_random() {
+    let mut rng = StdRng::seed_from_u64(0x5eed);
+    for _ in 0..10000 {
+        let len = rng.gen_range(0..64);
+        let mut buf: Vec<u8> = (0..len).map(|_| rng.gen()).collect();
+        if len > 0 && rng.gen_bool(0.75) {
+            // mostly valid versions to reach the deeper checks
+            buf[0] = (buf[0] & 0x3f) | 0x80;
+        }
+
+        let packet = match RtpPacket::parse(&buf) {
+            Ok(packet) => packet,
+            Err(_) => continue,
+        };
+        let _ = packet.csrcs().count();
+        let extension_len = packet
+            .extension_data()
+            .map(|d| EXTENSION_HEADER_LENGTH + d.len())
+            .unwrap_or(0);
+        assert_eq!(
+            packet.header_len(),
+            HEADER_LENGTH + packet.csrc_count() * 4 + extension_len
+        );
+        assert_eq!(
+            packet.header_len() + packet.payload().len() + packet.padding_len(),
+            buf.len()
+        );
+
+        if packet.padding_len() == 0 {
+            let rebuilt = RtpPacketBuilder {
+                marker: packet.marker(),
+                payload_type: packet.payload_type(),
+                sequence_number: packet.sequence_number(),
+                timestamp: packet.timestamp(),
+                ssrc: packet.ssrc(),
+                csrcs: packet.csrcs().collect(),
+                extension: packet.extension_profile().zip(packet.extension_data()),
+                payload: packet.payload(),
+                padding: 0,
+            }
+            .marshal()
+            .unwrap();
+            assert_eq!(rebuilt, buf);
+        }
+    }
+}
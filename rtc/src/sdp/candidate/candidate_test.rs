@@ -0,0 +1,136 @@
+use super::*;
+use crate::sdp::media::MediaDescription;
+
+use std::net::Ipv4Addr;
+
+#[test]
+fn test_ice_candidate_round_trip() -> Result<()> {
+    let passingtests = [
+        "1052353102 1 udp 2122260223 192.168.1.2 52011 typ host generation 0 network-id 1 network-cost 10",
+        "842163049 1 udp 1677729535 203.0.113.7 61665 typ srflx raddr 192.168.1.2 rport 52011 generation 0 ufrag AOV8 network-id 1",
+        "3171762302 1 tcp 1518280447 192.168.1.2 9 typ host tcptype active generation 0",
+        "1 1 UDP 2130706431 2001:db8::1 8998 typ host",
+        "2 2 UDP 16777215 198.51.100.1 3478 typ relay raddr 203.0.113.7 rport 61665",
+        "3 1 udp 1845501695 192.0.2.3 45664 typ prflx raddr 10.0.0.1 rport 8998",
+        "4 1 udp 2113937151 0f4b7c8a-2e6b-4c8f-a3c1-d3cfd2f1d0a0.local 54400 typ host generation 0",
+        "a+/b 1 udp 100 198.51.100.1 1000 typ host x-custom value",
+        "5 1 udp 2122260223 192.168.1.2 52011 typ host network-id 1 generation 0",
+        "6 1 udp 2122260223 192.168.1.2 52011 typ host x-custom value generation 0 ufrag AOV8",
+    ];
+
+    for (i, u) in passingtests.iter().enumerate() {
+        let candidate = IceCandidate::unmarshal(u)?;
+        assert_eq!(*u, candidate.to_string(), "{}: {}", i, u);
+        assert_eq!(
+            format!("candidate:{}", u),
+            candidate.marshal(),
+            "{}: {}",
+            i,
+            u
+        );
+
+        let prefixed = IceCandidate::unmarshal(&format!("a=candidate:{}\r\n", u))?;
+        assert_eq!(candidate, prefixed, "{}: {}", i, u);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_ice_candidate_fields() -> Result<()> {
+    let candidate: IceCandidate =
+        "candidate:842163049 1 udp 1677729535 203.0.113.7 61665 typ srflx raddr 192.168.1.2 rport 52011 tcptype so generation 3 ufrag AOV8 network-id 2 network-cost 50"
+            .parse()?;
+
+    assert_eq!(candidate.foundation, "842163049");
+    assert_eq!(candidate.component, 1);
+    assert_eq!(candidate.priority, 1677729535);
+    assert_eq!(
+        candidate.ip_addr(),
+        Some(IpAddr::V4(Ipv4Addr::new(203, 0, 113, 7)))
+    );
+    assert_eq!(candidate.port, 61665);
+    assert_eq!(candidate.typ, CandidateType::ServerReflexive);
+    assert_eq!(candidate.related_address.as_deref(), Some("192.168.1.2"));
+    assert_eq!(candidate.related_port, Some(52011));
+    assert_eq!(candidate.tcp_type(), Some(TcpType::SimultaneousOpen));
+    assert_eq!(candidate.generation(), Some(3));
+    assert_eq!(candidate.ufrag(), Some("AOV8"));
+    assert_eq!(candidate.network_id(), Some(2));
+    assert_eq!(candidate.network_cost(), Some(50));
+    assert_eq!(candidate.extension("ufrag"), Some("AOV8"));
+    assert_eq!(candidate.extension("x-custom"), None);
+    assert!(!candidate.is_mdns());
+    assert!(!candidate.is_tcp());
+
+    let mdns = IceCandidate::unmarshal("1 1 tcp 1 abc-def.local 9 typ host tcptype passive")?;
+    assert!(mdns.is_mdns());
+    assert!(mdns.is_tcp());
+    assert_eq!(mdns.ip_addr(), None);
+
+    let mut ordered =
+        IceCandidate::unmarshal("1 1 udp 1 192.168.1.2 9 typ host network-id 1 generation 0")?;
+    assert_eq!(ordered.network_id(), Some(1));
+    assert_eq!(ordered.generation(), Some(0));
+    ordered.set_extension("generation", "1".to_owned());
+    ordered.set_extension("network-cost", "10".to_owned());
+    assert_eq!(
+        ordered.to_string(),
+        "1 1 udp 1 192.168.1.2 9 typ host network-id 1 generation 1 network-cost 10"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_ice_candidate_failing() {
+    let failingtests = [
+        "",
+        "1 1 udp 2122260223 192.168.1.2 52011",
+        "1 1 udp 2122260223 192.168.1.2 52011 type host",
+        "1 1 udp 2122260223 192.168.1.2 52011 typ local",
+        "1 0 udp 2122260223 192.168.1.2 52011 typ host",
+        "1 257 udp 2122260223 192.168.1.2 52011 typ host",
+        "1 1 udp 4294967296 192.168.1.2 52011 typ host",
+        "1 1 udp 2122260223 192.168.1.2 65536 typ host",
+        "1 1 udp 2122260223 not_a_host 52011 typ host",
+        "1 1 udp 2122260223 -bad.local 52011 typ host",
+        "1 1 ud;p 2122260223 192.168.1.2 52011 typ host",
+        "f*o 1 udp 2122260223 192.168.1.2 52011 typ host",
+        "123456789012345678901234567890123 1 udp 1 192.168.1.2 1 typ host",
+        "1 1 udp 2122260223 192.168.1.2 52011 typ srflx raddr",
+        "1 1 udp 2122260223 192.168.1.2 52011 typ srflx rport x",
+        "1 1 tcp 2122260223 192.168.1.2 9 typ host tcptype both",
+        "1 1 udp 2122260223 192.168.1.2 52011 typ host generation x",
+    ];
+
+    for u in failingtests {
+        assert!(IceCandidate::unmarshal(u).is_err(), "{}", u);
+    }
+}
+
+#[test]
+fn test_media_description_candidates() -> Result<()> {
+    let host = IceCandidate::unmarshal("1 1 udp 2122260223 192.168.1.2 52011 typ host")?;
+    let media = MediaDescription::default()
+        .with_ice_candidate(&host)
+        .with_candidate(
+            "2 1 udp 1686052607 203.0.113.7 61665 typ srflx raddr 192.168.1.2 rport 52011"
+                .to_owned(),
+        );
+
+    assert!(!media.is_end_of_candidates());
+    let candidates = media.candidates()?;
+    assert_eq!(candidates.len(), 2);
+    assert_eq!(candidates[0], host);
+    assert_eq!(candidates[1].typ, CandidateType::ServerReflexive);
+
+    let media = media.with_end_of_candidates();
+    assert!(media.is_end_of_candidates());
+    assert_eq!(media.attributes[2].to_string(), ATTR_KEY_END_OF_CANDIDATES);
+
+    let media = media.with_candidate("garbage".to_owned());
+    assert!(media.candidates().is_err());
+
+    Ok(())
+}
@@ -0,0 +1,358 @@
+#[cfg(test)]
+mod candidate_test;
+
+use super::common::Attribute;
+use super::error::{Error, Result};
+
+use std::fmt;
+use std::net::IpAddr;
+use std::str::FromStr;
+
+pub const ATTR_KEY_CANDIDATE: &str = "candidate";
+pub const ATTR_KEY_END_OF_CANDIDATES: &str = "end-of-candidates";
+
+const MDNS_SUFFIX: &str = ".local";
+
+/// extension attributes with a typed accessor on IceCandidate
+const EXT_TCP_TYPE: &str = "tcptype";
+const EXT_GENERATION: &str = "generation";
+const EXT_UFRAG: &str = "ufrag";
+const EXT_NETWORK_ID: &str = "network-id";
+const EXT_NETWORK_COST: &str = "network-cost";
+
+/// CandidateType is the "typ" of an ICE candidate
+/// <https://tools.ietf.org/html/rfc8445#section-5.1.1>
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CandidateType {
+    #[default]
+    Host,
+    ServerReflexive,
+    PeerReflexive,
+    Relay,
+}
+
+const CANDIDATE_TYPE_HOST_STR: &str = "host";
+const CANDIDATE_TYPE_SRFLX_STR: &str = "srflx";
+const CANDIDATE_TYPE_PRFLX_STR: &str = "prflx";
+const CANDIDATE_TYPE_RELAY_STR: &str = "relay";
+
+impl fmt::Display for CandidateType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            CandidateType::Host => CANDIDATE_TYPE_HOST_STR,
+            CandidateType::ServerReflexive => CANDIDATE_TYPE_SRFLX_STR,
+            CandidateType::PeerReflexive => CANDIDATE_TYPE_PRFLX_STR,
+            CandidateType::Relay => CANDIDATE_TYPE_RELAY_STR,
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl FromStr for CandidateType {
+    type Err = Error;
+
+    fn from_str(raw: &str) -> Result<Self> {
+        match raw {
+            CANDIDATE_TYPE_HOST_STR => Ok(CandidateType::Host),
+            CANDIDATE_TYPE_SRFLX_STR => Ok(CandidateType::ServerReflexive),
+            CANDIDATE_TYPE_PRFLX_STR => Ok(CandidateType::PeerReflexive),
+            CANDIDATE_TYPE_RELAY_STR => Ok(CandidateType::Relay),
+            _ => Err(Error::ParseIceCandidate(format!("unknown typ {}", raw))),
+        }
+    }
+}
+
+/// TcpType is the "tcptype" extension of TCP candidates
+/// <https://tools.ietf.org/html/rfc6544#section-4.5>
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TcpType {
+    Active,
+    Passive,
+    SimultaneousOpen,
+}
+
+const TCP_TYPE_ACTIVE_STR: &str = "active";
+const TCP_TYPE_PASSIVE_STR: &str = "passive";
+const TCP_TYPE_SO_STR: &str = "so";
+
+impl fmt::Display for TcpType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            TcpType::Active => TCP_TYPE_ACTIVE_STR,
+            TcpType::Passive => TCP_TYPE_PASSIVE_STR,
+            TcpType::SimultaneousOpen => TCP_TYPE_SO_STR,
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl FromStr for TcpType {
+    type Err = Error;
+
+    fn from_str(raw: &str) -> Result<Self> {
+        match raw {
+            TCP_TYPE_ACTIVE_STR => Ok(TcpType::Active),
+            TCP_TYPE_PASSIVE_STR => Ok(TcpType::Passive),
+            TCP_TYPE_SO_STR => Ok(TcpType::SimultaneousOpen),
+            _ => Err(Error::ParseIceCandidate(format!("unknown tcptype {}", raw))),
+        }
+    }
+}
+
+/// IceCandidate is the structured form of an "a=candidate" attribute
+///
+/// `candidate:<foundation> <component-id> <transport> <priority>
+/// <connection-address> <port> typ <cand-type> [raddr <rel-addr>]
+/// [rport <rel-port>] *(<extension-att-name> <extension-att-value>)`
+///
+/// <https://tools.ietf.org/html/rfc8839#section-5.1>
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IceCandidate {
+    pub foundation: String,
+    pub component: u16,
+    /// transport as received, e.g. "udp" or "TCP"
+    pub transport: String,
+    pub priority: u32,
+    /// IP address or FQDN, mDNS candidates use a "<uuid>.local" name
+    pub address: String,
+    pub port: u16,
+    pub typ: CandidateType,
+    pub related_address: Option<String>,
+    pub related_port: Option<u16>,
+    /// extension attributes in received order, the known ones are read
+    /// through typed accessors such as `generation()`
+    pub extensions: Vec<(String, String)>,
+}
+
+/// Renders the value of the "a=candidate" attribute.
+impl fmt::Display for IceCandidate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} {} {} typ {}",
+            self.foundation,
+            self.component,
+            self.transport,
+            self.priority,
+            self.address,
+            self.port,
+            self.typ
+        )?;
+        if let Some(related_address) = &self.related_address {
+            write!(f, " raddr {}", related_address)?;
+        }
+        if let Some(related_port) = self.related_port {
+            write!(f, " rport {}", related_port)?;
+        }
+        for (name, value) in &self.extensions {
+            write!(f, " {} {}", name, value)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for IceCandidate {
+    type Err = Error;
+
+    fn from_str(raw: &str) -> Result<Self> {
+        IceCandidate::unmarshal(raw)
+    }
+}
+
+impl IceCandidate {
+    /// unmarshal parses a candidate, with or without the "a=candidate:" or
+    /// "candidate:" prefix
+    pub fn unmarshal(raw: &str) -> Result<Self> {
+        let value = raw.trim();
+        let value = value.strip_prefix("a=").unwrap_or(value);
+        let value = value.strip_prefix("candidate:").unwrap_or(value);
+
+        let fields: Vec<&str> = value.split_whitespace().collect();
+        if fields.len() < 8 || fields[6] != "typ" {
+            return Err(Error::ParseIceCandidate(raw.to_owned()));
+        }
+
+        let foundation = fields[0];
+        if foundation.is_empty() || foundation.len() > 32 || !foundation.bytes().all(is_ice_char) {
+            return Err(Error::ParseIceCandidate(format!(
+                "invalid foundation {}",
+                foundation
+            )));
+        }
+
+        let component = fields[1].parse::<u16>()?;
+        if !(1..=256).contains(&component) {
+            return Err(Error::ParseIceCandidate(format!(
+                "{} -- component-id must be in the range 1-256",
+                component
+            )));
+        }
+
+        let transport = fields[2];
+        if transport.is_empty()
+            || !transport
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'-')
+        {
+            return Err(Error::ParseIceCandidate(format!(
+                "invalid transport {}",
+                transport
+            )));
+        }
+
+        let priority = fields[3].parse::<u32>()?;
+        let address = parse_connection_address(fields[4])?;
+        let port = fields[5].parse::<u16>()?;
+        let typ = fields[7].parse::<CandidateType>()?;
+
+        let mut candidate = IceCandidate {
+            foundation: foundation.to_owned(),
+            component,
+            transport: transport.to_owned(),
+            priority,
+            address,
+            port,
+            typ,
+            ..Default::default()
+        };
+
+        let rest = &fields[8..];
+        if !rest.len().is_multiple_of(2) {
+            return Err(Error::ParseIceCandidate(format!(
+                "extension {} without value",
+                rest[rest.len() - 1]
+            )));
+        }
+        for pair in rest.chunks(2) {
+            let (name, value) = (pair[0], pair[1]);
+            match name {
+                "raddr" => {
+                    candidate.related_address = Some(parse_connection_address(value)?);
+                    continue;
+                }
+                "rport" => {
+                    candidate.related_port = Some(value.parse::<u16>()?);
+                    continue;
+                }
+                EXT_TCP_TYPE => {
+                    value.parse::<TcpType>()?;
+                }
+                EXT_GENERATION | EXT_NETWORK_ID | EXT_NETWORK_COST => {
+                    value.parse::<u32>()?;
+                }
+                _ => {}
+            }
+            candidate
+                .extensions
+                .push((name.to_owned(), value.to_owned()));
+        }
+
+        Ok(candidate)
+    }
+
+    /// extension returns the value of the first extension attribute with
+    /// the given name
+    pub fn extension(&self, name: &str) -> Option<&str> {
+        self.extensions
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// set_extension replaces the value of an extension attribute in place,
+    /// or appends it if there is none
+    pub fn set_extension(&mut self, name: &str, value: String) {
+        match self.extensions.iter_mut().find(|(n, _)| n == name) {
+            Some((_, v)) => *v = value,
+            None => self.extensions.push((name.to_owned(), value)),
+        }
+    }
+
+    /// tcp_type returns the "tcptype" extension (RFC 6544)
+    pub fn tcp_type(&self) -> Option<TcpType> {
+        self.extension(EXT_TCP_TYPE)?.parse().ok()
+    }
+
+    pub fn generation(&self) -> Option<u32> {
+        self.extension(EXT_GENERATION)?.parse().ok()
+    }
+
+    pub fn ufrag(&self) -> Option<&str> {
+        self.extension(EXT_UFRAG)
+    }
+
+    pub fn network_id(&self) -> Option<u32> {
+        self.extension(EXT_NETWORK_ID)?.parse().ok()
+    }
+
+    pub fn network_cost(&self) -> Option<u32> {
+        self.extension(EXT_NETWORK_COST)?.parse().ok()
+    }
+
+    /// marshal renders the candidate as "candidate:..." attribute line value
+    pub fn marshal(&self) -> String {
+        format!("{}:{}", ATTR_KEY_CANDIDATE, self)
+    }
+
+    /// converts this object to an Attribute
+    pub fn convert(&self) -> Attribute {
+        Attribute {
+            key: ATTR_KEY_CANDIDATE.to_owned(),
+            value: Some(self.to_string()),
+        }
+    }
+
+    /// ip_addr returns the connection address if it is an IP literal
+    pub fn ip_addr(&self) -> Option<IpAddr> {
+        self.address.parse::<IpAddr>().ok()
+    }
+
+    /// is_mdns returns true if the address is an mDNS ".local" name hiding the
+    /// host address <https://tools.ietf.org/html/draft-ietf-mmusic-mdns-ice-candidates>
+    pub fn is_mdns(&self) -> bool {
+        is_mdns_name(&self.address)
+    }
+
+    /// is_tcp returns true for TCP candidates (RFC 6544)
+    pub fn is_tcp(&self) -> bool {
+        self.transport.eq_ignore_ascii_case("tcp")
+    }
+}
+
+/// ice-char = ALPHA / DIGIT / "+" / "/"
+fn is_ice_char(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'+' || b == b'/'
+}
+
+fn is_mdns_name(address: &str) -> bool {
+    address.len() > MDNS_SUFFIX.len()
+        && address[address.len() - MDNS_SUFFIX.len()..].eq_ignore_ascii_case(MDNS_SUFFIX)
+}
+
+/// parse_connection_address accepts IPv4 and IPv6 literals and FQDNs
+fn parse_connection_address(address: &str) -> Result<String> {
+    if address.parse::<IpAddr>().is_ok() {
+        return Ok(address.to_owned());
+    }
+
+    let is_fqdn = address.len() <= 253
+        && address.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && label
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || b == b'-')
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+        });
+    if !is_fqdn {
+        return Err(Error::ParseIceCandidate(format!(
+            "invalid connection-address {}",
+            address
+        )));
+    }
+    Ok(address.to_owned())
+}
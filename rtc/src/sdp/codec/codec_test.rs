@@ -0,0 +1,300 @@
+use super::*;
+use crate::sdp::media::MediaDescription;
+
+#[test]
+fn test_parse_rtpmap() -> Result<()> {
+    let tests = [
+        ("111 opus/48000/2", Codec::new(111, "opus", 48000, 2)),
+        ("96 VP8/90000", Codec::new(96, "VP8", 90000, 0)),
+        ("0 PCMU/8000", Codec::new(0, "PCMU", 8000, 0)),
+    ];
+
+    for (i, u) in tests.iter().enumerate() {
+        let codec = parse_rtpmap(u.0)?;
+        assert_eq!(u.1, codec, "{}: {}", i, u.0);
+        assert_eq!(u.0, codec.to_string(), "{}: {}", i, u.0);
+    }
+
+    let failingtests = [
+        "111",
+        "111 opus",
+        "abc opus/48000",
+        "111 opus/abc",
+        "111 opus/48000/2/1",
+    ];
+    for u in failingtests {
+        assert!(parse_rtpmap(u).is_err(), "{}", u);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_fmtp_and_rtcp_fb() -> Result<()> {
+    assert_eq!(
+        parse_fmtp("111 minptime=10;useinbandfec=1")?,
+        (111, "minptime=10;useinbandfec=1".parse()?)
+    );
+    assert_eq!(parse_fmtp("63 111/111")?, (63, "111/111".parse()?));
+    assert_eq!(parse_fmtp("63"), Err(Error::MissingWhitespace));
+
+    assert_eq!(
+        parse_rtcp_fb("96 nack pli")?,
+        (Some(96), "nack pli".to_owned())
+    );
+    assert_eq!(
+        parse_rtcp_fb("* transport-cc")?,
+        (None, "transport-cc".to_owned())
+    );
+    assert!(parse_rtcp_fb("x nack").is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_fmtp_params() -> Result<()> {
+    let codec = Codec {
+        fmtp: "level-asymmetry-allowed=1;packetization-mode=1; profile-level-id=42001f".parse()?,
+        ..Codec::new(102, "H264", 90000, 0)
+    };
+    assert_eq!(codec.fmtp.len(), 3);
+    assert_eq!(codec.fmtp.get("packetization-mode"), Some("1"));
+    assert_eq!(codec.fmtp_param("profile-level-id"), Some("42001f"));
+    assert_eq!(codec.fmtp_param("apt"), None);
+    assert_eq!(
+        codec.fmtp_value(),
+        Some(
+            "102 level-asymmetry-allowed=1;packetization-mode=1;profile-level-id=42001f".to_owned()
+        )
+    );
+
+    // parameters keep their order when replaced or added
+    let mut params = codec.fmtp;
+    params.set("packetization-mode", "0");
+    params.set("max-fr", "30");
+    assert_eq!(
+        params.remove("level-asymmetry-allowed"),
+        Some("1".to_owned())
+    );
+    assert_eq!(
+        params.to_string(),
+        "packetization-mode=0;profile-level-id=42001f;max-fr=30"
+    );
+
+    let red = Codec {
+        fmtp: "111/111".parse()?,
+        ..Codec::new(63, "red", 48000, 2)
+    };
+    assert_eq!(red.fmtp_param("111/111"), Some(""));
+    assert_eq!(red.fmtp.iter().collect::<Vec<_>>(), vec![("111/111", None)]);
+    assert_eq!(red.fmtp.to_string(), "111/111");
+
+    assert!("a=1;=2".parse::<FmtpParams>().is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_is_compatible() -> Result<()> {
+    let h264 = |fmtp: &str| -> Result<Codec> {
+        Ok(Codec {
+            fmtp: fmtp.parse()?,
+            ..Codec::new(102, "H264", 90000, 0)
+        })
+    };
+
+    // constrained baseline, whichever constraint flags signal it
+    let cb = h264("packetization-mode=1;profile-level-id=42e01f")?;
+    assert!(cb.is_compatible(&h264("packetization-mode=1;profile-level-id=42c01f")?));
+    assert!(cb.is_compatible(&h264("packetization-mode=1;profile-level-id=4d801f")?));
+    // the level may differ
+    assert!(cb.is_compatible(&h264("packetization-mode=1;profile-level-id=42E034")?));
+    // constrained baseline is the default profile
+    assert!(cb.is_compatible(&h264("packetization-mode=1")?));
+
+    assert!(!cb.is_compatible(&h264("packetization-mode=0;profile-level-id=42e01f")?));
+    assert!(!cb.is_compatible(&h264("packetization-mode=1;profile-level-id=42001f")?));
+    assert!(!cb.is_compatible(&h264("packetization-mode=1;profile-level-id=4d001f")?));
+    assert!(!cb.is_compatible(&h264("packetization-mode=1;profile-level-id=640c1f")?));
+    assert!(!cb.is_compatible(&h264("packetization-mode=1;profile-level-id=42e0")?));
+
+    let high = h264("profile-level-id=640032")?;
+    assert!(high.is_compatible(&h264("profile-level-id=64001f")?));
+    assert!(!high.is_compatible(&h264("profile-level-id=640c32")?));
+    // unknown profiles never match
+    let unknown = h264("profile-level-id=ff001f")?;
+    assert!(!unknown.is_compatible(&unknown));
+
+    let vp9 = |fmtp: &str| -> Result<Codec> {
+        Ok(Codec {
+            fmtp: fmtp.parse()?,
+            ..Codec::new(98, "VP9", 90000, 0)
+        })
+    };
+    assert!(vp9("profile-id=0")?.is_compatible(&vp9("")?));
+    assert!(!vp9("profile-id=0")?.is_compatible(&vp9("profile-id=2")?));
+    assert!(!vp9("")?.is_compatible(&Codec::new(98, "VP8", 90000, 0)));
+
+    Ok(())
+}
+
+#[test]
+fn test_codec_for_payload_type() {
+    let md = MediaDescription::default()
+        .with_codec(111, "opus".to_owned(), 48000, 2, "minptime=10".to_owned())
+        .with_value_attribute("rtcp-fb".to_owned(), "111 transport-cc".to_owned())
+        .with_value_attribute("rtcp-fb".to_owned(), "* nack".to_owned())
+        .with_value_attribute("rtcp-fb".to_owned(), "9 nack pli".to_owned())
+        .with_codec(9, "G722".to_owned(), 8000, 0, "".to_owned());
+    let md = MediaDescription {
+        media_name: crate::sdp::media::MediaName {
+            formats: vec![
+                "111".to_owned(),
+                "9".to_owned(),
+                "8".to_owned(),
+                "100".to_owned(),
+            ],
+            ..md.media_name
+        },
+        ..md
+    };
+
+    let opus = md.codec_for_payload_type(111).unwrap();
+    assert_eq!(opus.name, "opus");
+    assert_eq!(opus.channels, 2);
+    assert_eq!(opus.fmtp.to_string(), "minptime=10");
+    assert_eq!(opus.rtcp_feedback, vec!["transport-cc", "nack"]);
+
+    let g722 = md.codec_for_payload_type(9).unwrap();
+    assert_eq!(g722.rtcp_feedback, vec!["nack", "nack pli"]);
+
+    // static payload types resolve without an rtpmap, dynamic ones do not
+    let pcma = md.codec_for_payload_type(8).unwrap();
+    assert_eq!(pcma.to_string(), "8 PCMA/8000");
+    assert_eq!(pcma.rtcp_feedback, vec!["nack"]);
+    assert_eq!(md.codec_for_payload_type(100), Err(Error::CodecNotFound));
+    assert_eq!(
+        md.codec_for_payload_type(0),
+        Err(Error::PayloadTypeNotFound)
+    );
+
+    let codecs = md.codecs();
+    assert_eq!(codecs.len(), 3);
+    assert_eq!(codecs[0].payload_type, 111);
+    assert_eq!(codecs[1].payload_type, 9);
+    assert_eq!(codecs[2].payload_type, 8);
+
+    assert_eq!(
+        md.payload_type_for_codec(&Codec::new(0, "OPUS", 48000, 2)),
+        Ok(111)
+    );
+    assert_eq!(
+        md.payload_type_for_codec(&Codec::new(0, "opus", 48000, 1)),
+        Err(Error::CodecNotFound)
+    );
+}
+
+#[test]
+fn test_codec_mutation() -> Result<()> {
+    let mut md = MediaDescription::default();
+
+    let vp8 = Codec {
+        rtcp_feedback: vec!["nack".to_owned()],
+        ..Codec::new(96, "VP8", 90000, 0)
+    };
+    let rtx = Codec {
+        fmtp: "apt=96".parse()?,
+        ..Codec::new(97, "rtx", 90000, 0)
+    };
+    md.add_codec(&vp8);
+    md.add_codec(&rtx);
+    assert_eq!(md.media_name.formats, vec!["96", "97"]);
+    assert_eq!(md.codecs(), vec![vp8.clone(), rtx.clone()]);
+
+    md.add_codec_feedback(96, "nack pli")?;
+    md.add_codec_feedback(96, "nack")?;
+    md.set_codec_fmtp(96, &"max-fr=30".parse()?)?;
+    let attrs: Vec<String> = md.attributes.iter().map(|a| a.to_string()).collect();
+    assert_eq!(
+        attrs,
+        vec![
+            "rtpmap:96 VP8/90000",
+            "rtcp-fb:96 nack",
+            "rtcp-fb:96 nack pli",
+            "fmtp:96 max-fr=30",
+            "rtpmap:97 rtx/90000",
+            "fmtp:97 apt=96",
+        ]
+    );
+
+    md.remove_codec_feedback(96, "nack")?;
+    md.set_codec_fmtp(96, &FmtpParams::default())?;
+    assert_eq!(
+        md.codec_for_payload_type(96)?.rtcp_feedback,
+        vec!["nack pli".to_owned()]
+    );
+    assert!(md.codec_for_payload_type(96)?.fmtp.is_empty());
+
+    let removed = md.remove_codec(97)?;
+    assert_eq!(removed, rtx);
+    assert_eq!(md.media_name.formats, vec!["96"]);
+    assert_eq!(md.attributes.len(), 2);
+    assert_eq!(md.remove_codec(97), Err(Error::PayloadTypeNotFound));
+
+    // replacing a codec keeps its position in the preference order
+    md.add_codec(&rtx);
+    md.add_codec(&Codec::new(96, "VP8", 90000, 0));
+    assert_eq!(md.media_name.formats, vec!["96", "97"]);
+    let attrs: Vec<String> = md.attributes.iter().map(|a| a.to_string()).collect();
+    assert_eq!(
+        attrs,
+        vec![
+            "rtpmap:96 VP8/90000",
+            "rtpmap:97 rtx/90000",
+            "fmtp:97 apt=96"
+        ]
+    );
+    assert_eq!(
+        md.set_codec_fmtp(100, &"a=b".parse()?),
+        Err(Error::PayloadTypeNotFound)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_static_payload_type() -> Result<()> {
+    let tests = [
+        (0, "0 PCMU/8000"),
+        (3, "3 GSM/8000"),
+        (9, "9 G722/8000"),
+        (10, "10 L16/44100/2"),
+        (18, "18 G729/8000"),
+        (34, "34 H263/90000"),
+    ];
+    for (payload_type, rtpmap) in tests {
+        let codec = Codec::static_payload_type(payload_type);
+        assert_eq!(codec, Some(parse_rtpmap(rtpmap)?), "{}", rtpmap);
+    }
+
+    for payload_type in [1, 2, 19, 35, 96, 127] {
+        assert_eq!(Codec::static_payload_type(payload_type), None);
+    }
+
+    // a legacy SIP offer listing static payload types only
+    let md = MediaDescription {
+        media_name: crate::sdp::media::MediaName {
+            formats: vec!["0".to_owned(), "8".to_owned(), "18".to_owned()],
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let names: Vec<String> = md.codecs().into_iter().map(|c| c.name).collect();
+    assert_eq!(names, vec!["PCMU", "PCMA", "G729"]);
+    assert_eq!(
+        md.payload_type_for_codec(&Codec::new(0, "pcma", 8000, 0)),
+        Ok(8)
+    );
+
+    Ok(())
+}
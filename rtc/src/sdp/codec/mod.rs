@@ -0,0 +1,359 @@
+#[cfg(test)]
+mod codec_test;
+
+use super::error::{Error, Result};
+
+use std::fmt;
+use std::str::FromStr;
+
+pub const ATTR_KEY_RTPMAP: &str = "rtpmap";
+pub const ATTR_KEY_FMTP: &str = "fmtp";
+pub const ATTR_KEY_RTCP_FB: &str = "rtcp-fb";
+
+/// STATIC_PAYLOAD_TYPES lists the payload types with a fixed meaning that
+/// may be used without an "a=rtpmap" line, as payload type, encoding name,
+/// clock rate and channels. Mono encodings carry 0 channels, as an rtpmap
+/// without encoding parameters would.
+///
+/// <https://tools.ietf.org/html/rfc3551#section-6>
+pub const STATIC_PAYLOAD_TYPES: &[(u8, &str, u32, u16)] = &[
+    (0, "PCMU", 8000, 0),
+    (3, "GSM", 8000, 0),
+    (4, "G723", 8000, 0),
+    (5, "DVI4", 8000, 0),
+    (6, "DVI4", 16000, 0),
+    (7, "LPC", 8000, 0),
+    (8, "PCMA", 8000, 0),
+    (9, "G722", 8000, 0),
+    (10, "L16", 44100, 2),
+    (11, "L16", 44100, 0),
+    (12, "QCELP", 8000, 0),
+    (13, "CN", 8000, 0),
+    (14, "MPA", 90000, 0),
+    (15, "G728", 8000, 0),
+    (16, "DVI4", 11025, 0),
+    (17, "DVI4", 22050, 0),
+    (18, "G729", 8000, 0),
+    (25, "CelB", 90000, 0),
+    (26, "JPEG", 90000, 0),
+    (28, "nv", 90000, 0),
+    (31, "H261", 90000, 0),
+    (32, "MPV", 90000, 0),
+    (33, "MP2T", 90000, 0),
+    (34, "H263", 90000, 0),
+];
+
+/// Codec describes a single payload type of a media description, assembled
+/// from its "a=rtpmap", "a=fmtp" and "a=rtcp-fb" attributes.
+///
+/// `a=rtpmap:<payload type> <encoding name>/<clock rate>[/<encoding parameters>]`
+///
+/// <https://tools.ietf.org/html/rfc4566#section-6>
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Codec {
+    pub payload_type: u8,
+    pub name: String,
+    pub clock_rate: u32,
+    /// number of audio channels, 0 when the rtpmap carries no encoding parameters
+    pub channels: u16,
+    /// parameters of the "a=fmtp" line
+    pub fmtp: FmtpParams,
+    /// feedback mechanisms of the "a=rtcp-fb" lines, e.g. "nack pli"
+    pub rtcp_feedback: Vec<String>,
+}
+
+/// Renders the value of the "a=rtpmap" attribute.
+impl fmt::Display for Codec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}/{}", self.payload_type, self.name, self.clock_rate)?;
+        if self.channels > 0 {
+            write!(f, "/{}", self.channels)?;
+        }
+        Ok(())
+    }
+}
+
+impl Codec {
+    /// new constructs a codec without fmtp parameters and feedback mechanisms
+    pub fn new(payload_type: u8, name: &str, clock_rate: u32, channels: u16) -> Self {
+        Codec {
+            payload_type,
+            name: name.to_owned(),
+            clock_rate,
+            channels,
+            ..Default::default()
+        }
+    }
+
+    /// static_payload_type returns the codec a static payload type stands
+    /// for, None for unassigned and dynamic payload types
+    pub fn static_payload_type(payload_type: u8) -> Option<Codec> {
+        STATIC_PAYLOAD_TYPES
+            .iter()
+            .find(|(pt, _, _, _)| *pt == payload_type)
+            .map(|(pt, name, clock_rate, channels)| Codec::new(*pt, name, *clock_rate, *channels))
+    }
+
+    /// fmtp_param returns the value of a single fmtp parameter
+    pub fn fmtp_param(&self, key: &str) -> Option<&str> {
+        self.fmtp.get(key)
+    }
+
+    /// is_rtx returns true for retransmission payload types (RFC 4588)
+    pub fn is_rtx(&self) -> bool {
+        self.name.eq_ignore_ascii_case("rtx")
+    }
+
+    /// matches compares encoding name, clock rate and channel count the way
+    /// payload types are compared during offer/answer, the encoding name is
+    /// case-insensitive.
+    pub fn matches(&self, other: &Codec) -> bool {
+        self.name.eq_ignore_ascii_case(&other.name)
+            && self.clock_rate == other.clock_rate
+            && self.channels == other.channels
+    }
+
+    /// is_compatible reports whether two codecs can be negotiated with each
+    /// other. On top of `matches` the format parameters that select a
+    /// different bitstream have to agree: packetization-mode and the
+    /// profile of profile-level-id for H264 and profile-id for VP9.
+    pub fn is_compatible(&self, other: &Codec) -> bool {
+        if !self.matches(other) {
+            return false;
+        }
+
+        let (a, b) = (&self.fmtp, &other.fmtp);
+        if self.name.eq_ignore_ascii_case("h264") {
+            let packetization_mode =
+                |p: &FmtpParams| p.get("packetization-mode").unwrap_or("0").to_owned();
+            // the profile decoded from profile_idc and profile_iop, the
+            // level may differ
+            let profile = |p: &FmtpParams| match p.get("profile-level-id") {
+                Some(id) => H264Profile::parse(id),
+                None => Some(H264Profile::ConstrainedBaseline),
+            };
+            return packetization_mode(a) == packetization_mode(b)
+                && profile(a).is_some()
+                && profile(a) == profile(b);
+        }
+        if self.name.eq_ignore_ascii_case("vp9") {
+            let profile_id = |p: &FmtpParams| p.get("profile-id").unwrap_or("0").to_owned();
+            return profile_id(a) == profile_id(b);
+        }
+        true
+    }
+
+    /// fmtp_value renders the value of the "a=fmtp" attribute
+    pub fn fmtp_value(&self) -> Option<String> {
+        if self.fmtp.is_empty() {
+            None
+        } else {
+            Some(format!("{} {}", self.payload_type, self.fmtp))
+        }
+    }
+
+    /// rtcp_feedback_values renders the values of the "a=rtcp-fb" attributes
+    pub fn rtcp_feedback_values(&self) -> Vec<String> {
+        self.rtcp_feedback
+            .iter()
+            .map(|fb| format!("{} {}", self.payload_type, fb))
+            .collect()
+    }
+}
+
+/// H264Profile is the profile signalled by the first two bytes of an H264
+/// profile-level-id, profile_idc and profile_iop. Several encodings denote
+/// the same profile since only some of the constraint flags matter.
+///
+/// <https://tools.ietf.org/html/rfc6184#section-8.1>
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum H264Profile {
+    ConstrainedBaseline,
+    Baseline,
+    Main,
+    ConstrainedHigh,
+    High,
+    PredictiveHigh444,
+}
+
+impl H264Profile {
+    /// profile_idc, then mask and value of the profile_iop bits that select
+    /// the profile, following Table 5 of RFC 6184 and libwebrtc
+    const PATTERNS: [(u8, u8, u8, Self); 9] = [
+        (0x42, 0b0100_1111, 0b0100_0000, Self::ConstrainedBaseline),
+        (0x4d, 0b1000_1111, 0b1000_0000, Self::ConstrainedBaseline),
+        (0x58, 0b1100_1111, 0b1100_0000, Self::ConstrainedBaseline),
+        (0x42, 0b0100_1111, 0b0000_0000, Self::Baseline),
+        (0x58, 0b1100_1111, 0b1000_0000, Self::Baseline),
+        (0x4d, 0b1010_1111, 0b0000_0000, Self::Main),
+        (0x64, 0b1111_1111, 0b0000_0000, Self::High),
+        (0x64, 0b1111_1111, 0b0000_1100, Self::ConstrainedHigh),
+        (0xf4, 0b1111_1111, 0b0000_0000, Self::PredictiveHigh444),
+    ];
+
+    /// parse decodes the profile of a profile-level-id of 6 hex digits,
+    /// None if it is malformed or names an unknown profile
+    fn parse(profile_level_id: &str) -> Option<Self> {
+        if profile_level_id.len() != 6 || !profile_level_id.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+        let id = u32::from_str_radix(profile_level_id, 16).ok()?;
+        let (profile_idc, profile_iop) = ((id >> 16) as u8, (id >> 8) as u8);
+        Self::PATTERNS
+            .iter()
+            .find(|(idc, mask, value, _)| *idc == profile_idc && profile_iop & mask == *value)
+            .map(|(_, _, _, profile)| *profile)
+    }
+}
+
+/// FmtpParams maps the `k1=v1;k2=v2` parameters of an "a=fmtp" line, kept in
+/// received order so that they render the way they were parsed. Parameters
+/// without a value, such as the "111/111" of RED, have no value.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FmtpParams(Vec<(String, Option<String>)>);
+
+/// Renders the parameters without the payload type prefix.
+impl fmt::Display for FmtpParams {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (key, value)) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ";")?;
+            }
+            write!(f, "{}", key)?;
+            if let Some(value) = value {
+                write!(f, "={}", value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for FmtpParams {
+    type Err = Error;
+
+    fn from_str(fmtp: &str) -> Result<Self> {
+        let mut params = FmtpParams::default();
+        for param in fmtp.split(';') {
+            let param = param.trim();
+            if param.is_empty() {
+                continue;
+            }
+            let (key, value) = match param.split_once('=') {
+                Some((key, value)) => (key.trim(), Some(value.trim().to_owned())),
+                None => (param, None),
+            };
+            if key.is_empty() {
+                return Err(Error::SdpInvalidValue(fmtp.to_owned()));
+            }
+            params.0.push((key.to_owned(), value));
+        }
+        Ok(params)
+    }
+}
+
+impl FmtpParams {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// get returns the value of a parameter, Some("") if it has no value
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_deref().unwrap_or_default())
+    }
+
+    /// set replaces the value of a parameter in place or appends it
+    pub fn set(&mut self, key: &str, value: &str) {
+        let value = Some(value.to_owned());
+        match self.0.iter_mut().find(|(k, _)| k == key) {
+            Some(param) => param.1 = value,
+            None => self.0.push((key.to_owned(), value)),
+        }
+    }
+
+    /// remove removes a parameter and returns its value
+    pub fn remove(&mut self, key: &str) -> Option<String> {
+        let i = self.0.iter().position(|(k, _)| k == key)?;
+        Some(self.0.remove(i).1.unwrap_or_default())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, Option<&str>)> {
+        self.0.iter().map(|(k, v)| (k.as_str(), v.as_deref()))
+    }
+}
+
+/// parse_rtpmap parses the value of an "a=rtpmap" attribute
+pub fn parse_rtpmap(rtpmap: &str) -> Result<Codec> {
+    // a=rtpmap:<payload type> <encoding name>/<clock rate>[/<encoding parameters>]
+    let split: Vec<&str> = rtpmap.split_whitespace().collect();
+    if split.len() != 2 {
+        return Err(Error::MissingWhitespace);
+    }
+
+    let payload_type = split[0].parse::<u8>()?;
+
+    let split: Vec<&str> = split[1].split('/').collect();
+    if split.len() < 2 || split.len() > 3 {
+        return Err(Error::SdpInvalidValue(rtpmap.to_owned()));
+    }
+    let name = split[0].to_owned();
+    let clock_rate = split[1].parse::<u32>()?;
+    let channels = if split.len() == 3 {
+        split[2].parse::<u16>()?
+    } else {
+        0
+    };
+
+    Ok(Codec {
+        payload_type,
+        name,
+        clock_rate,
+        channels,
+        ..Default::default()
+    })
+}
+
+/// parse_fmtp parses the value of an "a=fmtp" attribute into its payload
+/// type and parameters
+pub fn parse_fmtp(fmtp: &str) -> Result<(u8, FmtpParams)> {
+    // a=fmtp:<format> <format specific parameters>
+    let split: Vec<&str> = fmtp.splitn(2, ' ').collect();
+    if split.len() != 2 {
+        return Err(Error::MissingWhitespace);
+    }
+
+    let payload_type = split[0].parse::<u8>()?;
+    Ok((payload_type, split[1].parse()?))
+}
+
+/// parse_rtcp_fb parses the value of an "a=rtcp-fb" attribute into its
+/// payload type and feedback mechanism. The wildcard payload type "*" is
+/// returned as None.
+pub fn parse_rtcp_fb(rtcp_fb: &str) -> Result<(Option<u8>, String)> {
+    // a=rtcp-fb:<payload type> <RTCP feedback type> [<RTCP feedback parameter>]
+    let split: Vec<&str> = rtcp_fb.splitn(2, ' ').collect();
+    if split.len() != 2 {
+        return Err(Error::MissingWhitespace);
+    }
+
+    let payload_type = if split[0] == "*" {
+        None
+    } else {
+        Some(split[0].parse::<u8>()?)
+    };
+    Ok((payload_type, split[1].trim().to_owned()))
+}
+
+/// payload_type_of returns the payload type prefix of a codec attribute
+/// value, e.g. 111 for "111 opus/48000/2"
+pub(crate) fn payload_type_of(value: &str) -> Option<u8> {
+    value.split(' ').next().and_then(|pt| pt.parse::<u8>().ok())
+}
@@ -1,8 +1,10 @@
 use std::fmt;
+use std::net::IpAddr;
 
 /// ConnectionInformation defines the representation for the "c=" field
 /// containing connection data.
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ConnectionInformation {
     pub network_type: String,
     pub address_type: String,
@@ -20,7 +22,8 @@ impl fmt::Display for ConnectionInformation {
 }
 
 /// Address describes a structured address token from within the "c=" field.
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Address {
     pub address: String,
     pub ttl: Option<isize>,
@@ -29,20 +32,33 @@ pub struct Address {
 
 impl fmt::Display for Address {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let mut parts = vec![self.address.to_owned()];
+        write!(f, "{}", self.address)?;
         if let Some(t) = &self.ttl {
-            parts.push(t.to_string());
+            write!(f, "/{}", t)?;
         }
         if let Some(r) = &self.range {
-            parts.push(r.to_string());
+            write!(f, "/{}", r)?;
         }
-        write!(f, "{}", parts.join("/"))
+        Ok(())
+    }
+}
+
+impl Address {
+    /// ip_addr returns the address if it is an IP literal rather than a FQDN
+    pub fn ip_addr(&self) -> Option<IpAddr> {
+        self.address.parse::<IpAddr>().ok()
+    }
+
+    /// is_multicast returns true for IP4 and IP6 multicast addresses
+    pub fn is_multicast(&self) -> bool {
+        self.ip_addr().is_some_and(|ip| ip.is_multicast())
     }
 }
 
 /// Bandwidth describes an optional field which denotes the proposed bandwidth
 /// to be used by the session or media.
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Bandwidth {
     pub experimental: bool,
     pub bandwidth_type: String,
@@ -58,7 +74,8 @@ impl fmt::Display for Bandwidth {
 
 /// Attribute describes the "a=" field which represents the primary means for
 /// extending SDP.
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Attribute {
     pub key: String,
     pub value: Option<String>,
@@ -0,0 +1,128 @@
+use super::*;
+use crate::sdp::SDP;
+
+const DATACHANNEL_SDP: &str = "v=0\r\n\
+o=- 4611731400430051336 2 IN IP4 127.0.0.1\r\n\
+s=-\r\n\
+t=0 0\r\n\
+a=group:BUNDLE 0 1\r\n\
+m=application 9 UDP/DTLS/SCTP webrtc-datachannel\r\n\
+c=IN IP4 0.0.0.0\r\n\
+a=mid:0\r\n\
+a=sctp-port:5001\r\n\
+a=max-message-size:262144\r\n\
+m=application 9 DTLS/SCTP 5000\r\n\
+c=IN IP4 0.0.0.0\r\n\
+a=mid:1\r\n\
+a=sctpmap:5000 webrtc-datachannel 1024\r\n\
+m=application 9 TCP/DTLS/SCTP webrtc-datachannel\r\n\
+c=IN IP4 0.0.0.0\r\n\
+a=mid:2\r\n";
+
+#[test]
+fn test_sctpmap_round_trip() -> Result<()> {
+    let passingtests = [
+        (
+            "5000 webrtc-datachannel 1024",
+            Sctpmap::new(5000, Some(1024)),
+        ),
+        ("5000 webrtc-datachannel", Sctpmap::new(5000, None)),
+    ];
+    for (i, (u, expected)) in passingtests.iter().enumerate() {
+        let sctpmap = Sctpmap::unmarshal(u)?;
+        assert_eq!(*expected, sctpmap, "{}: {}", i, u);
+        assert_eq!(*u, sctpmap.to_string(), "{}: {}", i, u);
+        assert_eq!(format!("sctpmap:{}", u), sctpmap.marshal());
+        assert_eq!(sctpmap, format!("a=sctpmap:{}", u).parse::<Sctpmap>()?);
+    }
+
+    let failingtests = [
+        "",
+        "5000",
+        "abc webrtc-datachannel",
+        "70000 webrtc-datachannel",
+        "5000 webrtc-datachannel many",
+        "5000 webrtc-datachannel 1024 extra",
+    ];
+    for u in failingtests {
+        assert!(Sctpmap::unmarshal(u).is_err(), "{}", u);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_unmarshal_datachannel() -> Result<()> {
+    let sdp = SDP::unmarshal(DATACHANNEL_SDP.as_bytes())?;
+    assert_eq!(SDP::marshal(&sdp), DATACHANNEL_SDP);
+
+    let current = &sdp.media_descriptions[0];
+    assert!(current.is_datachannel());
+    assert_eq!(current.sctpmap()?, None);
+    assert_eq!(current.sctp_port()?, 5001);
+    assert_eq!(current.max_message_size()?, 262144);
+
+    let legacy = &sdp.media_descriptions[1];
+    assert!(legacy.is_datachannel());
+    assert_eq!(legacy.sctpmap()?, Some(Sctpmap::new(5000, Some(1024))));
+    assert_eq!(legacy.sctp_port()?, 5000);
+    assert_eq!(legacy.max_message_size()?, DEFAULT_MAX_MESSAGE_SIZE);
+
+    let tcp = &sdp.media_descriptions[2];
+    assert_eq!(tcp.media_name.protos, vec!["TCP", "DTLS", "SCTP"]);
+    assert!(tcp.is_datachannel());
+    assert_eq!(tcp.sctp_port()?, DEFAULT_SCTP_PORT);
+
+    assert!(
+        !MediaDescription::new_jsep_media_description("audio".to_owned(), vec![]).is_datachannel()
+    );
+    assert!(sdp.validate().is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_datachannel_builders() -> Result<()> {
+    let media = MediaDescription::new_datachannel_media_description(5000)
+        .with_max_message_size(0)
+        .with_value_attribute("mid".to_owned(), "data".to_owned());
+    let sdp = SDP {
+        media_descriptions: vec![media.clone()],
+        ..Default::default()
+    };
+    let marshaled = SDP::marshal(&sdp);
+    assert!(marshaled.contains(
+        "m=application 9 UDP/DTLS/SCTP webrtc-datachannel\r\n\
+         c=IN IP4 0.0.0.0\r\n\
+         a=sctp-port:5000\r\n\
+         a=max-message-size:0\r\n\
+         a=mid:data\r\n"
+    ));
+    assert_eq!(media.sctp_port()?, 5000);
+    assert_eq!(media.max_message_size()?, 0);
+
+    let legacy = MediaDescription::new_legacy_datachannel_media_description(5000, Some(1024));
+    let sdp = SDP {
+        media_descriptions: vec![legacy.clone()],
+        ..Default::default()
+    };
+    assert!(SDP::marshal(&sdp).contains(
+        "m=application 9 DTLS/SCTP 5000\r\n\
+         c=IN IP4 0.0.0.0\r\n\
+         a=sctpmap:5000 webrtc-datachannel 1024\r\n"
+    ));
+    assert!(legacy.is_datachannel());
+
+    let invalid = media.with_value_attribute(ATTR_KEY_SCTP_PORT.to_owned(), "x".to_owned());
+    let invalid = MediaDescription {
+        attributes: invalid
+            .attributes
+            .into_iter()
+            .filter(|a| a.value.as_deref() != Some("5000"))
+            .collect(),
+        ..invalid
+    };
+    assert_eq!(invalid.sctp_port(), Err(Error::ParseSctp("x".to_owned())));
+
+    Ok(())
+}
@@ -0,0 +1,224 @@
+#[cfg(test)]
+mod datachannel_test;
+
+use super::common::Attribute;
+use super::error::{Error, Result};
+use super::media::MediaDescription;
+
+use std::fmt;
+use std::str::FromStr;
+
+pub const ATTR_KEY_SCTP_PORT: &str = "sctp-port";
+pub const ATTR_KEY_MAX_MESSAGE_SIZE: &str = "max-message-size";
+pub const ATTR_KEY_SCTPMAP: &str = "sctpmap";
+
+pub const MEDIA_APPLICATION: &str = "application";
+pub const FORMAT_WEBRTC_DATACHANNEL: &str = "webrtc-datachannel";
+
+/// SCTP port assumed when a datachannel m-line carries no "a=sctp-port"
+/// <https://tools.ietf.org/html/rfc8841#section-5.2>
+pub const DEFAULT_SCTP_PORT: u16 = 5000;
+
+/// maximum message size assumed when "a=max-message-size" is absent
+/// <https://tools.ietf.org/html/rfc8841#section-6.1>
+pub const DEFAULT_MAX_MESSAGE_SIZE: u64 = 65536;
+
+/// Sctpmap is the structured form of the legacy "a=sctpmap" attribute used
+/// with `m=application <port> DTLS/SCTP <sctp port>` m-lines
+///
+/// `sctpmap:<sctp port> <app> [<streams>]`
+///
+/// <https://tools.ietf.org/html/draft-ietf-mmusic-sctp-sdp-05#section-4.3>
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Sctpmap {
+    pub port: u16,
+    pub app: String,
+    pub streams: Option<u16>,
+}
+
+/// Renders the value of the "a=sctpmap" attribute.
+impl fmt::Display for Sctpmap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.port, self.app)?;
+        if let Some(streams) = self.streams {
+            write!(f, " {}", streams)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Sctpmap {
+    type Err = Error;
+
+    fn from_str(raw: &str) -> Result<Self> {
+        Sctpmap::unmarshal(raw)
+    }
+}
+
+impl Sctpmap {
+    pub fn new(port: u16, streams: Option<u16>) -> Self {
+        Sctpmap {
+            port,
+            app: FORMAT_WEBRTC_DATACHANNEL.to_owned(),
+            streams,
+        }
+    }
+
+    /// unmarshal parses an sctpmap, with or without the "a=sctpmap:" or
+    /// "sctpmap:" prefix
+    pub fn unmarshal(raw: &str) -> Result<Self> {
+        let value = raw.trim();
+        let value = value.strip_prefix("a=").unwrap_or(value);
+        let value = value.strip_prefix("sctpmap:").unwrap_or(value);
+
+        let fields: Vec<&str> = value.split_whitespace().collect();
+        if fields.len() < 2 || fields.len() > 3 {
+            return Err(Error::ParseSctp(raw.to_owned()));
+        }
+
+        let port = fields[0]
+            .parse::<u16>()
+            .map_err(|_| Error::ParseSctp(raw.to_owned()))?;
+        let streams = match fields.get(2) {
+            Some(streams) => Some(
+                streams
+                    .parse::<u16>()
+                    .map_err(|_| Error::ParseSctp(raw.to_owned()))?,
+            ),
+            None => None,
+        };
+
+        Ok(Sctpmap {
+            port,
+            app: fields[1].to_owned(),
+            streams,
+        })
+    }
+
+    /// marshal renders the sctpmap as "sctpmap:..." attribute line value
+    pub fn marshal(&self) -> String {
+        format!("{}:{}", ATTR_KEY_SCTPMAP, self)
+    }
+
+    /// converts this object to an Attribute
+    pub fn convert(&self) -> Attribute {
+        Attribute {
+            key: ATTR_KEY_SCTPMAP.to_owned(),
+            value: Some(self.to_string()),
+        }
+    }
+}
+
+impl MediaDescription {
+    /// new_datachannel_media_description creates an application m-line for
+    /// WebRTC data channels
+    /// <https://tools.ietf.org/html/rfc8841#section-4.1>
+    ///
+    /// `m=application 9 UDP/DTLS/SCTP webrtc-datachannel`
+    pub fn new_datachannel_media_description(sctp_port: u16) -> Self {
+        let mut media =
+            MediaDescription::new_jsep_media_description(MEDIA_APPLICATION.to_owned(), vec![]);
+        media.media_name.protos = vec!["UDP".to_owned(), "DTLS".to_owned(), "SCTP".to_owned()];
+        media.media_name.formats = vec![FORMAT_WEBRTC_DATACHANNEL.to_owned()];
+        media.with_sctp_port(sctp_port)
+    }
+
+    /// new_legacy_datachannel_media_description creates an application
+    /// m-line in the pre-standard form still sent by older endpoints
+    ///
+    /// `m=application 9 DTLS/SCTP <sctp port>` with "a=sctpmap"
+    pub fn new_legacy_datachannel_media_description(sctp_port: u16, streams: Option<u16>) -> Self {
+        let mut media =
+            MediaDescription::new_jsep_media_description(MEDIA_APPLICATION.to_owned(), vec![]);
+        media.media_name.protos = vec!["DTLS".to_owned(), "SCTP".to_owned()];
+        media.media_name.formats = vec![sctp_port.to_string()];
+        media
+            .attributes
+            .push(Sctpmap::new(sctp_port, streams).convert());
+        media
+    }
+
+    /// with_sctp_port adds an "a=sctp-port" attribute
+    pub fn with_sctp_port(mut self, port: u16) -> Self {
+        self.attributes.push(Attribute::new(
+            ATTR_KEY_SCTP_PORT.to_owned(),
+            Some(port.to_string()),
+        ));
+        self
+    }
+
+    /// with_max_message_size adds an "a=max-message-size" attribute, 0
+    /// announces that any message size can be received
+    pub fn with_max_message_size(mut self, size: u64) -> Self {
+        self.attributes.push(Attribute::new(
+            ATTR_KEY_MAX_MESSAGE_SIZE.to_owned(),
+            Some(size.to_string()),
+        ));
+        self
+    }
+
+    /// is_datachannel returns true for application m-lines carrying WebRTC
+    /// data channels, in either the current or the legacy form
+    pub fn is_datachannel(&self) -> bool {
+        if self.media_name.media != MEDIA_APPLICATION
+            || !self.media_name.protos.iter().any(|p| p == "SCTP")
+        {
+            return false;
+        }
+        if self
+            .media_name
+            .formats
+            .iter()
+            .any(|f| f == FORMAT_WEBRTC_DATACHANNEL)
+        {
+            return true;
+        }
+        match self.sctpmap() {
+            Ok(Some(sctpmap)) => sctpmap.app == FORMAT_WEBRTC_DATACHANNEL,
+            _ => false,
+        }
+    }
+
+    /// sctpmap parses the legacy "a=sctpmap" attribute
+    pub fn sctpmap(&self) -> Result<Option<Sctpmap>> {
+        self.attribute(ATTR_KEY_SCTPMAP)
+            .map(|value| Sctpmap::unmarshal(value.unwrap_or_default()))
+            .transpose()
+    }
+
+    /// sctp_port returns the SCTP port of a datachannel m-line, taken from
+    /// "a=sctp-port", else from the legacy "a=sctpmap" or format, else the
+    /// default 5000
+    pub fn sctp_port(&self) -> Result<u16> {
+        if let Some(value) = self.attribute(ATTR_KEY_SCTP_PORT) {
+            let value = value.unwrap_or_default();
+            return value
+                .parse::<u16>()
+                .map_err(|_| Error::ParseSctp(value.to_owned()));
+        }
+        if let Some(sctpmap) = self.sctpmap()? {
+            return Ok(sctpmap.port);
+        }
+        Ok(self
+            .media_name
+            .formats
+            .first()
+            .and_then(|format| format.parse::<u16>().ok())
+            .unwrap_or(DEFAULT_SCTP_PORT))
+    }
+
+    /// max_message_size returns the value of "a=max-message-size", the
+    /// default 65536 if absent. 0 means that there is no limit.
+    pub fn max_message_size(&self) -> Result<u64> {
+        match self.attribute(ATTR_KEY_MAX_MESSAGE_SIZE) {
+            Some(value) => {
+                let value = value.unwrap_or_default();
+                value
+                    .parse::<u64>()
+                    .map_err(|_| Error::ParseSctp(value.to_owned()))
+            }
+            None => Ok(DEFAULT_MAX_MESSAGE_SIZE),
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use super::*;
+use crate::sdp::common::Attribute;
+use crate::sdp::error::Result;
+use crate::sdp::sdp_test::SDPSTR;
+
+#[test]
+fn test_diff_unchanged() -> Result<()> {
+    let sdp = SDP::unmarshal(SDPSTR.as_bytes())?;
+    let sdp_diff = diff(&sdp, &sdp.clone());
+    assert!(sdp_diff.is_empty());
+    assert_eq!(sdp_diff.to_string(), "");
+
+    Ok(())
+}
+
+#[test]
+fn test_diff_renegotiation() -> Result<()> {
+    let old = SDP::unmarshal(SDPSTR.as_bytes())?;
+    let mut new = old.clone();
+    new.session.origin.session_version += 1;
+
+    // audio: PCMU dropped, opus fmtp changed, ICE restarted
+    let audio = &mut new.media_descriptions[0];
+    audio.media_name.formats.retain(|f| f != "0");
+    audio
+        .attributes
+        .retain(|a| a.value.as_deref() != Some("0 PCMU/8000"));
+    for a in audio.attributes.iter_mut() {
+        match a.key.as_str() {
+            "fmtp" if a.value.as_deref() == Some("111 minptime=10;useinbandfec=1") => {
+                a.value = Some("111 minptime=20".to_owned())
+            }
+            ATTR_KEY_ICE_UFRAG => a.value = Some("b2Xz".to_owned()),
+            _ => {}
+        }
+    }
+
+    // first video: direction changed
+    let video = &mut new.media_descriptions[1];
+    for a in video.attributes.iter_mut() {
+        if a.key == "sendrecv" {
+            a.key = "recvonly".to_owned();
+        }
+    }
+
+    // second video replaced by a section with a new mid
+    new.media_descriptions[2].set_mid("5");
+
+    let sdp_diff = new.diff(&new.clone());
+    assert!(sdp_diff.is_empty());
+
+    let sdp_diff = old.diff(&new);
+    assert_eq!(sdp_diff.origin_version, Some((3, 4)));
+    assert_eq!(sdp_diff.added_media, vec!["5"]);
+    assert_eq!(sdp_diff.removed_media, vec!["4"]);
+    assert!(sdp_diff.ice_restart());
+
+    let mut opus = old.media_descriptions[0].codec_for_payload_type(111)?;
+    let old_opus = opus.clone();
+    opus.fmtp = "minptime=20".parse()?;
+    assert_eq!(
+        sdp_diff.changes("2"),
+        &[
+            MediaChange::CodecRemoved(Codec::new(0, "PCMU", 8000, 0)),
+            MediaChange::CodecChanged {
+                old: old_opus,
+                new: opus,
+            },
+            MediaChange::IceRestart,
+        ]
+    );
+    assert_eq!(
+        sdp_diff.changes("3"),
+        &[MediaChange::DirectionChanged {
+            old: Direction::SendRecv,
+            new: Direction::RecvOnly,
+        }]
+    );
+
+    assert_eq!(
+        sdp_diff.to_string(),
+        "origin version 3 -> 4\n\
+         + media 5\n\
+         - media 4\n\
+         ~ media 2: codec removed 0 PCMU/8000\n\
+         ~ media 2: codec changed 111 opus/48000/2 fmtp \"minptime=10;useinbandfec=1\" -> \"minptime=20\"\n\
+         ~ media 2: ice restart\n\
+         ~ media 3: direction sendrecv -> recvonly\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_diff_session_level_inheritance() -> Result<()> {
+    let mut old = SDP::unmarshal(SDPSTR.as_bytes())?;
+    for media in old.media_descriptions.iter_mut() {
+        media
+            .attributes
+            .retain(|a| a.key != ATTR_KEY_ICE_PWD && a.key != "sendrecv");
+    }
+    old.session.attributes.push(Attribute::new(
+        ATTR_KEY_ICE_PWD.to_owned(),
+        Some("JZBw86RpZXvCLCn5E2rya5bI".to_owned()),
+    ));
+
+    // an ICE restart and a direction change on session level apply to every
+    // media section
+    let mut new = old.clone();
+    new.session.attributes.retain(|a| a.key != ATTR_KEY_ICE_PWD);
+    new.session.attributes.push(Attribute::new(
+        ATTR_KEY_ICE_PWD.to_owned(),
+        Some("pT0mV5Hb7d4X2yqWk3cF9sNe".to_owned()),
+    ));
+    new.session
+        .attributes
+        .push(Attribute::new("inactive".to_owned(), None));
+    new.media_descriptions[1].media_name.port.value = 0;
+
+    let sdp_diff = diff(&old, &new);
+    let expected = [
+        MediaChange::DirectionChanged {
+            old: Direction::SendRecv,
+            new: Direction::Inactive,
+        },
+        MediaChange::IceRestart,
+    ];
+    assert_eq!(sdp_diff.changes("2"), &expected);
+    assert_eq!(
+        sdp_diff.changes("3")[0],
+        MediaChange::PortChanged { old: 9, new: 0 }
+    );
+    assert_eq!(&sdp_diff.changes("3")[1..], &expected);
+    assert_eq!(sdp_diff.changed_media.len(), 3);
+
+    // sections without mid are matched by position
+    let audio = MediaDescription::new_jsep_media_description("audio".to_owned(), vec![]);
+    let old = SDP {
+        media_descriptions: vec![audio.clone()],
+        ..Default::default()
+    };
+    let new = SDP {
+        media_descriptions: vec![audio.clone(), audio],
+        ..Default::default()
+    };
+    assert_eq!(diff(&old, &new).added_media, vec!["#1"]);
+
+    Ok(())
+}
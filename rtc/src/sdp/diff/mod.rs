@@ -0,0 +1,272 @@
+#[cfg(test)]
+mod diff_test;
+
+use super::codec::Codec;
+use super::direction::{effective_direction, Direction};
+use super::effective::inherited_attribute;
+use super::media::{MediaDescription, ATTR_KEY_ICE_PWD, ATTR_KEY_ICE_UFRAG};
+use super::session::Session;
+use super::SDP;
+
+use std::fmt;
+
+/// MediaChange is a single difference between two versions of a media section
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MediaChange {
+    CodecAdded(Codec),
+    CodecRemoved(Codec),
+    /// same payload type, different rtpmap, fmtp or rtcp-fb
+    CodecChanged {
+        old: Codec,
+        new: Codec,
+    },
+    /// the effective direction, including session-level inheritance
+    DirectionChanged {
+        old: Direction,
+        new: Direction,
+    },
+    /// the port changed, a port of 0 rejects the section
+    PortChanged {
+        old: isize,
+        new: isize,
+    },
+    /// the effective ICE ufrag or pwd changed
+    /// <https://tools.ietf.org/html/rfc8839#section-4.4.1.1.1>
+    IceRestart,
+}
+
+impl fmt::Display for MediaChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MediaChange::CodecAdded(codec) => write!(f, "codec added {}", codec),
+            MediaChange::CodecRemoved(codec) => write!(f, "codec removed {}", codec),
+            MediaChange::CodecChanged { old, new } => {
+                write!(f, "codec changed {}", old)?;
+                if old.to_string() != new.to_string() {
+                    write!(f, " -> {}", new)?;
+                }
+                if old.fmtp != new.fmtp {
+                    write!(f, " fmtp \"{}\" -> \"{}\"", old.fmtp, new.fmtp)?;
+                }
+                if old.rtcp_feedback != new.rtcp_feedback {
+                    write!(
+                        f,
+                        " rtcp-fb [{}] -> [{}]",
+                        old.rtcp_feedback.join(", "),
+                        new.rtcp_feedback.join(", ")
+                    )?;
+                }
+                Ok(())
+            }
+            MediaChange::DirectionChanged { old, new } => {
+                write!(f, "direction {} -> {}", old, new)
+            }
+            MediaChange::PortChanged { old, new } => write!(f, "port {} -> {}", old, new),
+            MediaChange::IceRestart => write!(f, "ice restart"),
+        }
+    }
+}
+
+/// MediaDiff lists the changes of a media section present in both
+/// descriptions
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MediaDiff {
+    /// see [`media_key`]
+    pub key: String,
+    pub changes: Vec<MediaChange>,
+}
+
+/// SdpDiff is the structured difference between two session descriptions,
+/// typically the previous and the current offer or answer of a session
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SdpDiff {
+    /// old and new `<sess-version>` of the origin, None if unchanged
+    pub origin_version: Option<(u64, u64)>,
+    /// keys of media sections only present in the new description
+    pub added_media: Vec<String>,
+    /// keys of media sections only present in the old description
+    pub removed_media: Vec<String>,
+    pub changed_media: Vec<MediaDiff>,
+}
+
+impl SdpDiff {
+    /// is_empty returns true if both descriptions are equivalent
+    pub fn is_empty(&self) -> bool {
+        self.origin_version.is_none()
+            && self.added_media.is_empty()
+            && self.removed_media.is_empty()
+            && self.changed_media.is_empty()
+    }
+
+    /// ice_restart returns true if any media section restarted ICE
+    pub fn ice_restart(&self) -> bool {
+        self.changed_media
+            .iter()
+            .any(|m| m.changes.contains(&MediaChange::IceRestart))
+    }
+
+    /// changes returns the changes of the media section with the given key
+    pub fn changes(&self, key: &str) -> &[MediaChange] {
+        self.changed_media
+            .iter()
+            .find(|m| m.key == key)
+            .map(|m| m.changes.as_slice())
+            .unwrap_or_default()
+    }
+}
+
+/// Renders one line per difference, e.g.
+///
+/// ```text
+/// origin version 2 -> 3
+/// + media 2
+/// - media 1
+/// ~ media 0: direction sendrecv -> recvonly
+/// ```
+impl fmt::Display for SdpDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some((old, new)) = self.origin_version {
+            writeln!(f, "origin version {} -> {}", old, new)?;
+        }
+        for key in &self.added_media {
+            writeln!(f, "+ media {}", key)?;
+        }
+        for key in &self.removed_media {
+            writeln!(f, "- media {}", key)?;
+        }
+        for media in &self.changed_media {
+            for change in &media.changes {
+                writeln!(f, "~ media {}: {}", media.key, change)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// media_key identifies a media section across descriptions: its mid, or
+/// "#<index>" for sections without "a=mid"
+pub fn media_key(index: usize, media: &MediaDescription) -> String {
+    match media.mid() {
+        Some(mid) => mid.to_owned(),
+        None => format!("#{}", index),
+    }
+}
+
+/// diff computes the differences from `old` to `new`. Media sections are
+/// matched by [`media_key`], the changed ones are listed in the order of
+/// `new`.
+pub fn diff(old: &SDP, new: &SDP) -> SdpDiff {
+    let mut sdp_diff = SdpDiff::default();
+
+    let old_version = old.session.origin.session_version;
+    let new_version = new.session.origin.session_version;
+    if old_version != new_version {
+        sdp_diff.origin_version = Some((old_version, new_version));
+    }
+
+    let old_media: Vec<(String, &MediaDescription)> = old
+        .media_descriptions
+        .iter()
+        .enumerate()
+        .map(|(i, m)| (media_key(i, m), m))
+        .collect();
+    let new_media: Vec<(String, &MediaDescription)> = new
+        .media_descriptions
+        .iter()
+        .enumerate()
+        .map(|(i, m)| (media_key(i, m), m))
+        .collect();
+
+    for (key, _) in &old_media {
+        if !new_media.iter().any(|(k, _)| k == key) {
+            sdp_diff.removed_media.push(key.clone());
+        }
+    }
+
+    for (key, new_md) in &new_media {
+        let old_md = match old_media.iter().find(|(k, _)| k == key) {
+            Some((_, old_md)) => old_md,
+            None => {
+                sdp_diff.added_media.push(key.clone());
+                continue;
+            }
+        };
+
+        let changes = diff_media((&old.session, old_md), (&new.session, new_md));
+        if !changes.is_empty() {
+            sdp_diff.changed_media.push(MediaDiff {
+                key: key.clone(),
+                changes,
+            });
+        }
+    }
+
+    sdp_diff
+}
+
+impl SDP {
+    /// diff computes the differences from `self` to `new`, see [`diff`]
+    pub fn diff(&self, new: &SDP) -> SdpDiff {
+        diff(self, new)
+    }
+}
+
+fn diff_media(
+    (old_session, old): (&Session, &MediaDescription),
+    (new_session, new): (&Session, &MediaDescription),
+) -> Vec<MediaChange> {
+    let mut changes = vec![];
+
+    let (old_port, new_port) = (old.media_name.port.value, new.media_name.port.value);
+    if old_port != new_port {
+        changes.push(MediaChange::PortChanged {
+            old: old_port,
+            new: new_port,
+        });
+    }
+
+    let old_codecs = old.codecs();
+    let new_codecs = new.codecs();
+    for codec in &old_codecs {
+        if !new_codecs
+            .iter()
+            .any(|c| c.payload_type == codec.payload_type)
+        {
+            changes.push(MediaChange::CodecRemoved(codec.clone()));
+        }
+    }
+    for codec in &new_codecs {
+        match old_codecs
+            .iter()
+            .find(|c| c.payload_type == codec.payload_type)
+        {
+            Some(old_codec) if old_codec != codec => changes.push(MediaChange::CodecChanged {
+                old: old_codec.clone(),
+                new: codec.clone(),
+            }),
+            Some(_) => {}
+            None => changes.push(MediaChange::CodecAdded(codec.clone())),
+        }
+    }
+
+    let old_direction = effective_direction(old_session, old);
+    let new_direction = effective_direction(new_session, new);
+    if old_direction != new_direction {
+        changes.push(MediaChange::DirectionChanged {
+            old: old_direction,
+            new: new_direction,
+        });
+    }
+
+    let ice_restart = [ATTR_KEY_ICE_UFRAG, ATTR_KEY_ICE_PWD].iter().any(|key| {
+        inherited_attribute(old_session, old, key) != inherited_attribute(new_session, new, key)
+    });
+    if ice_restart {
+        changes.push(MediaChange::IceRestart);
+    }
+
+    changes
+}
@@ -3,20 +3,20 @@ use std::iter::Iterator;
 
 #[test]
 fn test_new_direction() {
-    let passingtests = vec![
+    let passingtests = [
         ("sendrecv", Direction::SendRecv),
         ("sendonly", Direction::SendOnly),
         ("recvonly", Direction::RecvOnly),
         ("inactive", Direction::Inactive),
     ];
 
-    let failingtests = vec!["", "notadirection"];
+    let failingtests = ["", "notadirection"];
 
     for (i, u) in passingtests.iter().enumerate() {
         let dir = Direction::new(u.0);
         assert!(u.1 == dir, "{}: {}", i, u.0);
     }
-    for (_, &u) in failingtests.iter().enumerate() {
+    for &u in failingtests.iter() {
         let dir = Direction::new(u);
         assert!(dir == Direction::Unspecified);
     }
@@ -24,7 +24,7 @@ fn test_new_direction() {
 
 #[test]
 fn test_direction_string() {
-    let tests = vec![
+    let tests = [
         (Direction::Unspecified, DIRECTION_UNSPECIFIED_STR),
         (Direction::SendRecv, "sendrecv"),
         (Direction::SendOnly, "sendonly"),
@@ -36,3 +36,119 @@ fn test_direction_string() {
         assert!(u.1 == u.0.to_string(), "{}: {}", i, u.1);
     }
 }
+
+#[test]
+fn test_direction_from_str() -> Result<()> {
+    for direction in [
+        Direction::SendRecv,
+        Direction::SendOnly,
+        Direction::RecvOnly,
+        Direction::Inactive,
+    ] {
+        assert_eq!(direction.to_string().parse::<Direction>()?, direction);
+        assert_eq!(
+            Direction::try_from(direction.to_string().as_str())?,
+            direction
+        );
+    }
+
+    for u in ["", "notadirection", DIRECTION_UNSPECIFIED_STR] {
+        assert_eq!(
+            u.parse::<Direction>(),
+            Err(Error::ParseDirection(u.to_owned()))
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_direction_algebra() {
+    let tests = [
+        (Direction::SendRecv, Direction::SendRecv),
+        (Direction::SendOnly, Direction::RecvOnly),
+        (Direction::RecvOnly, Direction::SendOnly),
+        (Direction::Inactive, Direction::Inactive),
+        (Direction::Unspecified, Direction::Unspecified),
+    ];
+    for (direction, reversed) in tests {
+        assert_eq!(direction.reverse(), reversed, "{}", direction);
+        assert_eq!(direction.reverse().reverse(), direction, "{}", direction);
+    }
+
+    let tests = [
+        (
+            Direction::SendRecv,
+            Direction::SendOnly,
+            Direction::SendOnly,
+        ),
+        (
+            Direction::SendRecv,
+            Direction::Unspecified,
+            Direction::SendRecv,
+        ),
+        (
+            Direction::SendOnly,
+            Direction::RecvOnly,
+            Direction::Inactive,
+        ),
+        (
+            Direction::RecvOnly,
+            Direction::SendRecv,
+            Direction::RecvOnly,
+        ),
+        (
+            Direction::Inactive,
+            Direction::SendRecv,
+            Direction::Inactive,
+        ),
+    ];
+    for (a, b, expected) in tests {
+        assert_eq!(a.intersect(b), expected, "{} {}", a, b);
+        assert_eq!(b.intersect(a), expected, "{} {}", b, a);
+    }
+
+    assert!(Direction::SendOnly.is_sending() && !Direction::SendOnly.is_receiving());
+    assert_eq!(Direction::from_send_recv(false, true), Direction::RecvOnly);
+}
+
+#[test]
+fn test_media_direction() {
+    let mut sdp = SDP {
+        media_descriptions: vec![MediaDescription::default(), MediaDescription::default()],
+        ..Default::default()
+    };
+    assert_eq!(sdp.session.direction(), None);
+    assert_eq!(sdp.media_descriptions[0].direction(), None);
+    assert_eq!(
+        sdp.media_direction(&sdp.media_descriptions[0]),
+        Direction::SendRecv
+    );
+
+    // a session-level direction applies to media sections without their own
+    sdp.session.set_direction(Direction::Inactive);
+    sdp.media_descriptions[1].set_direction(Direction::SendOnly);
+    assert_eq!(
+        sdp.media_direction(&sdp.media_descriptions[0]),
+        Direction::Inactive
+    );
+    assert_eq!(
+        sdp.media_direction(&sdp.media_descriptions[1]),
+        Direction::SendOnly
+    );
+
+    // hold and resume replace the attribute in place
+    let media = &mut sdp.media_descriptions[1];
+    media.set_mid("0");
+    media.attributes.push(Direction::SendRecv.convert());
+    media.set_direction(Direction::RecvOnly);
+    let keys: Vec<&str> = media.attributes.iter().map(|a| a.key.as_str()).collect();
+    assert_eq!(keys, vec!["recvonly", "mid"]);
+
+    media.set_direction(Direction::Unspecified);
+    assert_eq!(media.direction(), None);
+    assert_eq!(
+        sdp.media_direction(&sdp.media_descriptions[1]),
+        Direction::Inactive
+    );
+}
@@ -1,11 +1,20 @@
 use std::fmt;
+use std::str::FromStr;
 
 #[cfg(test)]
 mod direction_test;
 
+use super::common::Attribute;
+use super::error::{Error, Result};
+use super::media::MediaDescription;
+use super::session::Session;
+use super::SDP;
+
 /// Direction is a marker for transmission direction of an endpoint
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Direction {
+    #[default]
     Unspecified = 0,
     /// Direction::SendRecv is for bidirectional communication
     SendRecv = 1,
@@ -36,12 +45,6 @@ impl fmt::Display for Direction {
     }
 }
 
-impl Default for Direction {
-    fn default() -> Direction {
-        Direction::Unspecified
-    }
-}
-
 impl Direction {
     /// new defines a procedure for creating a new direction from a raw string.
     pub fn new(raw: &str) -> Self {
@@ -53,4 +56,141 @@ impl Direction {
             _ => Direction::Unspecified,
         }
     }
+
+    /// from_send_recv returns the direction sending and/or receiving
+    pub fn from_send_recv(send: bool, recv: bool) -> Self {
+        match (send, recv) {
+            (true, true) => Direction::SendRecv,
+            (true, false) => Direction::SendOnly,
+            (false, true) => Direction::RecvOnly,
+            (false, false) => Direction::Inactive,
+        }
+    }
+
+    /// is_sending returns true for directions that send media, an
+    /// unspecified direction defaults to sendrecv
+    pub fn is_sending(&self) -> bool {
+        matches!(
+            self,
+            Direction::SendRecv | Direction::SendOnly | Direction::Unspecified
+        )
+    }
+
+    /// is_receiving returns true for directions that receive media, an
+    /// unspecified direction defaults to sendrecv
+    pub fn is_receiving(&self) -> bool {
+        matches!(
+            self,
+            Direction::SendRecv | Direction::RecvOnly | Direction::Unspecified
+        )
+    }
+
+    /// reverse returns the direction as seen from the peer, e.g. the
+    /// direction an answer mirrors for an offered sendonly stream
+    /// <https://tools.ietf.org/html/rfc3264#section-6.1>
+    pub fn reverse(&self) -> Self {
+        match self {
+            Direction::SendOnly => Direction::RecvOnly,
+            Direction::RecvOnly => Direction::SendOnly,
+            d => *d,
+        }
+    }
+
+    /// intersect restricts a direction to what `other` allows, sending only
+    /// if both send and receiving only if both receive
+    pub fn intersect(&self, other: Direction) -> Self {
+        Direction::from_send_recv(
+            self.is_sending() && other.is_sending(),
+            self.is_receiving() && other.is_receiving(),
+        )
+    }
+
+    /// converts this object to a property Attribute, e.g. "a=sendonly"
+    pub fn convert(&self) -> Attribute {
+        Attribute::new(self.to_string(), None)
+    }
+}
+
+/// Parses a direction attribute key, unlike `Direction::new` anything but
+/// the four directions is an error.
+impl FromStr for Direction {
+    type Err = Error;
+
+    fn from_str(raw: &str) -> Result<Self> {
+        match Direction::new(raw) {
+            Direction::Unspecified => Err(Error::ParseDirection(raw.to_owned())),
+            direction => Ok(direction),
+        }
+    }
+}
+
+impl TryFrom<&str> for Direction {
+    type Error = Error;
+
+    fn try_from(raw: &str) -> Result<Self> {
+        raw.parse()
+    }
+}
+
+/// direction_of returns the first direction property attribute
+fn direction_of(attributes: &[Attribute]) -> Option<Direction> {
+    attributes.iter().find_map(|a| a.key.parse().ok())
+}
+
+/// set_direction_of replaces all direction property attributes, the new one
+/// takes the position of the first
+fn set_direction_of(attributes: &mut Vec<Attribute>, direction: Direction) {
+    let position = attributes
+        .iter()
+        .position(|a| Direction::new(&a.key) != Direction::Unspecified);
+    attributes.retain(|a| Direction::new(&a.key) == Direction::Unspecified);
+    if direction == Direction::Unspecified {
+        return;
+    }
+    let position = position.unwrap_or(attributes.len());
+    attributes.insert(position, direction.convert());
+}
+
+impl Session {
+    /// direction returns the session-level direction attribute
+    pub fn direction(&self) -> Option<Direction> {
+        direction_of(&self.attributes)
+    }
+
+    /// set_direction replaces the session-level direction attribute,
+    /// Direction::Unspecified removes it
+    pub fn set_direction(&mut self, direction: Direction) {
+        set_direction_of(&mut self.attributes, direction);
+    }
+}
+
+impl MediaDescription {
+    /// direction returns the media-level direction attribute
+    pub fn direction(&self) -> Option<Direction> {
+        direction_of(&self.attributes)
+    }
+
+    /// set_direction replaces the media-level direction attribute,
+    /// Direction::Unspecified removes it
+    pub fn set_direction(&mut self, direction: Direction) {
+        set_direction_of(&mut self.attributes, direction);
+    }
+}
+
+impl SDP {
+    /// media_direction returns the direction that applies to a media
+    /// section: its own, else the session-level one, else sendrecv
+    /// <https://tools.ietf.org/html/rfc4566#section-6>
+    pub fn media_direction(&self, media: &MediaDescription) -> Direction {
+        effective_direction(&self.session, media)
+    }
+}
+
+/// effective_direction returns the media-level direction, else the
+/// session-level one, else sendrecv
+pub(crate) fn effective_direction(session: &Session, media: &MediaDescription) -> Direction {
+    media
+        .direction()
+        .or_else(|| session.direction())
+        .unwrap_or(Direction::SendRecv)
 }
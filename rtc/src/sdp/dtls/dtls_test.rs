@@ -0,0 +1,120 @@
+use super::*;
+use crate::sdp::sdp_test::SDPSTR;
+
+const SHA256_FINGERPRINT: &str = "sha-256 96:01:FD:7F:1D:4D:D8:3E:54:9A:1A:E2:0F:E3:9D:60:05:D3:0E:D7:AB:03:98:D3:B8:8D:BE:95:85:19:BC:E3";
+
+#[test]
+fn test_fingerprint_round_trip() -> Result<()> {
+    let passingtests = [
+        SHA256_FINGERPRINT,
+        "sha-1 4A:AD:B9:B1:3F:82:18:3B:54:02:12:DF:3E:5D:49:6B:19:E5:7C:AB",
+    ];
+
+    for (i, u) in passingtests.iter().enumerate() {
+        let fingerprint = Fingerprint::unmarshal(u)?;
+        assert_eq!(*u, fingerprint.to_string(), "{}: {}", i, u);
+        assert_eq!(format!("fingerprint:{}", u), fingerprint.marshal());
+        assert_eq!(
+            fingerprint,
+            Fingerprint::unmarshal(&format!("a=fingerprint:{}\r\n", u))?
+        );
+    }
+
+    // hash function and digest are case-insensitive, rendering is canonical
+    let fingerprint =
+        Fingerprint::unmarshal(&SHA256_FINGERPRINT.to_lowercase().replace("sha", "SHA"))?;
+    assert_eq!(fingerprint.algorithm, HashAlgorithm::Sha256);
+    assert_eq!(fingerprint.to_string(), SHA256_FINGERPRINT);
+
+    let failingtests = [
+        "",
+        "sha-256",
+        "md5 4A:AD:B9:B1:3F:82:18:3B:54:02:12:DF:3E:5D:49:6B",
+        "sha-1 4A:AD:B9:B1:3F:82:18:3B:54:02:12:DF:3E:5D:49:6B:19:E5:7C",
+        "sha-1 4A:AD:B9:B1:3F:82:18:3B:54:02:12:DF:3E:5D:49:6B:19:E5:7C:AB:00",
+        "sha-1 4AAD:B9:B1:3F:82:18:3B:54:02:12:DF:3E:5D:49:6B:19:E5:7C:AB",
+        "sha-1 4A:AD:B9:B1:3F:82:18:3B:54:02:12:DF:3E:5D:49:6B:19:E5:7C:XY",
+        "sha-1 4A:AD:B9:B1:3F:82:18:3B:54:02:12:DF:3E:5D:49:6B:19:E5:7C:AB extra",
+    ];
+    for u in failingtests {
+        assert!(Fingerprint::unmarshal(u).is_err(), "{}", u);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_fingerprint_verify() -> Result<()> {
+    let fingerprint = Fingerprint::unmarshal(SHA256_FINGERPRINT)?;
+    let mut digest = fingerprint.digest.clone();
+    assert!(fingerprint.verify(&digest));
+
+    digest[31] ^= 1;
+    assert!(!fingerprint.verify(&digest));
+    assert!(!fingerprint.verify(&digest[..20]));
+
+    let other = Fingerprint::new(HashAlgorithm::Sha256, &digest)?;
+    assert_ne!(fingerprint, other);
+    assert!(Fingerprint::new(HashAlgorithm::Sha512, &digest).is_err());
+    assert_eq!(HashAlgorithm::Sha384.digest_len(), 48);
+
+    Ok(())
+}
+
+#[test]
+fn test_setup_role() -> Result<()> {
+    let tests = [
+        (SetupRole::ActPass, SetupRole::Active),
+        (SetupRole::Active, SetupRole::Passive),
+        (SetupRole::Passive, SetupRole::Active),
+        (SetupRole::HoldConn, SetupRole::HoldConn),
+    ];
+    for (offered, answer) in tests {
+        assert_eq!(offered.answer(), answer, "{}", offered);
+        assert!(offered.is_valid_answer(answer), "{}", offered);
+        assert_eq!(offered.to_string().parse::<SetupRole>()?, offered);
+    }
+
+    assert!(SetupRole::ActPass.is_valid_answer(SetupRole::Passive));
+    assert!(!SetupRole::ActPass.is_valid_answer(SetupRole::ActPass));
+    assert!(!SetupRole::Active.is_valid_answer(SetupRole::Active));
+    assert!(SetupRole::Active.is_dtls_client());
+    assert!(!SetupRole::Passive.is_dtls_client());
+    assert!("both".parse::<SetupRole>().is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_fingerprint_inheritance() -> Result<()> {
+    let mut sdp = SDP::unmarshal(SDPSTR.as_bytes())?;
+    let expected = Fingerprint::unmarshal(SHA256_FINGERPRINT)?;
+
+    let audio = &sdp.media_descriptions[0];
+    assert_eq!(sdp.media_fingerprints(audio)?, vec![expected.clone()]);
+    assert_eq!(sdp.media_setup_role(audio)?, Some(SetupRole::ActPass));
+
+    // media sections without their own attributes inherit the session ones
+    let session_fingerprint = Fingerprint::new(HashAlgorithm::Sha1, &[0xab; 20])?;
+    sdp.session.attributes.push(session_fingerprint.convert());
+    sdp.session.attributes.push(SetupRole::Passive.convert());
+    for media in sdp.media_descriptions.iter_mut() {
+        media
+            .attributes
+            .retain(|a| a.key != ATTR_KEY_FINGERPRINT && a.key != ATTR_KEY_SETUP);
+    }
+    sdp.media_descriptions[1] = sdp.media_descriptions[1]
+        .clone()
+        .with_dtls_fingerprint(&expected)
+        .with_setup_role(SetupRole::Active);
+
+    let audio = &sdp.media_descriptions[0];
+    assert_eq!(sdp.media_fingerprints(audio)?, vec![session_fingerprint]);
+    assert_eq!(sdp.media_setup_role(audio)?, Some(SetupRole::Passive));
+
+    let video = &sdp.media_descriptions[1];
+    assert_eq!(sdp.media_fingerprints(video)?, vec![expected]);
+    assert_eq!(sdp.media_setup_role(video)?, Some(SetupRole::Active));
+
+    Ok(())
+}
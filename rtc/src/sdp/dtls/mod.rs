@@ -0,0 +1,351 @@
+#[cfg(test)]
+mod dtls_test;
+
+use super::common::Attribute;
+use super::error::{Error, Result};
+use super::media::MediaDescription;
+use super::session::Session;
+use super::SDP;
+
+use std::fmt;
+use std::str::FromStr;
+
+pub const ATTR_KEY_FINGERPRINT: &str = "fingerprint";
+pub const ATTR_KEY_SETUP: &str = "setup";
+
+/// HashAlgorithm is the hash function of a certificate fingerprint
+/// <https://tools.ietf.org/html/rfc8122#section-5>
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HashAlgorithm {
+    Sha1,
+    Sha224,
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+const HASH_ALGORITHM_SHA1_STR: &str = "sha-1";
+const HASH_ALGORITHM_SHA224_STR: &str = "sha-224";
+const HASH_ALGORITHM_SHA256_STR: &str = "sha-256";
+const HASH_ALGORITHM_SHA384_STR: &str = "sha-384";
+const HASH_ALGORITHM_SHA512_STR: &str = "sha-512";
+
+impl fmt::Display for HashAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            HashAlgorithm::Sha1 => HASH_ALGORITHM_SHA1_STR,
+            HashAlgorithm::Sha224 => HASH_ALGORITHM_SHA224_STR,
+            HashAlgorithm::Sha256 => HASH_ALGORITHM_SHA256_STR,
+            HashAlgorithm::Sha384 => HASH_ALGORITHM_SHA384_STR,
+            HashAlgorithm::Sha512 => HASH_ALGORITHM_SHA512_STR,
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl FromStr for HashAlgorithm {
+    type Err = Error;
+
+    /// hash function names are case-insensitive
+    fn from_str(raw: &str) -> Result<Self> {
+        match raw.to_ascii_lowercase().as_str() {
+            HASH_ALGORITHM_SHA1_STR => Ok(HashAlgorithm::Sha1),
+            HASH_ALGORITHM_SHA224_STR => Ok(HashAlgorithm::Sha224),
+            HASH_ALGORITHM_SHA256_STR => Ok(HashAlgorithm::Sha256),
+            HASH_ALGORITHM_SHA384_STR => Ok(HashAlgorithm::Sha384),
+            HASH_ALGORITHM_SHA512_STR => Ok(HashAlgorithm::Sha512),
+            _ => Err(Error::ParseFingerprint(format!(
+                "unsupported hash function {}",
+                raw
+            ))),
+        }
+    }
+}
+
+impl HashAlgorithm {
+    /// digest_len returns the digest size in bytes
+    pub fn digest_len(&self) -> usize {
+        match self {
+            HashAlgorithm::Sha1 => 20,
+            HashAlgorithm::Sha224 => 28,
+            HashAlgorithm::Sha256 => 32,
+            HashAlgorithm::Sha384 => 48,
+            HashAlgorithm::Sha512 => 64,
+        }
+    }
+}
+
+/// Fingerprint is the structured form of an "a=fingerprint" attribute
+///
+/// `fingerprint:<hash-func> <XX:XX:...:XX>`
+///
+/// <https://tools.ietf.org/html/rfc8122#section-5>
+///
+/// Fingerprints compare in constant time so that checking the certificate
+/// of a peer does not leak how much of the digest matched.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Fingerprint {
+    pub algorithm: HashAlgorithm,
+    pub digest: Vec<u8>,
+}
+
+/// Renders the value of the "a=fingerprint" attribute with upper case hex.
+impl fmt::Display for Fingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ", self.algorithm)?;
+        for (i, b) in self.digest.iter().enumerate() {
+            if i > 0 {
+                write!(f, ":")?;
+            }
+            write!(f, "{:02X}", b)?;
+        }
+        Ok(())
+    }
+}
+
+impl PartialEq for Fingerprint {
+    fn eq(&self, other: &Self) -> bool {
+        self.algorithm == other.algorithm && self.verify(&other.digest)
+    }
+}
+
+impl Eq for Fingerprint {}
+
+impl FromStr for Fingerprint {
+    type Err = Error;
+
+    fn from_str(raw: &str) -> Result<Self> {
+        Fingerprint::unmarshal(raw)
+    }
+}
+
+impl Fingerprint {
+    /// new checks that the digest has the size produced by the algorithm
+    pub fn new(algorithm: HashAlgorithm, digest: &[u8]) -> Result<Self> {
+        if digest.len() != algorithm.digest_len() {
+            return Err(Error::ParseFingerprint(format!(
+                "{} digest of {} bytes",
+                algorithm,
+                digest.len()
+            )));
+        }
+        Ok(Fingerprint {
+            algorithm,
+            digest: digest.to_vec(),
+        })
+    }
+
+    /// unmarshal parses a fingerprint, with or without the "a=fingerprint:"
+    /// or "fingerprint:" prefix
+    pub fn unmarshal(raw: &str) -> Result<Self> {
+        let value = raw.trim();
+        let value = value.strip_prefix("a=").unwrap_or(value);
+        let value = value.strip_prefix("fingerprint:").unwrap_or(value);
+
+        let fields: Vec<&str> = value.split_whitespace().collect();
+        if fields.len() != 2 {
+            return Err(Error::ParseFingerprint(raw.to_owned()));
+        }
+
+        let algorithm = fields[0].parse::<HashAlgorithm>()?;
+        let digest = fields[1]
+            .split(':')
+            .map(|hex| {
+                if hex.len() != 2 {
+                    return Err(Error::ParseFingerprint(format!(
+                        "invalid digest {}",
+                        fields[1]
+                    )));
+                }
+                u8::from_str_radix(hex, 16)
+                    .map_err(|_| Error::ParseFingerprint(format!("invalid digest {}", fields[1])))
+            })
+            .collect::<Result<Vec<u8>>>()?;
+
+        Fingerprint::new(algorithm, &digest)
+    }
+
+    /// marshal renders the fingerprint as "fingerprint:..." attribute line
+    /// value
+    pub fn marshal(&self) -> String {
+        format!("{}:{}", ATTR_KEY_FINGERPRINT, self)
+    }
+
+    /// converts this object to an Attribute
+    pub fn convert(&self) -> Attribute {
+        Attribute {
+            key: ATTR_KEY_FINGERPRINT.to_owned(),
+            value: Some(self.to_string()),
+        }
+    }
+
+    /// verify compares a certificate digest with this fingerprint in constant
+    /// time
+    pub fn verify(&self, digest: &[u8]) -> bool {
+        if digest.len() != self.digest.len() {
+            return false;
+        }
+        let diff = self
+            .digest
+            .iter()
+            .zip(digest)
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b));
+        diff == 0
+    }
+}
+
+/// SetupRole is the value of an "a=setup" attribute, it decides which
+/// endpoint opens the connection and thereby acts as DTLS client
+/// <https://tools.ietf.org/html/rfc4145#section-4>
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SetupRole {
+    Active,
+    Passive,
+    ActPass,
+    HoldConn,
+}
+
+const SETUP_ROLE_ACTIVE_STR: &str = "active";
+const SETUP_ROLE_PASSIVE_STR: &str = "passive";
+const SETUP_ROLE_ACTPASS_STR: &str = "actpass";
+const SETUP_ROLE_HOLDCONN_STR: &str = "holdconn";
+
+impl fmt::Display for SetupRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            SetupRole::Active => SETUP_ROLE_ACTIVE_STR,
+            SetupRole::Passive => SETUP_ROLE_PASSIVE_STR,
+            SetupRole::ActPass => SETUP_ROLE_ACTPASS_STR,
+            SetupRole::HoldConn => SETUP_ROLE_HOLDCONN_STR,
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl FromStr for SetupRole {
+    type Err = Error;
+
+    fn from_str(raw: &str) -> Result<Self> {
+        match raw {
+            SETUP_ROLE_ACTIVE_STR => Ok(SetupRole::Active),
+            SETUP_ROLE_PASSIVE_STR => Ok(SetupRole::Passive),
+            SETUP_ROLE_ACTPASS_STR => Ok(SetupRole::ActPass),
+            SETUP_ROLE_HOLDCONN_STR => Ok(SetupRole::HoldConn),
+            _ => Err(Error::SdpInvalidValue(raw.to_owned())),
+        }
+    }
+}
+
+impl SetupRole {
+    /// answer returns the role of an answerer to this offered role. An
+    /// "actpass" offer is answered with "active" so that the answerer starts
+    /// the handshake without waiting for the answer to be delivered.
+    /// <https://tools.ietf.org/html/rfc8842#section-5.3>
+    pub fn answer(&self) -> SetupRole {
+        match self {
+            SetupRole::Active => SetupRole::Passive,
+            SetupRole::Passive | SetupRole::ActPass => SetupRole::Active,
+            SetupRole::HoldConn => SetupRole::HoldConn,
+        }
+    }
+
+    /// is_valid_answer checks the role chosen by the answerer against the
+    /// offered one
+    pub fn is_valid_answer(&self, answer: SetupRole) -> bool {
+        match self {
+            SetupRole::ActPass => answer == SetupRole::Active || answer == SetupRole::Passive,
+            offered => answer == offered.answer(),
+        }
+    }
+
+    /// is_dtls_client returns true if the endpoint with this resolved role
+    /// sends the DTLS ClientHello
+    pub fn is_dtls_client(&self) -> bool {
+        *self == SetupRole::Active
+    }
+
+    /// converts this object to an Attribute
+    pub fn convert(&self) -> Attribute {
+        Attribute {
+            key: ATTR_KEY_SETUP.to_owned(),
+            value: Some(self.to_string()),
+        }
+    }
+}
+
+fn fingerprints_of(attributes: &[Attribute]) -> Result<Vec<Fingerprint>> {
+    attributes
+        .iter()
+        .filter(|a| a.key == ATTR_KEY_FINGERPRINT)
+        .map(|a| Fingerprint::unmarshal(a.value.as_deref().unwrap_or_default()))
+        .collect()
+}
+
+fn setup_role_of(attributes: &[Attribute]) -> Result<Option<SetupRole>> {
+    attributes
+        .iter()
+        .find(|a| a.key == ATTR_KEY_SETUP)
+        .map(|a| a.value.as_deref().unwrap_or_default().parse())
+        .transpose()
+}
+
+impl Session {
+    /// fingerprints parses the session-level "a=fingerprint" attributes
+    pub fn fingerprints(&self) -> Result<Vec<Fingerprint>> {
+        fingerprints_of(&self.attributes)
+    }
+
+    /// setup_role parses the session-level "a=setup" attribute
+    pub fn setup_role(&self) -> Result<Option<SetupRole>> {
+        setup_role_of(&self.attributes)
+    }
+}
+
+impl MediaDescription {
+    /// fingerprints parses the media-level "a=fingerprint" attributes
+    pub fn fingerprints(&self) -> Result<Vec<Fingerprint>> {
+        fingerprints_of(&self.attributes)
+    }
+
+    /// setup_role parses the media-level "a=setup" attribute
+    pub fn setup_role(&self) -> Result<Option<SetupRole>> {
+        setup_role_of(&self.attributes)
+    }
+
+    /// with_dtls_fingerprint adds an "a=fingerprint" attribute
+    pub fn with_dtls_fingerprint(mut self, fingerprint: &Fingerprint) -> Self {
+        self.attributes.push(fingerprint.convert());
+        self
+    }
+
+    /// with_setup_role adds an "a=setup" attribute
+    pub fn with_setup_role(mut self, role: SetupRole) -> Self {
+        self.attributes.push(role.convert());
+        self
+    }
+}
+
+impl SDP {
+    /// media_fingerprints returns the fingerprints that apply to a media
+    /// section: its own, or the session-level ones if it has none
+    /// <https://tools.ietf.org/html/rfc8122#section-5>
+    pub fn media_fingerprints(&self, media: &MediaDescription) -> Result<Vec<Fingerprint>> {
+        let fingerprints = media.fingerprints()?;
+        if fingerprints.is_empty() {
+            return self.session.fingerprints();
+        }
+        Ok(fingerprints)
+    }
+
+    /// media_setup_role returns the setup role that applies to a media
+    /// section: its own, or the session-level one if it has none
+    pub fn media_setup_role(&self, media: &MediaDescription) -> Result<Option<SetupRole>> {
+        match media.setup_role()? {
+            Some(role) => Ok(Some(role)),
+            None => self.session.setup_role(),
+        }
+    }
+}
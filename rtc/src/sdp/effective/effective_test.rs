@@ -0,0 +1,95 @@
+use super::*;
+use crate::sdp::extmap::{AUDIO_LEVEL_EXT, SDES_MID_EXT, TRANSPORT_CC_EXT};
+use crate::sdp::sdp_test::SDPSTR;
+
+const INHERITING_SDP: &str = "v=0\r\n\
+o=- 1 1 IN IP4 127.0.0.1\r\n\
+s=-\r\n\
+c=IN IP4 203.0.113.1\r\n\
+t=0 0\r\n\
+a=ice-ufrag:session\r\n\
+a=ice-pwd:sessionpasswordsessionpassword\r\n\
+a=fingerprint:sha-1 4A:AD:B9:B1:3F:82:18:3B:54:02:12:DF:3E:5D:49:6B:19:E5:7C:AB\r\n\
+a=setup:actpass\r\n\
+a=recvonly\r\n\
+a=extmap:1 urn:ietf:params:rtp-hdrext:ssrc-audio-level\r\n\
+a=extmap:3 urn:ietf:params:rtp-hdrext:sdes:mid\r\n\
+m=audio 49170 RTP/AVP 0\r\n\
+m=video 51372 RTP/AVP 99\r\n\
+c=IN IP4 203.0.113.2\r\n\
+a=ice-ufrag:media\r\n\
+a=setup:active\r\n\
+a=sendonly\r\n\
+a=extmap:3 http://www.ietf.org/id/draft-holmer-rmcat-transport-wide-cc-extensions-01\r\n\
+a=extmap:2 urn:ietf:params:rtp-hdrext:sdes:mid\r\n";
+
+#[test]
+fn test_effective_media_inherits_session() -> Result<()> {
+    let sdp = SDP::unmarshal(INHERITING_SDP.as_bytes())?;
+
+    let audio = sdp.effective_media(0)?;
+    assert_eq!(
+        audio.connection_information.map(|c| c.to_string()),
+        Some("IN IP4 203.0.113.1".to_owned())
+    );
+    assert_eq!(audio.ice_ufrag, Some("session"));
+    assert_eq!(audio.ice_pwd, Some("sessionpasswordsessionpassword"));
+    assert_eq!(audio.fingerprints.len(), 1);
+    assert_eq!(audio.setup_role, Some(SetupRole::ActPass));
+    assert_eq!(audio.direction, Direction::RecvOnly);
+    let uris: Vec<(isize, &str)> = audio.extmaps.iter().map(|e| (e.value, e.uri())).collect();
+    assert_eq!(uris, vec![(1, AUDIO_LEVEL_EXT), (3, SDES_MID_EXT)]);
+
+    let video = sdp.effective_media(1)?;
+    assert_eq!(
+        video.connection_information.map(|c| c.to_string()),
+        Some("IN IP4 203.0.113.2".to_owned())
+    );
+    assert_eq!(video.ice_ufrag, Some("media"));
+    assert_eq!(video.ice_pwd, Some("sessionpasswordsessionpassword"));
+    assert_eq!(video.setup_role, Some(SetupRole::Active));
+    assert_eq!(video.direction, Direction::SendOnly);
+    let uris: Vec<(isize, &str)> = video.extmaps.iter().map(|e| (e.value, e.uri())).collect();
+    assert_eq!(
+        uris,
+        vec![
+            (1, AUDIO_LEVEL_EXT),
+            (2, SDES_MID_EXT),
+            (3, TRANSPORT_CC_EXT)
+        ]
+    );
+
+    assert_eq!(sdp.effective_media(2), Err(Error::MediaIndexOutOfRange(2)));
+
+    Ok(())
+}
+
+#[test]
+fn test_effective_media_without_session_defaults() -> Result<()> {
+    let sdp = SDP::unmarshal(SDPSTR.as_bytes())?;
+    let effective = sdp.effective_media_descriptions()?;
+    assert_eq!(effective.len(), 3);
+
+    for (media, effective) in sdp.media_descriptions.iter().zip(&effective) {
+        assert_eq!(effective.media, media);
+        assert_eq!(
+            effective.connection_information,
+            media.connection_information.as_ref()
+        );
+        assert_eq!(effective.ice_ufrag, Some("AOV8"));
+        assert_eq!(effective.direction, Direction::SendRecv);
+        assert_eq!(effective.extmaps.len(), media.extmaps()?.len());
+    }
+
+    // without any direction attribute the media is sendrecv
+    let sdp = SDP::unmarshal(
+        b"v=0\r\no=- 1 1 IN IP4 127.0.0.1\r\ns=-\r\nt=0 0\r\nm=audio 9 RTP/AVP 0\r\n",
+    )?;
+    let audio = sdp.effective_media(0)?;
+    assert_eq!(audio.direction, Direction::SendRecv);
+    assert_eq!(audio.connection_information, None);
+    assert_eq!(audio.ice_ufrag, None);
+    assert!(audio.fingerprints.is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,109 @@
+#[cfg(test)]
+mod effective_test;
+
+use super::common::ConnectionInformation;
+use super::direction::Direction;
+use super::dtls::{Fingerprint, SetupRole};
+use super::error::{Error, Result};
+use super::extmap::{extmaps_of, ExtMap};
+use super::media::{MediaDescription, ATTR_KEY_ICE_PWD, ATTR_KEY_ICE_UFRAG};
+use super::session::Session;
+use super::SDP;
+
+/// EffectiveMedia is the view of a media section after applying the
+/// session-level defaults it inherits
+/// <https://tools.ietf.org/html/rfc8866#section-5>
+///
+/// - `c=`: the media-level line, else the session-level one (section 5.7)
+/// - `a=ice-ufrag`, `a=ice-pwd`: media-level, else session-level
+///   (RFC 8839 section 5.4)
+/// - `a=fingerprint`, `a=setup`: media-level, else session-level
+///   (RFC 8122 section 5, RFC 4145 section 4)
+/// - direction: media-level, else session-level, else sendrecv
+///   (section 6.7)
+/// - `a=extmap`: the session-level extmaps apply to every media section,
+///   a media-level extmap with the same id replaces the session-level one
+///   (RFC 8285 section 5), the result is ordered by id
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EffectiveMedia<'a> {
+    pub media: &'a MediaDescription,
+    pub connection_information: Option<&'a ConnectionInformation>,
+    pub ice_ufrag: Option<&'a str>,
+    pub ice_pwd: Option<&'a str>,
+    pub fingerprints: Vec<Fingerprint>,
+    pub setup_role: Option<SetupRole>,
+    pub direction: Direction,
+    pub extmaps: Vec<ExtMap>,
+}
+
+impl SDP {
+    /// effective_media resolves the media section at `index` against the
+    /// session-level attributes, see [`EffectiveMedia`]
+    pub fn effective_media(&self, index: usize) -> Result<EffectiveMedia<'_>> {
+        let media = self
+            .media_descriptions
+            .get(index)
+            .ok_or(Error::MediaIndexOutOfRange(index))?;
+        let session = &self.session;
+
+        let connection_information = media
+            .connection_information
+            .as_ref()
+            .or(session.connection_information.as_ref());
+
+        let ice_ufrag = inherited_attribute(session, media, ATTR_KEY_ICE_UFRAG);
+        let ice_pwd = inherited_attribute(session, media, ATTR_KEY_ICE_PWD);
+        let direction = self.media_direction(media);
+
+        let mut extmaps = session.extmaps()?;
+        let media_extmaps = media.extmaps()?;
+        extmaps.retain(|e| !media_extmaps.iter().any(|m| m.value == e.value));
+        extmaps.extend(media_extmaps);
+        extmaps.sort_by_key(|e| e.value);
+
+        Ok(EffectiveMedia {
+            media,
+            connection_information,
+            ice_ufrag,
+            ice_pwd,
+            fingerprints: self.media_fingerprints(media)?,
+            setup_role: self.media_setup_role(media)?,
+            direction,
+            extmaps,
+        })
+    }
+
+    /// effective_media_descriptions resolves every media section
+    pub fn effective_media_descriptions(&self) -> Result<Vec<EffectiveMedia<'_>>> {
+        (0..self.media_descriptions.len())
+            .map(|index| self.effective_media(index))
+            .collect()
+    }
+}
+
+impl Session {
+    /// extmaps parses the session-level "a=extmap" attributes
+    pub fn extmaps(&self) -> Result<Vec<ExtMap>> {
+        extmaps_of(&self.attributes)
+    }
+}
+
+impl MediaDescription {
+    /// extmaps parses the media-level "a=extmap" attributes
+    pub fn extmaps(&self) -> Result<Vec<ExtMap>> {
+        extmaps_of(&self.attributes)
+    }
+}
+
+/// inherited_attribute returns the value of the media-level attribute, else
+/// the one of the session-level attribute
+pub(crate) fn inherited_attribute<'a>(
+    session: &'a Session,
+    media: &'a MediaDescription,
+    key: &str,
+) -> Option<&'a str> {
+    media
+        .attribute(key)
+        .or_else(|| session.attribute(key))
+        .flatten()
+}
@@ -9,6 +9,7 @@ pub type Result<T> = std::result::Result<T, Error>;
 
 #[derive(Debug, Error, PartialEq)]
 #[non_exhaustive]
+#[allow(clippy::enum_variant_names)]
 pub enum Error {
     #[error("codec not found")]
     CodecNotFound,
@@ -34,8 +35,56 @@ pub enum Error {
     ParseUrl(#[from] url::ParseError),
     #[error("parse extmap: {0}")]
     ParseExtMap(String),
-    #[error("{} --> {} <-- {}", .s.substring(0,*.p), .s.substring(*.p, *.p+1), .s.substring(*.p+1, .s.len()))]
-    SyntaxError { s: String, p: usize },
+    #[error("parse ice candidate: {0}")]
+    ParseIceCandidate(String),
+    #[error("parse rid: {0}")]
+    ParseRid(String),
+    #[error("parse simulcast: {0}")]
+    ParseSimulcast(String),
+    #[error("simulcast rid {0} has no matching a=rid")]
+    SimulcastRidNotFound(String),
+    #[error("parse group: {0}")]
+    ParseGroup(String),
+    #[error("mid {0} not found")]
+    MidNotFound(String),
+    #[error("duplicate mid {0}")]
+    DuplicateMid(String),
+    #[error("parse fingerprint: {0}")]
+    ParseFingerprint(String),
+    #[error("media description {0} out of range")]
+    MediaIndexOutOfRange(usize),
+    #[error("payload type {0} is not listed in the m-line")]
+    UnlistedPayloadType(u8),
+    #[error("non-static payload type {0} has no rtpmap")]
+    FormatWithoutRtpmap(u8),
+    #[error("rtx payload type {0} has no associated payload type")]
+    RtxWithoutAssociatedPayloadType(u8),
+    #[error("duplicate extmap id {0}")]
+    DuplicateExtMapId(isize),
+    #[error("extmap id {0} out of range")]
+    ExtMapIdOutOfRange(isize),
+    #[error("invalid ice credentials {0}")]
+    InvalidIceCredentials(String),
+    #[error("failed to parse sctp attribute {0}")]
+    ParseSctp(String),
+    #[error("failed to parse ssrc attribute {0}")]
+    ParseSsrc(String),
+    #[error("failed to parse msid {0}")]
+    ParseMsid(String),
+    #[error("invalid direction {0}")]
+    ParseDirection(String),
+    #[error("no free extmap id left")]
+    ExtMapIdsExhausted,
+    /// SyntaxError locates an error in the parsed SDP: `line` is the 1-based
+    /// line number, `s` the text of the line and `p` the byte offset of the
+    /// offending token within it.
+    #[error("line {}: {} --> {} <-- {}: {}", .line, .s.substring(0,*.p), .s.substring(*.p, *.p+1), .s.substring(*.p+1, .s.len()), .source)]
+    SyntaxError {
+        line: usize,
+        s: String,
+        p: usize,
+        source: Box<Error>,
+    },
 }
 
 #[derive(Debug, Error)]
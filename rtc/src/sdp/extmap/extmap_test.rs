@@ -1,6 +1,7 @@
 use super::*;
 //pub(crate) const END_LINE: &str = "\r\n";
 use super::super::lexer::END_LINE;
+use super::super::media::{MediaDescription, TRANSPORT_CC_EXT_VALUE};
 
 use std::io::BufReader;
 use std::iter::Iterator;
@@ -20,11 +21,11 @@ fn test_extmap() -> Result<()> {
         format!("{}{}{}", "a=", FAILING_ATTR_EXTMAP1, END_LINE);
     let failing_attr_extmap2_line =
         format!("{}{}{}", "a=", FAILING_ATTR_EXTMAP2, END_LINE);
-    let passingtests = vec![
+    let passingtests = [
         (EXAMPLE_ATTR_EXTMAP1, example_attr_extmap1_line),
         (EXAMPLE_ATTR_EXTMAP2, example_attr_extmap2_line),
     ];
-    let failingtests = vec![
+    let failingtests = [
         (FAILING_ATTR_EXTMAP1, failing_attr_extmap1_line),
         (FAILING_ATTR_EXTMAP2, failing_attr_extmap2_line),
     ];
@@ -61,11 +62,12 @@ fn test_transport_cc_extmap() -> Result<()> {
         uri_idx,
         direction: Direction::Unspecified,
         ext_attr: None,
+        ..Default::default()
     };
 
     let s = e.marshal();
     if s == "3 http://www.ietf.org/id/draft-holmer-rmcat-transport-wide-cc-extensions-01" {
-        assert!(false, "TestTransportCC failed");
+        panic!("TestTransportCC failed");
     } else {
         assert_eq!(
             s,
@@ -75,3 +77,225 @@ fn test_transport_cc_extmap() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_extmap_unknown_uri_round_trip() -> Result<()> {
+    let tests = [
+        "extmap:12 https://aomediacodec.github.io/av1-rtp-spec/#dependency-descriptor-rtp-header-extension",
+        "extmap:13 http://www.webrtc.org/experiments/rtp-hdrext/abs-capture-time",
+        "extmap:14/recvonly http://www.webrtc.org/experiments/rtp-hdrext/video-layers-allocation00",
+        "extmap:15 urn:example:unregistered attr",
+    ];
+
+    for (i, u) in tests.iter().enumerate() {
+        let mut reader = BufReader::new(u.as_bytes());
+        let actual = ExtMap::unmarshal(&mut reader)?;
+        assert_eq!(actual.uri_idx, ExtIdxNone, "{}: {}", i, u);
+        assert_eq!(*u, actual.marshal(), "{}: {}", i, u);
+        assert_eq!(
+            format!("a={}{}", actual.marshal(), END_LINE),
+            format!("a={}", actual.convert()) + END_LINE,
+        );
+    }
+
+    let known = ExtMap::new(3, TRANSPORT_CC_EXT);
+    assert_eq!(known.uri_idx, TRANSPORT_CC_EXT_IDX);
+    assert_eq!(known.uri, None);
+    assert_eq!(known.uri(), TRANSPORT_CC_EXT);
+
+    Ok(())
+}
+
+#[test]
+fn test_register_ext_uri() -> Result<()> {
+    const CUSTOM_EXT: &str = "urn:example:params:rtp-hdrext:registered";
+
+    let line = format!("extmap:7 {}", CUSTOM_EXT);
+    assert_eq!(get_idx_by_ext_uri(CUSTOM_EXT), ExtIdxNone);
+    let before = ExtMap::unmarshal(&mut BufReader::new(line.as_bytes()))?;
+    assert_eq!(before.uri.as_deref(), Some(CUSTOM_EXT));
+
+    let idx = register_ext_uri(CUSTOM_EXT);
+    assert!(idx > TOFFSET_EXT_IDX);
+    assert_eq!(register_ext_uri(CUSTOM_EXT), idx);
+    assert_eq!(get_idx_by_ext_uri(CUSTOM_EXT), idx);
+    assert_eq!(get_ext_uri_by_idx(idx), CUSTOM_EXT);
+
+    // built-in URIs keep their index
+    assert_eq!(register_ext_uri(SDES_MID_EXT), SDES_MID_EXT_IDX);
+
+    let mut reader = BufReader::new(line.as_bytes());
+    let e = ExtMap::unmarshal(&mut reader)?;
+    assert_eq!(e.uri_idx, idx);
+    assert_eq!(e.uri, None);
+    assert_eq!(e.marshal(), line);
+
+    // the same line compares equal however the URI is stored
+    assert_eq!(e, before);
+    assert_ne!(e, ExtMap::new(7, SDES_MID_EXT));
+
+    Ok(())
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_extmap_serde_uses_uri() -> Result<()> {
+    const CUSTOM_EXT: &str = "urn:example:params:rtp-hdrext:serde";
+
+    let idx = register_ext_uri(CUSTOM_EXT);
+    let line = format!("extmap:9/recvonly {} attr", CUSTOM_EXT);
+    let e = ExtMap::unmarshal(&mut BufReader::new(line.as_bytes()))?;
+    assert_eq!(e.uri_idx, idx);
+
+    let json = serde_json::to_string(&e).unwrap();
+    assert!(json.contains(CUSTOM_EXT));
+    assert!(!json.contains("uri_idx"));
+
+    let parsed: ExtMap = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed.uri_idx, idx);
+    assert_eq!(parsed.uri, None);
+    assert_eq!(parsed, e);
+    assert_eq!(parsed.marshal(), line);
+
+    // the index is resolved from the URI when deserializing
+    let json = json.replace(CUSTOM_EXT, TOFFSET_EXT);
+    let parsed: ExtMap = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed.uri_idx, TOFFSET_EXT_IDX);
+
+    Ok(())
+}
+
+#[test]
+fn test_allocate_ext_map_id() {
+    assert_eq!(allocate_ext_map_id(&[], false), Some(1));
+    assert_eq!(allocate_ext_map_id(&[1, 2, 4], false), Some(3));
+
+    let one_byte: Vec<isize> = ONE_BYTE_EXT_MAP_IDS.collect();
+    assert_eq!(allocate_ext_map_id(&one_byte, false), None);
+    assert_eq!(allocate_ext_map_id(&one_byte, true), Some(15));
+
+    let all: Vec<isize> = ONE_BYTE_EXT_MAP_IDS.chain(TWO_BYTE_EXT_MAP_IDS).collect();
+    assert_eq!(allocate_ext_map_id(&all, true), None);
+}
+
+#[test]
+fn test_ext_map_negotiator_answer() {
+    let negotiator = ExtMapNegotiator::new()
+        .with_extension(SDES_MID_EXT, Direction::Unspecified)
+        .with_extension(AUDIO_LEVEL_EXT, Direction::Unspecified)
+        .with_extension(ABS_SEND_TIME_EXT, Direction::RecvOnly)
+        .with_extension(VIDEO_TIMING_EXT, Direction::SendOnly);
+
+    let mut timing = ExtMap::new(7, VIDEO_TIMING_EXT);
+    timing.direction = Direction::SendOnly;
+    let mut abs_send_time = ExtMap::new(12, ABS_SEND_TIME_EXT);
+    abs_send_time.direction = Direction::SendRecv;
+    let mut audio_level = ExtMap::new(1, AUDIO_LEVEL_EXT);
+    audio_level.ext_attr = Some("vad=on".to_owned());
+    let offered = [
+        ExtMap::new(4, SDES_MID_EXT),
+        ExtMap::new(2, TRANSPORT_CC_EXT),
+        audio_level,
+        abs_send_time,
+        timing,
+    ];
+
+    let answered: Vec<String> = negotiator
+        .answer(&offered)
+        .iter()
+        .map(|e| e.to_string())
+        .collect();
+    assert_eq!(
+        answered,
+        vec![
+            format!("4 {}", SDES_MID_EXT),
+            format!("1 {}", AUDIO_LEVEL_EXT),
+            // restricted to the local direction
+            format!("12/recvonly {}", ABS_SEND_TIME_EXT),
+            // the offerer sends, the answerer only wants to send: inactive
+        ]
+    );
+}
+
+#[test]
+fn test_ext_map_negotiator_offer() -> Result<()> {
+    let negotiator = ExtMapNegotiator::new()
+        .with_extension(SDES_MID_EXT, Direction::Unspecified)
+        .with_extension(TRANSPORT_CC_EXT, Direction::Unspecified)
+        .with_extension("urn:example:negotiator-offer", Direction::SendOnly);
+
+    let offer = negotiator.offer(&[])?;
+    let ids: Vec<(isize, &str)> = offer.iter().map(|e| (e.value, e.uri())).collect();
+    assert_eq!(
+        ids,
+        vec![
+            (1, SDES_MID_EXT),
+            (2, TRANSPORT_CC_EXT),
+            (3, "urn:example:negotiator-offer"),
+        ]
+    );
+    assert_eq!(offer[2].direction, Direction::SendOnly);
+    assert_ne!(get_idx_by_ext_uri("urn:example:negotiator-offer"), ExtIdxNone);
+
+    // ids negotiated before are kept, ids of dropped extensions not reused
+    let previous = [
+        ExtMap::new(9, TRANSPORT_CC_EXT),
+        ExtMap::new(1, TOFFSET_EXT),
+        ExtMap::new(2, VIDEO_TIMING_EXT),
+    ];
+    let offer = negotiator.offer(&previous)?;
+    let ids: Vec<isize> = offer.iter().map(|e| e.value).collect();
+    assert_eq!(ids, vec![3, 9, 4]);
+
+    // without extmap-allow-mixed only the 14 one-byte ids are available
+    let crowded: Vec<ExtMap> = ONE_BYTE_EXT_MAP_IDS
+        .map(|id| ExtMap::new(id, &format!("urn:example:crowded:{}", id)))
+        .collect();
+    assert_eq!(negotiator.offer(&crowded), Err(Error::ExtMapIdsExhausted));
+    let offer = negotiator.with_allow_mixed(true).offer(&crowded)?;
+    let ids: Vec<isize> = offer.iter().map(|e| e.value).collect();
+    assert_eq!(ids, vec![15, 16, 17]);
+
+    Ok(())
+}
+
+#[test]
+fn test_try_with_transport_cc_extmap_allocates_free_id() -> Result<()> {
+    let md = MediaDescription::default().try_with_transport_cc_extmap()?;
+    assert_eq!(md.extmaps()?[0].value, TRANSPORT_CC_EXT_VALUE);
+
+    let md = MediaDescription::default()
+        .with_extmap(ExtMap::new(3, SDES_MID_EXT))
+        .with_extmap(ExtMap::new(1, AUDIO_LEVEL_EXT))
+        .try_with_transport_cc_extmap()?;
+    let transport_cc = &md.extmaps()?[2];
+    assert_eq!(transport_cc.uri_idx, TRANSPORT_CC_EXT_IDX);
+    assert_eq!(transport_cc.value, 2);
+
+    // the infallible form keeps id 3
+    let md = MediaDescription::default()
+        .with_extmap(ExtMap::new(3, SDES_MID_EXT))
+        .with_transport_cc_extmap();
+    assert_eq!(md.extmaps()?[1].value, TRANSPORT_CC_EXT_VALUE);
+    assert_eq!(md.extmaps()?[1].uri(), TRANSPORT_CC_EXT);
+
+    // two-byte ids need "a=extmap-allow-mixed"
+    let full = ONE_BYTE_EXT_MAP_IDS.fold(MediaDescription::default(), |md, id| {
+        md.with_extmap(ExtMap::new(id, &format!("urn:example:ext{}", id)))
+    });
+    assert_eq!(
+        full.clone().try_with_transport_cc_extmap().err(),
+        Some(Error::ExtMapIdsExhausted)
+    );
+    let md = full
+        .with_property_attribute(ATTR_KEY_EXTMAP_ALLOW_MIXED.to_owned())
+        .try_with_transport_cc_extmap()?;
+    assert_eq!(md.extmaps()?.last().map(|e| e.value), Some(15));
+
+    // malformed extmaps are reported instead of being overwritten
+    let md = MediaDescription::default()
+        .with_value_attribute(ATTR_KEY_EXTMAP.to_owned(), "x urn:example".to_owned());
+    assert!(md.try_with_transport_cc_extmap().is_err());
+
+    Ok(())
+}
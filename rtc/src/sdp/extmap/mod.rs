@@ -7,8 +7,8 @@ use super::direction::*;
 
 use std::fmt;
 use std::io;
-use url::Url;
 use std::collections::HashMap;
+use std::sync::RwLock;
 
 type ExtIdx = u32;
 
@@ -18,6 +18,14 @@ pub const DEF_EXT_MAP_VALUE_TRANSPORT_CC: usize = 2;
 pub const DEF_EXT_MAP_VALUE_SDES_MID: usize = 3;
 pub const DEF_EXT_MAP_VALUE_SDES_RTP_STREAM_ID: usize = 4;
 
+pub const ATTR_KEY_EXTMAP: &str = "extmap";
+pub const ATTR_KEY_EXTMAP_ALLOW_MIXED: &str = "extmap-allow-mixed";
+
+/// extmap ids usable with the one-byte and the two-byte header
+/// <https://tools.ietf.org/html/rfc8285#section-5>
+pub const ONE_BYTE_EXT_MAP_IDS: std::ops::RangeInclusive<isize> = 1..=14;
+pub const TWO_BYTE_EXT_MAP_IDS: std::ops::RangeInclusive<isize> = 15..=255;
+
 pub const NONE_EXT: &str = "";
 pub const ABS_SEND_TIME_EXT: &str = "http://www.webrtc.org/experiments/rtp-hdrext/abs-send-time";
 pub const TRANSPORT_CC_EXT: &str = "http://www.ietf.org/id/draft-holmer-rmcat-transport-wide-cc-extensions-01";
@@ -32,6 +40,7 @@ pub const AUDIO_LEVEL_EXT: &str = "urn:ietf:params:rtp-hdrext:ssrc-audio-level";
 pub const VIDEO_ORIENTATION_EXT: &str = "urn:3gpp:video-orientation";
 pub const TOFFSET_EXT: &str = "urn:ietf:params:rtp-hdrext:toffset";
 
+#[allow(non_upper_case_globals)]
 pub const ExtIdxNone: ExtIdx = 0;
 pub const ABS_SEND_TIME_EXT_IDX: ExtIdx = 1;
 pub const TRANSPORT_CC_EXT_IDX: ExtIdx = 2;
@@ -46,26 +55,28 @@ pub const AUDIO_LEVEL_EXT_IDX: ExtIdx = 10;
 pub const VIDEO_ORIENTATION_EXT_IDX: ExtIdx = 11; 
 pub const TOFFSET_EXT_IDX: ExtIdx = 12;
 
+/// ExtUriRegistry maps header extension URIs to compact indexes. It is
+/// seeded with `ext_idx_url_map` and grows through `register_ext_uri`.
+struct ExtUriRegistry {
+    url_idx: HashMap<&'static str, ExtIdx>,
+    idx_url: Vec<&'static str>,
+}
+
 lazy_static! {
-    static ref ext_url_idx_map: HashMap<&'static str, ExtIdx> = {
-        let mut m = HashMap::new();
-        m.insert(ABS_SEND_TIME_EXT, ABS_SEND_TIME_EXT_IDX);
-        m.insert(TRANSPORT_CC_EXT, TRANSPORT_CC_EXT_IDX);
-        m.insert(PLAYOUT_DELAY_EXT, PLAYOUT_DELAY_EXT_IDX);
-        m.insert(VIDEO_CONTENT_TYPE_EXT, VIDEO_CONTENT_TYPE_EXT_IDX);
-        m.insert(VIDEO_TIMING_EXT, VIDEO_TIMING_EXT_IDX);
-        m.insert(COLOR_SPACE_EXT, COLOR_SPACE_EXT_IDX);
-        m.insert(SDES_MID_EXT, SDES_MID_EXT_IDX);
-        m.insert(SDES_RID_EXT, SDES_RID_EXT_IDX);
-        m.insert(SDES_RRID_EXT, SDES_RRID_EXT_IDX);
-        m.insert(AUDIO_LEVEL_EXT, AUDIO_LEVEL_EXT_IDX);
-        m.insert(VIDEO_ORIENTATION_EXT, VIDEO_ORIENTATION_EXT_IDX);
-        m.insert(TOFFSET_EXT, TOFFSET_EXT_IDX);
-        m
+    static ref ext_url_idx_map: RwLock<ExtUriRegistry> = {
+        let mut url_idx = HashMap::new();
+        for (idx, uri) in ext_idx_url_map.iter().enumerate().skip(1) {
+            url_idx.insert(*uri, idx as ExtIdx);
+        }
+        RwLock::new(ExtUriRegistry {
+            url_idx,
+            idx_url: ext_idx_url_map.to_vec(),
+        })
     };
 }
 
-pub const ext_idx_url_map: [&'static str;13] = [
+#[allow(non_upper_case_globals)]
+pub const ext_idx_url_map: [&str; 13] = [
     NONE_EXT,
     ABS_SEND_TIME_EXT,
     TRANSPORT_CC_EXT,
@@ -81,15 +92,70 @@ pub const ext_idx_url_map: [&'static str;13] = [
     TOFFSET_EXT,
 ];
 
-/// ExtMap represents the activation of a single RTP header extension
+/// ExtMap represents the activation of a single RTP header extension.
+/// It is serialized with its URI rather than the process local index.
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "ExtMapRepr", from = "ExtMapRepr")
+)]
 pub struct ExtMap {
     pub value: isize,
     pub direction: Direction,
+    /// index of a registered URI, ExtIdxNone if the URI is unknown
     pub uri_idx: ExtIdx,
+    /// the URI as received when it is not registered
+    pub uri: Option<String>,
     pub ext_attr: Option<String>,
 }
 
+/// Compares the resolved URI, an ExtMap parsed before its URI was
+/// registered equals one parsed after.
+impl PartialEq for ExtMap {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+            && self.direction == other.direction
+            && self.uri() == other.uri()
+            && self.ext_attr == other.ext_attr
+    }
+}
+
+impl Eq for ExtMap {}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct ExtMapRepr {
+    value: isize,
+    direction: Direction,
+    uri: String,
+    ext_attr: Option<String>,
+}
+
+#[cfg(feature = "serde")]
+impl From<ExtMap> for ExtMapRepr {
+    fn from(extmap: ExtMap) -> Self {
+        ExtMapRepr {
+            value: extmap.value,
+            direction: extmap.direction,
+            uri: extmap.uri().to_owned(),
+            ext_attr: extmap.ext_attr,
+        }
+    }
+}
+
+/// The index is resolved against the registry of the deserializing process.
+#[cfg(feature = "serde")]
+impl From<ExtMapRepr> for ExtMap {
+    fn from(repr: ExtMapRepr) -> Self {
+        ExtMap {
+            direction: repr.direction,
+            ext_attr: repr.ext_attr,
+            ..ExtMap::new(repr.value, &repr.uri)
+        }
+    }
+}
+
 impl fmt::Display for ExtMap {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let mut output = format!("{}", self.value);
@@ -97,8 +163,7 @@ impl fmt::Display for ExtMap {
             output += format!("/{}", self.direction).as_str();
         }
 
-        let uri_str = get_ext_uri_by_idx(self.uri_idx);
-        output += format!(" {}", uri_str).as_str();
+        output += format!(" {}", self.uri()).as_str();
 
         if let Some(ext_attr) = &self.ext_attr {
             output += format!(" {}", ext_attr).as_str();
@@ -109,10 +174,33 @@ impl fmt::Display for ExtMap {
 }
 
 impl ExtMap {
+    /// new creates an ExtMap, registered URIs are stored by their index
+    pub fn new(value: isize, uri: &str) -> Self {
+        let uri_idx = get_idx_by_ext_uri(uri);
+        ExtMap {
+            value,
+            uri_idx,
+            uri: if uri_idx == ExtIdxNone {
+                Some(uri.to_owned())
+            } else {
+                None
+            },
+            ..Default::default()
+        }
+    }
+
+    /// uri returns the URI of the header extension
+    pub fn uri(&self) -> &str {
+        if self.uri_idx != ExtIdxNone {
+            return get_ext_uri_by_idx(self.uri_idx);
+        }
+        self.uri.as_deref().unwrap_or(NONE_EXT)
+    }
+
     /// converts this object to an Attribute
     pub fn convert(&self) -> Attribute {
         Attribute {
-            key: "extmap".to_string(),
+            key: ATTR_KEY_EXTMAP.to_string(),
             value: Some(self.to_string()),
         }
     }
@@ -133,9 +221,9 @@ impl ExtMap {
 
         let valdir: Vec<&str> = fields[0].split('/').collect();
         let value = valdir[0].parse::<isize>()?;
-        if !(1..=246).contains(&value) {
+        if !(1..=*TWO_BYTE_EXT_MAP_IDS.end()).contains(&value) {
             return Err(Error::ParseExtMap(format!(
-                "{} -- extmap key must be in the range 1-256",
+                "{} -- extmap key must be in the range 1-255",
                 valdir[0]
             )));
         }
@@ -151,8 +239,6 @@ impl ExtMap {
             }
         }
 
-        let uri_idx = get_idx_by_ext_uri(fields[1]);
-
         let ext_attr = if fields.len() == 3 {
             Some(fields[2].to_owned())
         } else {
@@ -160,10 +246,9 @@ impl ExtMap {
         };
 
         Ok(ExtMap {
-            value,
             direction,
-            uri_idx,
             ext_attr,
+            ..ExtMap::new(value, fields[1])
         })
     }
 
@@ -173,19 +258,152 @@ impl ExtMap {
     }
 }
 
+/// extmaps_of parses the "a=extmap" attributes of a session or media
+/// description
+pub(crate) fn extmaps_of(attributes: &[Attribute]) -> Result<Vec<ExtMap>> {
+    attributes
+        .iter()
+        .filter(|a| a.key == ATTR_KEY_EXTMAP)
+        .map(|a| ExtMap::unmarshal(&mut io::Cursor::new(a.to_string())))
+        .collect()
+}
+
+/// get_idx_by_ext_uri returns the index of a registered URI or ExtIdxNone
 pub fn get_idx_by_ext_uri(uri: &str) -> ExtIdx {
-    let opt_idx =  ext_url_idx_map.get(uri);
-    if let Some(idx) = opt_idx {
-        return *idx;
-    }
-    ExtIdxNone
+    let registry = ext_url_idx_map.read().unwrap_or_else(|e| e.into_inner());
+    registry.url_idx.get(uri).copied().unwrap_or(ExtIdxNone)
 }
 
+/// get_ext_uri_by_idx returns the URI of a registered index or NONE_EXT
 pub fn get_ext_uri_by_idx(idx: ExtIdx) -> &'static str {
-    let uri = ext_idx_url_map.get(idx as usize);
-    if let Some(uriStr) = uri {
-        return uriStr;
+    let registry = ext_url_idx_map.read().unwrap_or_else(|e| e.into_inner());
+    registry.idx_url.get(idx as usize).copied().unwrap_or(NONE_EXT)
+}
+
+/// register_ext_uri adds an application defined header extension URI to the
+/// registry so that it is matched by index like the built-in ones. The
+/// index of an already registered URI is returned unchanged.
+///
+/// Registrations cannot be removed and each URI is kept for the lifetime of
+/// the process. Register the URIs the application supports once at startup,
+/// never URIs taken from a remote description.
+pub fn register_ext_uri(uri: &str) -> ExtIdx {
+    let mut registry = ext_url_idx_map.write().unwrap_or_else(|e| e.into_inner());
+    if let Some(idx) = registry.url_idx.get(uri) {
+        return *idx;
     }
-    NONE_EXT
+
+    // registered URIs live as long as the process, like the built-in ones
+    let uri: &'static str = Box::leak(uri.to_owned().into_boxed_str());
+    let idx = registry.idx_url.len() as ExtIdx;
+    registry.idx_url.push(uri);
+    registry.url_idx.insert(uri, idx);
+    idx
 }
 
+/// allocate_ext_map_id returns the lowest id not in `used`, one-byte ids
+/// first and two-byte ids only if `allow_mixed`, None when all are taken
+pub fn allocate_ext_map_id(used: &[isize], allow_mixed: bool) -> Option<isize> {
+    ONE_BYTE_EXT_MAP_IDS
+        .chain(TWO_BYTE_EXT_MAP_IDS.filter(|_| allow_mixed))
+        .find(|id| !used.contains(id))
+}
+
+/// ExtMapNegotiator matches the header extensions of a remote description
+/// with the locally supported ones and assigns ids to local offers
+/// <https://tools.ietf.org/html/rfc8285#section-6>
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExtMapNegotiator {
+    /// registry indexes of the supported URIs in order of preference, with
+    /// the direction the local endpoint can use them in
+    supported: Vec<(ExtIdx, Direction)>,
+    allow_mixed: bool,
+}
+
+impl ExtMapNegotiator {
+    pub fn new() -> Self {
+        ExtMapNegotiator::default()
+    }
+
+    /// with_extension adds a supported header extension, URIs unknown to
+    /// the registry are registered. Direction::Unspecified puts no
+    /// restriction on the direction.
+    pub fn with_extension(mut self, uri: &str, direction: Direction) -> Self {
+        let idx = register_ext_uri(uri);
+        if !self.supported.iter().any(|(i, _)| *i == idx) {
+            self.supported.push((idx, direction));
+        }
+        self
+    }
+
+    /// with_allow_mixed enables the two-byte id range 15-255, to be used
+    /// when both sides signal "a=extmap-allow-mixed"
+    pub fn with_allow_mixed(mut self, allow_mixed: bool) -> Self {
+        self.allow_mixed = allow_mixed;
+        self
+    }
+
+    fn supported(&self, extmap: &ExtMap) -> Option<Direction> {
+        let idx = get_idx_by_ext_uri(extmap.uri());
+        self.supported
+            .iter()
+            .find(|(i, _)| idx != ExtIdxNone && *i == idx)
+            .map(|(_, direction)| *direction)
+    }
+
+    /// answer keeps the offered extensions that are supported locally with
+    /// the offerer's ids. The offered direction is reversed and restricted
+    /// to the local one, extensions that end up inactive are dropped.
+    pub fn answer(&self, offered: &[ExtMap]) -> Vec<ExtMap> {
+        let mut extmaps = vec![];
+        for extmap in offered {
+            let local = match self.supported(extmap) {
+                Some(local) => local,
+                None => continue,
+            };
+            let direction = match (extmap.direction, local) {
+                (remote, Direction::Unspecified) => remote.reverse(),
+                (remote, local) => remote.reverse().intersect(local),
+            };
+            if direction == Direction::Inactive {
+                continue;
+            }
+            extmaps.push(ExtMap {
+                direction,
+                ext_attr: None,
+                ..extmap.clone()
+            });
+        }
+        extmaps
+    }
+
+    /// offer lists every supported extension. Extensions already negotiated
+    /// in `previous` keep their id, the others get the lowest free id.
+    pub fn offer(&self, previous: &[ExtMap]) -> Result<Vec<ExtMap>> {
+        let mut used: Vec<isize> = vec![];
+        let mut extmaps = vec![];
+        for (idx, direction) in &self.supported {
+            let uri = get_ext_uri_by_idx(*idx);
+            let kept = previous
+                .iter()
+                .find(|e| e.uri() == uri && !used.contains(&e.value))
+                .map(|e| e.value);
+            let mut extmap = ExtMap::new(kept.unwrap_or_default(), uri);
+            extmap.direction = *direction;
+            if kept.is_some() {
+                used.push(extmap.value);
+            }
+            extmaps.push(extmap);
+        }
+
+        // ids of previous extensions that are no longer offered are not
+        // reused within the same session
+        used.extend(previous.iter().map(|e| e.value));
+        for extmap in extmaps.iter_mut().filter(|e| e.value == 0) {
+            extmap.value = allocate_ext_map_id(&used, self.allow_mixed)
+                .ok_or(Error::ExtMapIdsExhausted)?;
+            used.push(extmap.value);
+        }
+        Ok(extmaps)
+    }
+}
@@ -0,0 +1,119 @@
+use super::*;
+use crate::sdp::sdp_test::SDPSTR;
+
+#[test]
+fn test_group_round_trip() -> Result<()> {
+    let passingtests = [
+        ("BUNDLE 2 3 4", GroupSemantics::Bundle),
+        ("LS 1 2", GroupSemantics::LipSynchronization),
+        ("FID 1 2", GroupSemantics::FlowIdentification),
+        ("FEC 1 2", GroupSemantics::ForwardErrorCorrection),
+        ("DDP L1 L2", GroupSemantics::DecodingDependency),
+        ("BUNDLE", GroupSemantics::Bundle),
+        ("X-FOO a", GroupSemantics::Other("X-FOO".to_owned())),
+    ];
+
+    for (i, u) in passingtests.iter().enumerate() {
+        let group = Group::unmarshal(u.0)?;
+        assert_eq!(group.semantics, u.1, "{}: {}", i, u.0);
+        assert_eq!(u.0, group.to_string(), "{}: {}", i, u.0);
+        assert_eq!(format!("group:{}", u.0), group.marshal());
+        assert_eq!(group, Group::unmarshal(&format!("a=group:{}\r\n", u.0))?);
+    }
+
+    let failingtests = ["", "BUNDLE 1 1", "BUNDLE a\"b", "B@D 1"];
+    for u in failingtests {
+        assert!(Group::unmarshal(u).is_err(), "{}", u);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_mid_lookup() -> Result<()> {
+    let mut sdp = SDP::unmarshal(SDPSTR.as_bytes())?;
+
+    let mids: Vec<Option<&str>> = sdp.media_descriptions.iter().map(|m| m.mid()).collect();
+    assert_eq!(mids, vec![Some("2"), Some("3"), Some("4")]);
+    assert_eq!(
+        sdp.session.groups()?,
+        vec![Group::new(GroupSemantics::Bundle, &["2", "3", "4"])]
+    );
+
+    let video = sdp.media_description_by_mid("3").unwrap();
+    assert_eq!(video.media_name.media, "video");
+    assert!(sdp.media_description_by_mid("5").is_none());
+
+    sdp.media_description_by_mid_mut("4").unwrap().set_mid("5");
+    assert_eq!(sdp.media_descriptions[2].mid(), Some("5"));
+    assert_eq!(
+        sdp.validate_groups(),
+        Err(Error::MidNotFound("4".to_owned()))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_bundle_management() -> Result<()> {
+    let mut sdp = SDP::unmarshal(SDPSTR.as_bytes())?;
+
+    assert!(sdp.remove_from_bundle("3")?);
+    assert!(!sdp.remove_from_bundle("3")?);
+    assert_eq!(
+        sdp.session.attribute(ATTR_KEY_GROUP),
+        Some(Some("BUNDLE 2 4"))
+    );
+    sdp.validate_groups()?;
+
+    sdp.add_to_bundle("3")?;
+    sdp.add_to_bundle("3")?;
+    assert_eq!(
+        sdp.bundle_group()?,
+        Some(Group::new(GroupSemantics::Bundle, &["2", "4", "3"]))
+    );
+    assert_eq!(
+        sdp.add_to_bundle("9"),
+        Err(Error::MidNotFound("9".to_owned()))
+    );
+
+    for mid in ["2", "3", "4"] {
+        sdp.remove_from_bundle(mid)?;
+    }
+    assert_eq!(sdp.session.attribute(ATTR_KEY_GROUP), None);
+    assert_eq!(sdp.bundle_group()?, None);
+
+    sdp.add_to_bundle("4")?;
+    assert_eq!(sdp.session.attributes[0].to_string(), "group:BUNDLE 4");
+
+    Ok(())
+}
+
+#[test]
+fn test_validate_groups() -> Result<()> {
+    let mut sdp = SDP::unmarshal(SDPSTR.as_bytes())?;
+    sdp.validate_groups()?;
+
+    sdp.session = sdp
+        .session
+        .with_group(&Group::new(GroupSemantics::Bundle, &["4"]));
+    assert_eq!(
+        sdp.validate_groups(),
+        Err(Error::DuplicateMid("4".to_owned()))
+    );
+    sdp.session.attributes.pop();
+
+    // a mid may be part of a BUNDLE and an LS group at the same time
+    sdp.session = sdp
+        .session
+        .with_group(&Group::new(GroupSemantics::LipSynchronization, &["2", "3"]));
+    sdp.validate_groups()?;
+
+    sdp.media_descriptions[1].set_mid("2");
+    assert_eq!(
+        sdp.validate_groups(),
+        Err(Error::DuplicateMid("2".to_owned()))
+    );
+
+    Ok(())
+}
@@ -0,0 +1,313 @@
+#[cfg(test)]
+mod group_test;
+
+use super::common::Attribute;
+use super::error::{Error, Result};
+use super::media::MediaDescription;
+use super::session::Session;
+use super::SDP;
+
+use std::fmt;
+use std::str::FromStr;
+
+pub const ATTR_KEY_GROUP: &str = "group";
+pub const ATTR_KEY_MID: &str = "mid";
+/// marks a media section that is only usable within a BUNDLE group
+/// <https://tools.ietf.org/html/rfc8843#section-6>
+pub const ATTR_KEY_BUNDLE_ONLY: &str = "bundle-only";
+pub const SEMANTIC_TOKEN_BUNDLE: &str = "BUNDLE";
+pub const SEMANTIC_TOKEN_LIP_SYNCHRONIZATION: &str = "LS";
+pub const SEMANTIC_TOKEN_FLOW_IDENTIFICATION: &str = "FID";
+pub const SEMANTIC_TOKEN_FORWARD_ERROR_CORRECTION: &str = "FEC";
+pub const SEMANTIC_TOKEN_DECODING_DEPENDENCY: &str = "DDP";
+
+/// GroupSemantics is the semantics token of an "a=group" attribute
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GroupSemantics {
+    /// <https://tools.ietf.org/html/rfc8843>
+    Bundle,
+    /// <https://tools.ietf.org/html/rfc5888#section-7.1>
+    LipSynchronization,
+    /// <https://tools.ietf.org/html/rfc5888#section-7.2>
+    FlowIdentification,
+    /// <https://tools.ietf.org/html/rfc5956>
+    ForwardErrorCorrection,
+    /// <https://tools.ietf.org/html/rfc5583>
+    DecodingDependency,
+    Other(String),
+}
+
+impl fmt::Display for GroupSemantics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            GroupSemantics::Bundle => SEMANTIC_TOKEN_BUNDLE,
+            GroupSemantics::LipSynchronization => SEMANTIC_TOKEN_LIP_SYNCHRONIZATION,
+            GroupSemantics::FlowIdentification => SEMANTIC_TOKEN_FLOW_IDENTIFICATION,
+            GroupSemantics::ForwardErrorCorrection => SEMANTIC_TOKEN_FORWARD_ERROR_CORRECTION,
+            GroupSemantics::DecodingDependency => SEMANTIC_TOKEN_DECODING_DEPENDENCY,
+            GroupSemantics::Other(s) => s,
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl FromStr for GroupSemantics {
+    type Err = Error;
+
+    fn from_str(raw: &str) -> Result<Self> {
+        Ok(match raw {
+            SEMANTIC_TOKEN_BUNDLE => GroupSemantics::Bundle,
+            SEMANTIC_TOKEN_LIP_SYNCHRONIZATION => GroupSemantics::LipSynchronization,
+            SEMANTIC_TOKEN_FLOW_IDENTIFICATION => GroupSemantics::FlowIdentification,
+            SEMANTIC_TOKEN_FORWARD_ERROR_CORRECTION => GroupSemantics::ForwardErrorCorrection,
+            SEMANTIC_TOKEN_DECODING_DEPENDENCY => GroupSemantics::DecodingDependency,
+            _ if !raw.is_empty() && raw.bytes().all(is_token_char) => {
+                GroupSemantics::Other(raw.to_owned())
+            }
+            _ => return Err(Error::ParseGroup(format!("invalid semantics {}", raw))),
+        })
+    }
+}
+
+/// Group is the structured form of an "a=group" attribute
+///
+/// `group:<semantics> *(SP <identification-tag>)`
+///
+/// <https://tools.ietf.org/html/rfc5888#section-5>
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Group {
+    pub semantics: GroupSemantics,
+    pub mids: Vec<String>,
+}
+
+/// Renders the value of the "a=group" attribute.
+impl fmt::Display for Group {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.semantics)?;
+        for mid in &self.mids {
+            write!(f, " {}", mid)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Group {
+    type Err = Error;
+
+    fn from_str(raw: &str) -> Result<Self> {
+        Group::unmarshal(raw)
+    }
+}
+
+impl Group {
+    pub fn new(semantics: GroupSemantics, mids: &[&str]) -> Self {
+        Group {
+            semantics,
+            mids: mids.iter().map(|mid| mid.to_string()).collect(),
+        }
+    }
+
+    /// unmarshal parses a group, with or without the "a=group:" or "group:"
+    /// prefix
+    pub fn unmarshal(raw: &str) -> Result<Self> {
+        let value = raw.trim();
+        let value = value.strip_prefix("a=").unwrap_or(value);
+        let value = value.strip_prefix("group:").unwrap_or(value);
+
+        let mut fields = value.split_whitespace();
+        let semantics = fields
+            .next()
+            .ok_or_else(|| Error::ParseGroup(raw.to_owned()))?
+            .parse()?;
+
+        let mut mids = vec![];
+        for mid in fields {
+            if !mid.bytes().all(is_token_char) {
+                return Err(Error::ParseGroup(format!("invalid mid {}", mid)));
+            }
+            if mids.iter().any(|m| m == mid) {
+                return Err(Error::DuplicateMid(mid.to_owned()));
+            }
+            mids.push(mid.to_owned());
+        }
+
+        Ok(Group { semantics, mids })
+    }
+
+    /// marshal renders the group as "group:..." attribute line value
+    pub fn marshal(&self) -> String {
+        format!("{}:{}", ATTR_KEY_GROUP, self)
+    }
+
+    /// converts this object to an Attribute
+    pub fn convert(&self) -> Attribute {
+        Attribute {
+            key: ATTR_KEY_GROUP.to_owned(),
+            value: Some(self.to_string()),
+        }
+    }
+
+    pub fn is_bundle(&self) -> bool {
+        self.semantics == GroupSemantics::Bundle
+    }
+
+    pub fn contains(&self, mid: &str) -> bool {
+        self.mids.iter().any(|m| m == mid)
+    }
+}
+
+impl Session {
+    /// groups parses all "a=group" attributes
+    pub fn groups(&self) -> Result<Vec<Group>> {
+        self.attributes
+            .iter()
+            .filter(|a| a.key == ATTR_KEY_GROUP)
+            .map(|a| Group::unmarshal(a.value.as_deref().unwrap_or_default()))
+            .collect()
+    }
+
+    /// with_group adds an "a=group" attribute
+    pub fn with_group(mut self, group: &Group) -> Self {
+        self.attributes.push(group.convert());
+        self
+    }
+}
+
+impl MediaDescription {
+    /// mid returns the value of the "a=mid" attribute
+    /// <https://tools.ietf.org/html/rfc5888#section-4>
+    pub fn mid(&self) -> Option<&str> {
+        self.attribute(ATTR_KEY_MID).flatten()
+    }
+
+    /// set_mid adds or replaces the "a=mid" attribute
+    pub fn set_mid(&mut self, mid: &str) {
+        match self.attributes.iter_mut().find(|a| a.key == ATTR_KEY_MID) {
+            Some(attribute) => attribute.value = Some(mid.to_owned()),
+            None => self.attributes.push(Attribute::new(
+                ATTR_KEY_MID.to_owned(),
+                Some(mid.to_owned()),
+            )),
+        }
+    }
+
+    /// is_bundle_only returns true if the media section carries
+    /// "a=bundle-only", it is then offered with port 0
+    pub fn is_bundle_only(&self) -> bool {
+        self.attribute(ATTR_KEY_BUNDLE_ONLY).is_some()
+    }
+}
+
+impl SDP {
+    /// media_description_by_mid returns the media section with the given mid
+    pub fn media_description_by_mid(&self, mid: &str) -> Option<&MediaDescription> {
+        self.media_descriptions
+            .iter()
+            .find(|m| m.mid() == Some(mid))
+    }
+
+    /// media_description_by_mid_mut returns the media section with the given
+    /// mid for modification
+    pub fn media_description_by_mid_mut(&mut self, mid: &str) -> Option<&mut MediaDescription> {
+        self.media_descriptions
+            .iter_mut()
+            .find(|m| m.mid() == Some(mid))
+    }
+
+    /// bundle_group returns the first "a=group:BUNDLE"
+    pub fn bundle_group(&self) -> Result<Option<Group>> {
+        Ok(self.session.groups()?.into_iter().find(|g| g.is_bundle()))
+    }
+
+    /// add_to_bundle appends the media section with the given mid to the
+    /// BUNDLE group, creating the group if there is none
+    pub fn add_to_bundle(&mut self, mid: &str) -> Result<()> {
+        if self.media_description_by_mid(mid).is_none() {
+            return Err(Error::MidNotFound(mid.to_owned()));
+        }
+
+        match self.bundle_attribute()? {
+            Some((index, mut group)) => {
+                if !group.contains(mid) {
+                    group.mids.push(mid.to_owned());
+                    self.session.attributes[index] = group.convert();
+                }
+            }
+            None => {
+                let group = Group::new(GroupSemantics::Bundle, &[mid]);
+                self.session.attributes.insert(0, group.convert());
+            }
+        }
+        Ok(())
+    }
+
+    /// remove_from_bundle removes a mid from the BUNDLE group, the group is
+    /// dropped once empty. Returns false if the mid was not bundled.
+    pub fn remove_from_bundle(&mut self, mid: &str) -> Result<bool> {
+        let (index, mut group) = match self.bundle_attribute()? {
+            Some(bundle) => bundle,
+            None => return Ok(false),
+        };
+        if !group.contains(mid) {
+            return Ok(false);
+        }
+
+        group.mids.retain(|m| m != mid);
+        if group.mids.is_empty() {
+            self.session.attributes.remove(index);
+        } else {
+            self.session.attributes[index] = group.convert();
+        }
+        Ok(true)
+    }
+
+    /// validate_groups checks that mids are unique across the media sections,
+    /// that every grouped mid identifies exactly one of them and that no
+    /// media section is part of more than one BUNDLE group
+    /// <https://tools.ietf.org/html/rfc8843#section-7.1>
+    pub fn validate_groups(&self) -> Result<()> {
+        let mut mids: Vec<&str> = vec![];
+        for mid in self.media_descriptions.iter().filter_map(|m| m.mid()) {
+            if mids.contains(&mid) {
+                return Err(Error::DuplicateMid(mid.to_owned()));
+            }
+            mids.push(mid);
+        }
+
+        let mut bundled: Vec<String> = vec![];
+        for group in self.session.groups()? {
+            for mid in &group.mids {
+                if !mids.contains(&mid.as_str()) {
+                    return Err(Error::MidNotFound(mid.clone()));
+                }
+                if group.is_bundle() {
+                    if bundled.contains(mid) {
+                        return Err(Error::DuplicateMid(mid.clone()));
+                    }
+                    bundled.push(mid.clone());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn bundle_attribute(&self) -> Result<Option<(usize, Group)>> {
+        for (index, attribute) in self.session.attributes.iter().enumerate() {
+            if attribute.key != ATTR_KEY_GROUP {
+                continue;
+            }
+            let group = Group::unmarshal(attribute.value.as_deref().unwrap_or_default())?;
+            if group.is_bundle() {
+                return Ok(Some((index, group)));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// token-char as defined in RFC 4566 section 9
+fn is_token_char(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`{|}~".contains(&b)
+}
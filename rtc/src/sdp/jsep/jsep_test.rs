@@ -0,0 +1,344 @@
+use super::*;
+use crate::sdp::sdp_test::SDPSTR;
+
+fn local_capabilities() -> LocalCapabilities {
+    let opus = Codec {
+        fmtp: "minptime=10;useinbandfec=1".parse().unwrap(),
+        rtcp_feedback: vec!["transport-cc".to_owned()],
+        ..Codec::new(111, "opus", 48000, 2)
+    };
+    let feedback = vec![
+        "nack".to_owned(),
+        "nack pli".to_owned(),
+        "transport-cc".to_owned(),
+    ];
+    let vp8 = Codec {
+        rtcp_feedback: feedback.clone(),
+        ..Codec::new(96, "VP8", 90000, 0)
+    };
+    let h264 = Codec {
+        fmtp: "level-asymmetry-allowed=1;packetization-mode=1;profile-level-id=42e01f"
+            .parse()
+            .unwrap(),
+        rtcp_feedback: feedback,
+        ..Codec::new(102, "H264", 90000, 0)
+    };
+    let rtx = Codec {
+        fmtp: "apt=96".parse().unwrap(),
+        ..Codec::new(97, "rtx", 90000, 0)
+    };
+
+    LocalCapabilities {
+        session_id: 4_294_967_296,
+        audio: MediaCapabilities {
+            codecs: vec![opus, Codec::new(0, "PCMU", 8000, 0)],
+            header_extensions: vec![SDES_MID_EXT.to_owned(), AUDIO_LEVEL_EXT.to_owned()],
+            direction: Direction::SendRecv,
+        },
+        video: MediaCapabilities {
+            codecs: vec![h264, vp8, rtx],
+            header_extensions: vec![SDES_MID_EXT.to_owned(), TRANSPORT_CC_EXT.to_owned()],
+            direction: Direction::RecvOnly,
+        },
+        data_channel: Some(DataChannelCapabilities::default()),
+        ice_ufrag: "ufrag".to_owned(),
+        ice_pwd: "passwordpasswordpassword".to_owned(),
+        fingerprint_algorithm: "sha-256".to_owned(),
+        fingerprint: "00:11:22:33".to_owned(),
+    }
+}
+
+#[test]
+fn test_create_answer_browser_offer() -> Result<()> {
+    let offer = SDP::unmarshal(SDPSTR.as_bytes())?;
+    let answer = offer.create_answer(&local_capabilities())?;
+
+    // a new session starts at version 0
+    assert_eq!(
+        answer.session.origin.to_string(),
+        "- 4294967296 0 IN IP4 127.0.0.1"
+    );
+    assert_eq!(
+        answer.media_descriptions.len(),
+        offer.media_descriptions.len()
+    );
+    assert_eq!(
+        answer.session.attribute(ATTR_KEY_GROUP),
+        Some(Some("BUNDLE 2 3 4"))
+    );
+
+    let mids: Vec<Option<&str>> = answer
+        .media_descriptions
+        .iter()
+        .map(|m| m.attribute(ATTR_KEY_MID).flatten())
+        .collect();
+    assert_eq!(mids, vec![Some("2"), Some("3"), Some("4")]);
+
+    let audio = &answer.media_descriptions[0];
+    assert_eq!(
+        audio.media_name.to_string(),
+        "audio 9 UDP/TLS/RTP/SAVPF 111 0"
+    );
+    assert_eq!(audio.attribute("sendrecv"), Some(None));
+    assert_eq!(audio.attribute(ATTR_KEY_SETUP), Some(Some("active")));
+    assert_eq!(audio.attribute("ice-ufrag"), Some(Some("ufrag")));
+    let opus = audio.codec_for_payload_type(111)?;
+    assert_eq!(opus.fmtp.to_string(), "minptime=10;useinbandfec=1");
+    assert_eq!(opus.rtcp_feedback, vec!["transport-cc"]);
+    let extmaps: Vec<&str> = audio
+        .attributes
+        .iter()
+        .filter(|a| a.key == ATTR_KEY_EXTMAP)
+        .filter_map(|a| a.value.as_deref())
+        .collect();
+    assert_eq!(
+        extmaps,
+        vec![
+            "1 urn:ietf:params:rtp-hdrext:ssrc-audio-level",
+            "4 urn:ietf:params:rtp-hdrext:sdes:mid"
+        ]
+    );
+
+    let video = &answer.media_descriptions[1];
+    // VP8 + its rtx and the single H264 with packetization-mode=1 and the
+    // constrained baseline profile, kept in offer order
+    assert_eq!(video.media_name.formats, vec!["96", "97", "125", "107"]);
+    assert_eq!(video.attribute("recvonly"), Some(None));
+    assert_eq!(video.attribute(ATTR_KEY_RTCP_RSIZE), Some(None));
+    let vp8 = video.codec_for_payload_type(96)?;
+    assert_eq!(vp8.rtcp_feedback, vec!["transport-cc", "nack", "nack pli"]);
+    // rtx of an H264 payload type that is not accepted is dropped as well
+    assert!(video.codec_for_payload_type(121).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_create_answer_rejects_unsupported() -> Result<()> {
+    let offer = SDP::unmarshal(SDPSTR.as_bytes())?;
+    let mut local = local_capabilities();
+    local.video.codecs = vec![Codec::new(100, "H265", 90000, 0)];
+
+    let answer = offer.create_answer(&local)?;
+    assert_eq!(
+        answer.session.attribute(ATTR_KEY_GROUP),
+        Some(Some("BUNDLE 2"))
+    );
+
+    for video in &answer.media_descriptions[1..] {
+        assert_eq!(video.media_name.port.value, 0);
+        assert_eq!(video.media_name.formats, vec!["96"]);
+        assert_eq!(video.attributes.len(), 1);
+    }
+
+    let mut local = local_capabilities();
+    local.session_id = 0;
+    assert_ne!(offer.create_answer(&local)?.session.origin.session_id, 0);
+
+    // an answer marshals and parses back like any other description
+    let marshaled = SDP::marshal(&answer);
+    let parsed = SDP::unmarshal(marshaled.as_bytes())?;
+    assert_eq!(SDP::marshal(&parsed), marshaled);
+
+    Ok(())
+}
+
+#[test]
+fn test_create_answer_malformed_bundle() -> Result<()> {
+    let offer = SDPSTR.replace("a=group:BUNDLE 2 3 4", "a=group:BUNDLE 2 3 3");
+    let offer = SDP::unmarshal(offer.as_bytes())?;
+    assert_eq!(
+        offer.create_answer(&local_capabilities()),
+        Err(Error::DuplicateMid("3".to_owned()))
+    );
+
+    Ok(())
+}
+
+const BUNDLE_ONLY_OFFER: &str = "v=0\r\n\
+o=- 1 1 IN IP4 127.0.0.1\r\n\
+s=-\r\n\
+t=0 0\r\n\
+a=group:BUNDLE 0 1 2\r\n\
+m=audio 9 UDP/TLS/RTP/SAVPF 111\r\n\
+a=mid:0\r\n\
+a=setup:actpass\r\n\
+a=rtpmap:111 opus/48000/2\r\n\
+m=video 0 UDP/TLS/RTP/SAVPF 96\r\n\
+a=bundle-only\r\n\
+a=mid:1\r\n\
+a=rtpmap:96 VP8/90000\r\n\
+m=application 0 UDP/DTLS/SCTP webrtc-datachannel\r\n\
+a=bundle-only\r\n\
+a=mid:2\r\n\
+a=sctp-port:5000\r\n\
+a=max-message-size:262144\r\n\
+m=video 0 UDP/TLS/RTP/SAVPF 96\r\n\
+a=bundle-only\r\n\
+a=mid:3\r\n\
+a=rtpmap:96 VP8/90000\r\n";
+
+#[test]
+fn test_create_answer_bundle_only() -> Result<()> {
+    let offer = SDP::unmarshal(BUNDLE_ONLY_OFFER.as_bytes())?;
+    let answer = offer.create_answer(&local_capabilities())?;
+
+    assert_eq!(
+        answer.session.attribute(ATTR_KEY_GROUP),
+        Some(Some("BUNDLE 0 1 2"))
+    );
+    let ports: Vec<isize> = answer
+        .media_descriptions
+        .iter()
+        .map(|m| m.media_name.port.value)
+        .collect();
+    // the bundle-only section outside the BUNDLE group is rejected
+    assert_eq!(ports, vec![9, 9, 9, 0]);
+
+    let video = &answer.media_descriptions[1];
+    assert_eq!(video.media_name.formats, vec!["96"]);
+    assert_eq!(video.mid(), Some("1"));
+    assert!(!video.is_bundle_only());
+
+    let data = &answer.media_descriptions[2];
+    assert_eq!(
+        data.media_name.to_string(),
+        "application 9 UDP/DTLS/SCTP webrtc-datachannel"
+    );
+    assert!(data.is_datachannel());
+    assert_eq!(data.mid(), Some("2"));
+    assert_eq!(data.attribute(ATTR_KEY_SETUP), Some(Some("active")));
+    assert_eq!(data.sctp_port()?, DEFAULT_SCTP_PORT);
+    assert_eq!(data.max_message_size()?, DEFAULT_MAX_MESSAGE_SIZE);
+
+    Ok(())
+}
+
+#[test]
+fn test_create_answer_datachannel() -> Result<()> {
+    let offer = BUNDLE_ONLY_OFFER.replace("m=application 0", "m=application 9");
+    let offer = SDP::unmarshal(offer.as_bytes())?;
+
+    // without local data channel support the m-line is rejected
+    let mut local = local_capabilities();
+    local.data_channel = None;
+    let answer = offer.create_answer(&local)?;
+    assert_eq!(answer.media_descriptions[2].media_name.port.value, 0);
+    assert_eq!(
+        answer.session.attribute(ATTR_KEY_GROUP),
+        Some(Some("BUNDLE 0 1"))
+    );
+
+    // the legacy form is answered in kind
+    let offer = BUNDLE_ONLY_OFFER
+        .replace(
+            "m=application 0 UDP/DTLS/SCTP webrtc-datachannel",
+            "m=application 9 DTLS/SCTP 5000",
+        )
+        .replace(
+            "a=sctp-port:5000\r\na=max-message-size:262144",
+            "a=sctpmap:5000 webrtc-datachannel 1024",
+        );
+    let offer = SDP::unmarshal(offer.as_bytes())?;
+    local.data_channel = Some(DataChannelCapabilities {
+        sctp_port: 5001,
+        max_message_size: 0,
+    });
+    let answer = offer.create_answer(&local)?;
+    let data = &answer.media_descriptions[2];
+    assert_eq!(data.media_name.to_string(), "application 9 DTLS/SCTP 5001");
+    assert!(data.is_datachannel());
+    assert_eq!(data.sctp_port()?, 5001);
+    assert_eq!(data.attribute(ATTR_KEY_MAX_MESSAGE_SIZE), None);
+
+    Ok(())
+}
+
+#[test]
+fn test_create_answer_without_media() {
+    let offer = SDP::default();
+    let local = local_capabilities();
+    assert!(create_answer(&offer, &local).is_err());
+    assert_eq!(create_answer(&offer, &local), offer.create_answer(&local));
+}
+
+#[test]
+fn test_answer_direction() {
+    let tests = [
+        (
+            Direction::SendRecv,
+            Direction::SendRecv,
+            Direction::SendRecv,
+        ),
+        (
+            Direction::SendOnly,
+            Direction::SendRecv,
+            Direction::RecvOnly,
+        ),
+        (
+            Direction::RecvOnly,
+            Direction::SendRecv,
+            Direction::SendOnly,
+        ),
+        (
+            Direction::Inactive,
+            Direction::SendRecv,
+            Direction::Inactive,
+        ),
+        (
+            Direction::SendRecv,
+            Direction::RecvOnly,
+            Direction::RecvOnly,
+        ),
+        (
+            Direction::SendOnly,
+            Direction::SendOnly,
+            Direction::Inactive,
+        ),
+        (
+            Direction::SendRecv,
+            Direction::Unspecified,
+            Direction::SendRecv,
+        ),
+    ];
+
+    for (i, u) in tests.iter().enumerate() {
+        assert_eq!(answer_direction(u.0, u.1), u.2, "{}: {} {}", i, u.0, u.1);
+    }
+}
+
+#[test]
+fn test_negotiate_codecs() {
+    let offered = vec![
+        Codec {
+            fmtp: "profile-id=2".parse().unwrap(),
+            ..Codec::new(100, "VP9", 90000, 0)
+        },
+        Codec {
+            fmtp: "apt=100".parse().unwrap(),
+            ..Codec::new(101, "rtx", 90000, 0)
+        },
+        Codec::new(98, "VP9", 90000, 0),
+        Codec {
+            fmtp: "apt=98".parse().unwrap(),
+            ..Codec::new(99, "rtx", 90000, 0)
+        },
+    ];
+
+    let local = vec![Codec::new(96, "vp9", 90000, 0)];
+    let accepted: Vec<u8> = negotiate_codecs(&offered, &local)
+        .iter()
+        .map(|c| c.payload_type)
+        .collect();
+    assert_eq!(accepted, vec![98]);
+
+    let local = vec![
+        Codec::new(96, "vp9", 90000, 0),
+        Codec::new(97, "rtx", 90000, 0),
+    ];
+    let accepted: Vec<u8> = negotiate_codecs(&offered, &local)
+        .iter()
+        .map(|c| c.payload_type)
+        .collect();
+    assert_eq!(accepted, vec![98, 99]);
+}
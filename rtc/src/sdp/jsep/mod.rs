@@ -0,0 +1,369 @@
+#[cfg(test)]
+mod jsep_test;
+
+use super::codec::*;
+use super::common::*;
+use super::datachannel::*;
+use super::direction::*;
+use super::dtls::*;
+use super::error::{Error, Result};
+use super::extmap::*;
+use super::group::*;
+use super::lexer::LineEnding;
+use super::media::*;
+use super::session::*;
+use super::time::*;
+use super::SDP;
+
+pub const ATTR_KEY_RTCP_MUX: &str = "rtcp-mux";
+pub const ATTR_KEY_RTCP_RSIZE: &str = "rtcp-rsize";
+
+/// MediaCapabilities lists what the local endpoint supports for one kind of
+/// media ("audio" or "video").
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MediaCapabilities {
+    /// supported codecs in order of preference, the payload types are only
+    /// used to resolve the "apt" of local rtx codecs
+    pub codecs: Vec<Codec>,
+    /// URIs of the supported RTP header extensions
+    pub header_extensions: Vec<String>,
+    /// the directions the local endpoint is able to use
+    pub direction: Direction,
+}
+
+/// DataChannelCapabilities describes the local SCTP association used for
+/// data channels.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DataChannelCapabilities {
+    pub sctp_port: u16,
+    /// largest message the local endpoint can receive, 0 for no limit
+    pub max_message_size: u64,
+}
+
+impl Default for DataChannelCapabilities {
+    fn default() -> Self {
+        DataChannelCapabilities {
+            sctp_port: DEFAULT_SCTP_PORT,
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+        }
+    }
+}
+
+/// LocalCapabilities describes the local endpoint to create an answer for.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LocalCapabilities {
+    /// session id of the answer origin, 0 picks a random one
+    pub session_id: u64,
+    pub audio: MediaCapabilities,
+    pub video: MediaCapabilities,
+    /// data channel support, None rejects offered datachannel m-lines
+    pub data_channel: Option<DataChannelCapabilities>,
+    pub ice_ufrag: String,
+    pub ice_pwd: String,
+    /// hash function of the DTLS certificate fingerprint, e.g. "sha-256"
+    pub fingerprint_algorithm: String,
+    pub fingerprint: String,
+}
+
+impl LocalCapabilities {
+    fn media(&self, media: &str) -> Option<&MediaCapabilities> {
+        match media {
+            "audio" => Some(&self.audio),
+            "video" => Some(&self.video),
+            _ => None,
+        }
+    }
+}
+
+/// create_answer produces an answer to a remote offer following
+/// <https://tools.ietf.org/html/rfc8829#section-5.3>.
+///
+/// Every m-line of the offer is answered in order. Codecs and header
+/// extensions are intersected with the local capabilities keeping the
+/// payload types and ids chosen by the offerer, the offered direction is
+/// reversed and restricted to the local direction. Datachannel m-lines are
+/// answered in the form they were offered in if `local.data_channel` is
+/// set. m-lines offered with port 0 are rejected unless they are
+/// "a=bundle-only" sections of the offered BUNDLE group. Rejected m-lines,
+/// including the ones without a common codec or of an unsupported media
+/// type, get port 0 and are removed from the BUNDLE group.
+///
+/// An offer without media is an error.
+pub fn create_answer(offer: &SDP, local: &LocalCapabilities) -> Result<SDP> {
+    if offer.media_descriptions.is_empty() {
+        return Err(Error::SdpInvalidValue("offer without media".to_owned()));
+    }
+    let bundle_groups: Vec<Group> = offer
+        .session
+        .groups()?
+        .into_iter()
+        .filter(|g| g.is_bundle())
+        .collect();
+
+    let mut origin = Origin::new_jsep();
+    if local.session_id != 0 {
+        origin.session_id = local.session_id;
+    }
+
+    let mut answer = SDP {
+        session: Session {
+            version: 0,
+            origin,
+            session_name: "-".to_owned(),
+            ..Default::default()
+        },
+        time_descriptions: vec![TimeDescription {
+            timing: Timing {
+                start_time: 0,
+                stop_time: 0,
+            },
+            repeat_times: vec![],
+        }],
+        media_descriptions: vec![],
+        line_ending: LineEnding::default(),
+    };
+
+    let mut bundled_mids = vec![];
+    for offered in &offer.media_descriptions {
+        // bundle-only sections come with port 0 and are joined to the group
+        let bundle_only = offered.is_bundle_only()
+            && offered
+                .mid()
+                .is_some_and(|mid| bundle_groups.iter().any(|g| g.contains(mid)));
+        let media = if offered.media_name.port.value == 0 && !bundle_only {
+            None
+        } else {
+            let setup = offer
+                .media_setup_role(offered)?
+                .unwrap_or(SetupRole::ActPass);
+            if offered.is_datachannel() {
+                local
+                    .data_channel
+                    .as_ref()
+                    .map(|caps| answer_datachannel(offered, setup, caps, local))
+            } else if let Some(caps) = local.media(&offered.media_name.media) {
+                let direction = offer.media_direction(offered);
+                answer_media(offered, setup, direction, caps, local)?
+            } else {
+                None
+            }
+        };
+        let media = match media {
+            Some(media) => {
+                if let Some(mid) = offered.mid() {
+                    bundled_mids.push(mid.to_owned());
+                }
+                media
+            }
+            None => reject_media(offered),
+        };
+        answer.media_descriptions.push(media);
+    }
+
+    for attribute in &offer.session.attributes {
+        match attribute.key.as_str() {
+            ATTR_KEY_GROUP => {
+                if let Some(group) = answer_bundle_group(attribute, &bundled_mids)? {
+                    answer.session.attributes.push(group);
+                }
+            }
+            ATTR_KEY_EXTMAP_ALLOW_MIXED => answer
+                .session
+                .attributes
+                .push(Attribute::new(attribute.key.clone(), None)),
+            _ => {}
+        }
+    }
+
+    Ok(answer)
+}
+
+/// answer_media answers a single offered m-line, None if nothing can be
+/// negotiated
+fn answer_media(
+    offered: &MediaDescription,
+    offered_setup: SetupRole,
+    offered_direction: Direction,
+    caps: &MediaCapabilities,
+    local: &LocalCapabilities,
+) -> Result<Option<MediaDescription>> {
+    let codecs = negotiate_codecs(&offered.codecs(), &caps.codecs);
+    if codecs.is_empty() {
+        return Ok(None);
+    }
+
+    let mut media =
+        MediaDescription::new_jsep_media_description(offered.media_name.media.clone(), vec![]);
+    media.media_name.protos = offered.media_name.protos.clone();
+    media = with_answer_transport(media, offered, offered_setup, local);
+
+    for extmap in negotiate_extmaps(offered, &caps.header_extensions)? {
+        media = media.with_extmap(extmap);
+    }
+
+    let direction = answer_direction(offered_direction, caps.direction);
+    media.attributes.push(direction.convert());
+
+    for key in [ATTR_KEY_RTCP_MUX, ATTR_KEY_RTCP_RSIZE] {
+        if offered.attribute(key).is_some() {
+            media = media.with_property_attribute(key.to_owned());
+        }
+    }
+
+    for codec in &codecs {
+        media.add_codec(codec);
+    }
+
+    Ok(Some(media))
+}
+
+/// answer_datachannel accepts an offered datachannel m-line, in the current
+/// form with "a=sctp-port" or in the legacy one with "a=sctpmap" depending on
+/// the offer
+/// <https://tools.ietf.org/html/rfc8841#section-6>
+fn answer_datachannel(
+    offered: &MediaDescription,
+    offered_setup: SetupRole,
+    caps: &DataChannelCapabilities,
+    local: &LocalCapabilities,
+) -> MediaDescription {
+    let mut media =
+        MediaDescription::new_jsep_media_description(MEDIA_APPLICATION.to_owned(), vec![]);
+    media.media_name.protos = offered.media_name.protos.clone();
+    let legacy = !offered
+        .media_name
+        .formats
+        .iter()
+        .any(|f| f == FORMAT_WEBRTC_DATACHANNEL);
+    media.media_name.formats = if legacy {
+        vec![caps.sctp_port.to_string()]
+    } else {
+        vec![FORMAT_WEBRTC_DATACHANNEL.to_owned()]
+    };
+    media = with_answer_transport(media, offered, offered_setup, local);
+
+    if legacy {
+        media
+            .attributes
+            .push(Sctpmap::new(caps.sctp_port, None).convert());
+        media
+    } else {
+        media
+            .with_sctp_port(caps.sctp_port)
+            .with_max_message_size(caps.max_message_size)
+    }
+}
+
+/// with_answer_transport adds the offered mid and the local ICE and DTLS
+/// parameters to an accepted m-line
+fn with_answer_transport(
+    mut media: MediaDescription,
+    offered: &MediaDescription,
+    offered_setup: SetupRole,
+    local: &LocalCapabilities,
+) -> MediaDescription {
+    if let Some(mid) = offered.mid() {
+        media.set_mid(mid);
+    }
+    media
+        .with_ice_credentials(local.ice_ufrag.clone(), local.ice_pwd.clone())
+        .with_fingerprint(
+            local.fingerprint_algorithm.clone(),
+            local.fingerprint.clone(),
+        )
+        .with_setup_role(offered_setup.answer())
+}
+
+/// reject_media creates the answer to an m-line that is not accepted, the
+/// port is set to zero and only the first format and the mid are kept
+/// <https://tools.ietf.org/html/rfc8829#section-5.3.1>
+fn reject_media(offered: &MediaDescription) -> MediaDescription {
+    let mut media =
+        MediaDescription::new_jsep_media_description(offered.media_name.media.clone(), vec![]);
+    media.media_name.port.value = 0;
+    media.media_name.protos = offered.media_name.protos.clone();
+    media.media_name.formats = offered.media_name.formats.iter().take(1).cloned().collect();
+    if let Some(mid) = offered.mid() {
+        media.set_mid(mid);
+    }
+    media
+}
+
+/// negotiate_codecs intersects the offered codecs with the local ones. The
+/// offerer's order, payload types and format parameters are kept, rtcp-fb
+/// mechanisms are restricted to the ones supported locally. rtx payload
+/// types survive only if their associated payload type does.
+pub fn negotiate_codecs(offered: &[Codec], local: &[Codec]) -> Vec<Codec> {
+    let mut accepted: Vec<Codec> = vec![];
+    for codec in offered.iter().filter(|c| !c.is_rtx()) {
+        if let Some(l) = local.iter().find(|l| !l.is_rtx() && l.is_compatible(codec)) {
+            let mut codec = codec.clone();
+            codec
+                .rtcp_feedback
+                .retain(|fb| l.rtcp_feedback.iter().any(|lfb| lfb == fb));
+            accepted.push(codec);
+        }
+    }
+
+    if !local.iter().any(|l| l.is_rtx()) {
+        return accepted;
+    }
+
+    let mut rtx = vec![];
+    for codec in offered.iter().filter(|c| c.is_rtx()) {
+        let apt = codec
+            .fmtp_param("apt")
+            .and_then(|apt| apt.parse::<u8>().ok());
+        if accepted.iter().any(|a| Some(a.payload_type) == apt) {
+            rtx.push(codec.clone());
+        }
+    }
+
+    // keep every rtx right behind the offered position, i.e. in offer order
+    let order: Vec<u8> = offered.iter().map(|c| c.payload_type).collect();
+    accepted.extend(rtx);
+    accepted.sort_by_key(|c| order.iter().position(|pt| *pt == c.payload_type));
+    accepted
+}
+
+/// negotiate_extmaps keeps the offered header extensions that are supported
+/// locally with the id chosen by the offerer
+fn negotiate_extmaps(offered: &MediaDescription, supported: &[String]) -> Result<Vec<ExtMap>> {
+    let negotiator = supported.iter().fold(ExtMapNegotiator::new(), |n, uri| {
+        n.with_extension(uri, Direction::Unspecified)
+    });
+    Ok(negotiator.answer(&offered.extmaps()?))
+}
+
+/// answer_direction reverses the offered direction and restricts it to what
+/// the local endpoint is able to do
+/// <https://tools.ietf.org/html/rfc3264#section-6.1>
+pub fn answer_direction(offered: Direction, local: Direction) -> Direction {
+    offered.reverse().intersect(local)
+}
+
+/// answer_bundle_group restricts an offered "a=group:BUNDLE" to the
+/// accepted mids, other group semantics are dropped
+fn answer_bundle_group(group: &Attribute, accepted_mids: &[String]) -> Result<Option<Attribute>> {
+    let mut group = Group::unmarshal(group.value.as_deref().unwrap_or_default())?;
+    if !group.is_bundle() {
+        return Ok(None);
+    }
+
+    group.mids.retain(|mid| accepted_mids.contains(mid));
+    if group.mids.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(group.convert()))
+}
+
+impl SDP {
+    /// create_answer produces an answer to this offer, see [`create_answer`]
+    pub fn create_answer(&self, local: &LocalCapabilities) -> Result<SDP> {
+        create_answer(self, local)
+    }
+}
@@ -1,51 +1,302 @@
 use super::SDP;
+use super::session::Session;
 use super::error::{Error, Result};
 
+use std::fmt;
 use std::io;
-use std::io::SeekFrom;
 
 pub(crate) const END_LINE: &str = "\r\n";
+pub(crate) const END_LINE_LF: &str = "\n";
 
-pub struct Lexer<'a, R: io::BufRead + io::Seek> {
+/// LineEnding is the line terminator of a session description. RFC 4566
+/// mandates CRLF but LF-only descriptions are common in the wild.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LineEnding {
+    #[default]
+    CrLf,
+    Lf,
+}
+
+impl LineEnding {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LineEnding::CrLf => END_LINE,
+            LineEnding::Lf => END_LINE_LF,
+        }
+    }
+}
+
+/// UnmarshalOptions controls how closely `SDP::unmarshal_with_options`
+/// follows the grammar of RFC 4566.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnmarshalOptions {
+    /// strict parsing rejects lines out of the order given by RFC 4566
+    /// section 5, exactly like `SDP::unmarshal`. Lenient parsing accepts any
+    /// order within the session and media sections and reports a warning.
+    pub strict: bool,
+    /// when parsing leniently, skip lines of unknown type such as the 3GPP
+    /// "y=" line with a warning instead of failing
+    pub allow_unknown_types: bool,
+}
+
+impl Default for UnmarshalOptions {
+    fn default() -> Self {
+        UnmarshalOptions {
+            strict: true,
+            allow_unknown_types: false,
+        }
+    }
+}
+
+impl UnmarshalOptions {
+    /// lenient returns options accepting out of order and unknown lines
+    pub fn lenient() -> Self {
+        UnmarshalOptions {
+            strict: false,
+            allow_unknown_types: true,
+        }
+    }
+}
+
+/// ParseWarning reports a deviation from RFC 4566 tolerated by lenient
+/// parsing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ParseWarning {
+    /// 1-based line number, 0 for warnings about the description as a whole
+    pub line: usize,
+    /// text of the line without its line ending
+    pub text: String,
+    pub message: String,
+}
+
+impl fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.line == 0 {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "line {}: {}: {}", self.line, self.text, self.message)
+        }
+    }
+}
+
+pub struct Lexer<'a, R: io::BufRead> {
     pub sdp: SDP,
     pub reader: &'a mut R,
+    /// 1-based number of the line being parsed
+    pub line: usize,
+    /// text of the line being parsed without its line ending
+    pub text: String,
+    /// number of line feeds consumed so far
+    newlines: usize,
+    /// whether the value of the current line has been read
+    value_read: bool,
+    /// whether the line ending of the input has been detected
+    line_ending_detected: bool,
 }
 
 pub type StateFnType<'a, R> = fn(&mut Lexer<'a, R>) -> Result<Option<StateFn<'a, R>>>;
 
-pub struct StateFn<'a, R: io::BufRead + io::Seek> {
+pub struct StateFn<'a, R: io::BufRead> {
     pub f: StateFnType<'a, R>,
 }
 
-pub fn read_type<R: io::BufRead + io::Seek>(reader: &mut R) -> Result<(Vec<u8>, usize)> {
-    let mut b = [0; 1];
-
-    loop {
-        if reader.read_exact(&mut b).is_err() {
-            return Ok((b"".to_vec(), 0));
+impl<'a, R: io::BufRead> Lexer<'a, R> {
+    pub fn new(reader: &'a mut R) -> Self {
+        Lexer {
+            sdp: SDP {
+                session: Session::default(),
+                time_descriptions: vec![],
+                media_descriptions: vec![],
+                line_ending: LineEnding::default(),
+            },
+            reader,
+            line: 0,
+            text: String::new(),
+            newlines: 0,
+            value_read: false,
+            line_ending_detected: false,
         }
+    }
+
+    /// read_type reads the `<type>=` prefix of the next line
+    pub fn read_type(&mut self) -> Result<(Vec<u8>, usize)> {
+        // skip line endings by peeking into the buffer, the first byte of
+        // the type stays unconsumed
+        loop {
+            let buf = self.reader.fill_buf()?;
+            if buf.is_empty() {
+                return Ok((b"".to_vec(), 0));
+            }
 
-        if b[0] == b'\n' || b[0] == b'\r' {
-            continue;
+            let skip = buf
+                .iter()
+                .take_while(|b| **b == b'\n' || **b == b'\r')
+                .count();
+            self.newlines += buf[..skip].iter().filter(|b| **b == b'\n').count();
+            let found = skip < buf.len();
+            self.reader.consume(skip);
+            if found {
+                break;
+            }
         }
-        reader.seek(SeekFrom::Current(-1))?;
+
+        self.line = self.newlines + 1;
+        self.text.clear();
+        self.value_read = false;
 
         let mut buf = Vec::with_capacity(2);
-        let num_bytes = reader.read_until(b'=', &mut buf)?;
+        let num_bytes = self.reader.read_until(b'=', &mut buf)?;
         if num_bytes == 0 {
             return Ok((b"".to_vec(), num_bytes));
         }
         match buf.len() {
-            2 => return Ok((buf, num_bytes)),
-            _ => return Err(Error::SdpInvalidSyntax(String::from_utf8(buf)?)),
+            2 => {
+                self.text.push_str(&String::from_utf8_lossy(&buf));
+                Ok((buf, num_bytes))
+            }
+            _ => {
+                // without a '=' the key runs into the following lines
+                let line = buf.split(|b| *b == b'\n').next().unwrap_or_default();
+                self.text.push_str(String::from_utf8_lossy(line).trim_end());
+                Err(Error::SdpInvalidSyntax(String::from_utf8(buf)?))
+            }
+        }
+    }
+
+    /// read_value reads the rest of the current line
+    pub fn read_value(&mut self) -> Result<(String, usize)> {
+        let mut value = String::new();
+        let num_bytes = self.reader.read_line(&mut value)?;
+        if value.ends_with('\n') {
+            self.newlines += 1;
+            if !self.line_ending_detected {
+                self.line_ending_detected = true;
+                self.sdp.line_ending = if value.ends_with(END_LINE) {
+                    LineEnding::CrLf
+                } else {
+                    LineEnding::Lf
+                };
+            }
+        }
+        let value = value.trim().to_string();
+        self.text.push_str(&value);
+        self.value_read = true;
+        Ok((value, num_bytes))
+    }
+
+    /// warning records a ParseWarning for the current line
+    pub fn warning(&self, message: String) -> ParseWarning {
+        ParseWarning {
+            line: self.line,
+            text: self.text.clone(),
+            message,
+        }
+    }
+
+    /// syntax_error attaches the current line and the position of the
+    /// offending token to an error raised while parsing it
+    pub fn syntax_error(&mut self, err: Error) -> Error {
+        if let Error::SyntaxError { .. } = err {
+            return err;
+        }
+
+        let p = if self.value_read {
+            // point at the rejected token if it can be found, else at the
+            // start of the value
+            match &err {
+                Error::SdpInvalidValue(token) if !token.is_empty() => self.text[2..]
+                    .find(token.as_str())
+                    .map(|i| i + 2)
+                    .unwrap_or(2),
+                _ => 2,
+            }
+        } else {
+            // the type itself is wrong, complete the line for context
+            if self.text.len() == 2 {
+                let mut rest = String::new();
+                if self.reader.read_line(&mut rest).is_ok() {
+                    self.text.push_str(rest.trim_end());
+                }
+            }
+            0
+        };
+
+        Error::SyntaxError {
+            line: self.line,
+            s: self.text.clone(),
+            p,
+            source: Box::new(err),
+        }
+    }
+}
+
+/// SdpLine is a single `<type>=<value>` line borrowed from the input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SdpLine<'a> {
+    /// 1-based line number
+    pub line: usize,
+    pub typ: u8,
+    /// value without the line ending
+    pub value: &'a str,
+}
+
+/// SdpLines splits a session description into lines, accepting CRLF and LF
+/// line endings and skipping empty lines
+pub struct SdpLines<'a> {
+    rest: &'a str,
+    line: usize,
+}
+
+impl<'a> SdpLines<'a> {
+    pub fn new(input: &'a str) -> Self {
+        SdpLines {
+            rest: input,
+            line: 0,
         }
     }
 }
 
-pub fn read_value<R: io::BufRead + io::Seek>(reader: &mut R) -> Result<(String, usize)> {
-    let mut value = String::new();
-    let num_bytes = reader.read_line(&mut value)?;
-    Ok((value.trim().to_string(), num_bytes))
+impl<'a> Iterator for SdpLines<'a> {
+    type Item = Result<SdpLine<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.rest.is_empty() {
+                return None;
+            }
+
+            let (text, rest) = match self.rest.find('\n') {
+                Some(i) => (&self.rest[..i], &self.rest[i + 1..]),
+                None => (self.rest, ""),
+            };
+            self.rest = rest;
+            self.line += 1;
+
+            let text = text.strip_suffix('\r').unwrap_or(text);
+            if text.is_empty() {
+                continue;
+            }
+
+            let bytes = text.as_bytes();
+            if bytes.len() < 2 || bytes[1] != b'=' || !bytes[0].is_ascii_alphabetic() {
+                let typ = text.split('=').next().unwrap_or_default();
+                return Some(Err(Error::SyntaxError {
+                    line: self.line,
+                    s: text.to_owned(),
+                    p: 0,
+                    source: Box::new(Error::SdpInvalidSyntax(typ.to_owned())),
+                }));
+            }
+
+            return Some(Ok(SdpLine {
+                line: self.line,
+                typ: bytes[0],
+                value: text[2..].trim(),
+            }));
+        }
+    }
 }
 
 pub fn index_of(element: &str, data: &[&str]) -> i32 {
@@ -56,11 +307,3 @@ pub fn index_of(element: &str, data: &[&str]) -> i32 {
     }
     -1
 }
-
-pub fn key_value_build(key: &str, value: Option<&String>) -> String {
-    if let Some(val) = value {
-        format!("{}{}{}", key, val, END_LINE)
-    } else {
-        "".to_string()
-    }
-}
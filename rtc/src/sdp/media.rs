@@ -1,17 +1,24 @@
 
-use std::collections::HashMap;
 use std::fmt;
-use url::Url;
 
 use crate::sdp::extmap::*;
 use crate::sdp::common::*;
+use crate::sdp::codec::*;
+use crate::sdp::candidate::*;
+use crate::sdp::simulcast::*;
+use crate::sdp::ssrc::{Msid, SsrcInfo};
+use crate::sdp::error::{Error, Result};
+
+pub const ATTR_KEY_ICE_UFRAG: &str = "ice-ufrag";
+pub const ATTR_KEY_ICE_PWD: &str = "ice-pwd";
 
 /// Constants for extmap key
 pub const TRANSPORT_CC_EXT_VALUE: isize = 3; //EXT_MAP_VALUE_TRANSPORT_CC_KEY
 
 /// MediaDescription represents a media type.
 /// <https://tools.ietf.org/html/rfc4566#section-5.14>
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MediaDescription {
     /// `m=<media> <port>/<number of ports> <proto> <fmt> ...`
     ///
@@ -59,6 +66,184 @@ impl MediaDescription {
         None
     }
 
+    /// codecs returns the codecs of all payload types listed in the "m=" line
+    /// which are described by an "a=rtpmap" attribute, in the order of
+    /// preference given by the "m=" line.
+    pub fn codecs(&self) -> Vec<Codec> {
+        self.media_name
+            .formats
+            .iter()
+            .filter_map(|format| format.parse::<u8>().ok())
+            .filter_map(|payload_type| self.codec_for_payload_type(payload_type).ok())
+            .collect()
+    }
+
+    /// codec_for_payload_type assembles the codec of a payload type from its
+    /// rtpmap, fmtp and rtcp-fb attributes. Static payload types without an
+    /// rtpmap resolve to their RFC 3551 definition.
+    pub fn codec_for_payload_type(&self, payload_type: u8) -> Result<Codec> {
+        let pt = payload_type.to_string();
+        if !self.media_name.formats.contains(&pt) {
+            return Err(Error::PayloadTypeNotFound);
+        }
+
+        let mut codec = None;
+        let mut fmtp = FmtpParams::default();
+        let mut rtcp_feedback = vec![];
+        for a in &self.attributes {
+            let value = match &a.value {
+                Some(value) => value,
+                None => continue,
+            };
+            // "a=rtcp-fb:* <feedback>" applies to every payload type
+            if a.key == ATTR_KEY_RTCP_FB && value.starts_with("* ") {
+                rtcp_feedback.push(parse_rtcp_fb(value)?.1);
+                continue;
+            }
+            if payload_type_of(value) != Some(payload_type) {
+                continue;
+            }
+            match a.key.as_str() {
+                ATTR_KEY_RTPMAP => codec = Some(parse_rtpmap(value)?),
+                ATTR_KEY_FMTP => fmtp = parse_fmtp(value)?.1,
+                ATTR_KEY_RTCP_FB => rtcp_feedback.push(parse_rtcp_fb(value)?.1),
+                _ => {}
+            }
+        }
+
+        let mut codec = codec
+            .or_else(|| Codec::static_payload_type(payload_type))
+            .ok_or(Error::CodecNotFound)?;
+        codec.fmtp = fmtp;
+        codec.rtcp_feedback = rtcp_feedback;
+        Ok(codec)
+    }
+
+    /// payload_type_for_codec returns the payload type of the first codec
+    /// matching name, clock rate and channels. If the given codec carries fmtp
+    /// parameters they have to be equal as well.
+    pub fn payload_type_for_codec(&self, codec: &Codec) -> Result<u8> {
+        self.codecs()
+            .iter()
+            .find(|c| c.matches(codec) && (codec.fmtp.is_empty() || c.fmtp == codec.fmtp))
+            .map(|c| c.payload_type)
+            .ok_or(Error::CodecNotFound)
+    }
+
+    /// add_codec appends the payload type to the "m=" line and writes its
+    /// rtpmap, fmtp and rtcp-fb attributes. An existing codec with the same
+    /// payload type is replaced in place, keeping its preference order.
+    pub fn add_codec(&mut self, codec: &Codec) {
+        let mut attributes = vec![Attribute::new(
+            ATTR_KEY_RTPMAP.to_owned(),
+            Some(codec.to_string()),
+        )];
+        for fb in codec.rtcp_feedback_values() {
+            attributes.push(Attribute::new(ATTR_KEY_RTCP_FB.to_owned(), Some(fb)));
+        }
+        if let Some(fmtp) = codec.fmtp_value() {
+            attributes.push(Attribute::new(ATTR_KEY_FMTP.to_owned(), Some(fmtp)));
+        }
+
+        let pt = codec.payload_type.to_string();
+        if !self.media_name.formats.contains(&pt) {
+            self.media_name.formats.push(pt);
+            self.attributes.extend(attributes);
+            return;
+        }
+
+        let i = self
+            .attributes
+            .iter()
+            .position(|a| is_codec_attribute_of(a, codec.payload_type))
+            .unwrap_or(self.attributes.len());
+        self.attributes
+            .retain(|a| !is_codec_attribute_of(a, codec.payload_type));
+        self.attributes.splice(i..i, attributes);
+    }
+
+    /// remove_codec removes the payload type from the "m=" line together with
+    /// all of its rtpmap, fmtp and rtcp-fb attributes
+    pub fn remove_codec(&mut self, payload_type: u8) -> Result<Codec> {
+        let codec = self.codec_for_payload_type(payload_type);
+        let pt = payload_type.to_string();
+        let pos = self
+            .media_name
+            .formats
+            .iter()
+            .position(|f| *f == pt)
+            .ok_or(Error::PayloadTypeNotFound)?;
+        self.media_name.formats.remove(pos);
+        self.attributes
+            .retain(|a| !is_codec_attribute_of(a, payload_type));
+        codec
+    }
+
+    /// set_codec_fmtp replaces the fmtp parameters of a payload type, empty
+    /// parameters remove the "a=fmtp" line
+    pub fn set_codec_fmtp(&mut self, payload_type: u8, fmtp: &FmtpParams) -> Result<()> {
+        self.codec_for_payload_type(payload_type)?;
+
+        let value = format!("{} {}", payload_type, fmtp);
+        let existing = self.attributes.iter().position(|a| {
+            a.key == ATTR_KEY_FMTP && is_codec_attribute_of(a, payload_type)
+        });
+        match existing {
+            Some(i) if fmtp.is_empty() => {
+                self.attributes.remove(i);
+            }
+            Some(i) => self.attributes[i].value = Some(value),
+            None if fmtp.is_empty() => {}
+            None => {
+                let i = self.codec_insert_position(payload_type);
+                self.attributes
+                    .insert(i, Attribute::new(ATTR_KEY_FMTP.to_owned(), Some(value)));
+            }
+        }
+        Ok(())
+    }
+
+    /// add_codec_feedback adds an "a=rtcp-fb" line for the payload type unless
+    /// the feedback mechanism is already present
+    pub fn add_codec_feedback(&mut self, payload_type: u8, feedback: &str) -> Result<()> {
+        let codec = self.codec_for_payload_type(payload_type)?;
+        if codec.rtcp_feedback.iter().any(|fb| fb == feedback) {
+            return Ok(());
+        }
+
+        let i = self.codec_insert_position(payload_type);
+        self.attributes.insert(
+            i,
+            Attribute::new(
+                ATTR_KEY_RTCP_FB.to_owned(),
+                Some(format!("{} {}", payload_type, feedback)),
+            ),
+        );
+        Ok(())
+    }
+
+    /// remove_codec_feedback removes the "a=rtcp-fb" line of a feedback
+    /// mechanism from the payload type
+    pub fn remove_codec_feedback(&mut self, payload_type: u8, feedback: &str) -> Result<()> {
+        self.codec_for_payload_type(payload_type)?;
+        self.attributes.retain(|a| {
+            !(a.key == ATTR_KEY_RTCP_FB
+                && a.value.as_deref().and_then(|v| parse_rtcp_fb(v).ok())
+                    == Some((Some(payload_type), feedback.to_owned())))
+        });
+        Ok(())
+    }
+
+    /// codec_insert_position returns the index right after the last codec
+    /// attribute of the payload type
+    fn codec_insert_position(&self, payload_type: u8) -> usize {
+        self.attributes
+            .iter()
+            .rposition(|a| is_codec_attribute_of(a, payload_type))
+            .map(|i| i + 1)
+            .unwrap_or(self.attributes.len())
+    }
+
     /// new_jsep_media_description creates a new MediaName with
     /// some settings that are required by the JSEP spec.
     pub fn new_jsep_media_description(codec_type: String, _codec_prefs: Vec<&str>) -> Self {
@@ -112,8 +297,8 @@ impl MediaDescription {
 
     /// with_ice_credentials adds ICE credentials to the media description
     pub fn with_ice_credentials(self, username: String, password: String) -> Self {
-        self.with_value_attribute("ice-ufrag".to_string(), username)
-            .with_value_attribute("ice-pwd".to_string(), password)
+        self.with_value_attribute(ATTR_KEY_ICE_UFRAG.to_string(), username)
+            .with_value_attribute(ATTR_KEY_ICE_PWD.to_string(), password)
     }
 
     /// with_codec adds codec information to the media description
@@ -140,6 +325,7 @@ impl MediaDescription {
     }
 
     /// with_media_source adds media source information to the media description
+    /// Deprecated: use with_ssrc_info instead
     pub fn with_media_source(
         self,
         ssrc: u32,
@@ -147,42 +333,117 @@ impl MediaDescription {
         stream_label: String,
         label: String,
     ) -> Self {
-        self.
-            with_value_attribute("ssrc".to_string(), format!("{} cname:{}", ssrc, cname)). // Deprecated but not phased out?
-            with_value_attribute("ssrc".to_string(), format!("{} msid:{} {}", ssrc, stream_label, label)).
-            with_value_attribute("ssrc".to_string(), format!("{} mslabel:{}", ssrc, stream_label)). // Deprecated but not phased out?
-            with_value_attribute("ssrc".to_string(), format!("{} label:{}", ssrc, label))
-        // Deprecated but not phased out?
+        let info = SsrcInfo::new(ssrc, &cname)
+            .with_msid(Msid::new(&stream_label, &label))
+            .with_labels(&stream_label, &label);
+        self.with_ssrc_info(&info)
     }
 
     /// with_candidate adds an ICE candidate to the media description
-    /// Deprecated: use WithICECandidate instead
+    /// Deprecated: use with_ice_candidate instead
     pub fn with_candidate(self, value: String) -> Self {
         self.with_value_attribute("candidate".to_string(), value)
     }
 
-    pub fn with_extmap(self, e: ExtMap) -> Self {
-        self.with_property_attribute(e.marshal())
+    /// with_ice_candidate adds an ICE candidate to the media description
+    pub fn with_ice_candidate(mut self, candidate: &IceCandidate) -> Self {
+        self.attributes.push(candidate.convert());
+        self
+    }
+
+    /// with_end_of_candidates marks that no further candidates will be
+    /// trickled for this media description
+    pub fn with_end_of_candidates(self) -> Self {
+        self.with_property_attribute(ATTR_KEY_END_OF_CANDIDATES.to_owned())
+    }
+
+    /// candidates parses all "a=candidate" attributes
+    pub fn candidates(&self) -> Result<Vec<IceCandidate>> {
+        self.attributes
+            .iter()
+            .filter(|a| a.is_ice_candidate())
+            .map(|a| IceCandidate::unmarshal(a.value.as_deref().unwrap_or_default()))
+            .collect()
+    }
+
+    /// is_end_of_candidates returns true if "a=end-of-candidates" is present
+    pub fn is_end_of_candidates(&self) -> bool {
+        self.attribute(ATTR_KEY_END_OF_CANDIDATES).is_some()
+    }
+
+    /// with_rid adds an "a=rid" attribute
+    pub fn with_rid(mut self, rid: &Rid) -> Self {
+        self.attributes.push(rid.convert());
+        self
+    }
+
+    /// with_simulcast adds an "a=simulcast" attribute
+    pub fn with_simulcast(mut self, simulcast: &Simulcast) -> Self {
+        self.attributes.push(simulcast.convert());
+        self
     }
 
-    /// with_transport_cc_extmap adds an extmap to the media description
+    /// rids parses all "a=rid" attributes
+    pub fn rids(&self) -> Result<Vec<Rid>> {
+        self.attributes
+            .iter()
+            .filter(|a| a.key == ATTR_KEY_RID)
+            .map(|a| Rid::unmarshal(a.value.as_deref().unwrap_or_default()))
+            .collect()
+    }
+
+    /// simulcast parses the "a=simulcast" attribute and checks that every
+    /// rid-id it lists has a matching "a=rid"
+    pub fn simulcast(&self) -> Result<Option<Simulcast>> {
+        let value = match self.attribute(ATTR_KEY_SIMULCAST) {
+            Some(value) => value.unwrap_or_default(),
+            None => return Ok(None),
+        };
+        let simulcast = Simulcast::unmarshal(value)?;
+        simulcast.validate(&self.rids()?)?;
+        Ok(Some(simulcast))
+    }
+
+    /// with_extmap adds an extmap to the media description
+    pub fn with_extmap(mut self, e: ExtMap) -> Self {
+        self.attributes.push(e.convert());
+        self
+    }
+
+    /// with_transport_cc_extmap adds the transport-wide congestion control
+    /// extmap with id 3, whether or not the id is already taken
     pub fn with_transport_cc_extmap(self) -> Self {
+        self.with_extmap(ExtMap::new(TRANSPORT_CC_EXT_VALUE, TRANSPORT_CC_EXT))
+    }
 
-        let e = ExtMap {
-            value: TRANSPORT_CC_EXT_VALUE,
-            uri_idx: TRANSPORT_CC_EXT_IDX,
-            ..Default::default()
+    /// try_with_transport_cc_extmap adds the transport-wide congestion
+    /// control extmap with id 3, or the lowest free id if 3 is already taken.
+    /// Two-byte ids are only allocated with "a=extmap-allow-mixed" on the
+    /// section.
+    pub fn try_with_transport_cc_extmap(self) -> Result<Self> {
+        let used: Vec<isize> = self.extmaps()?.iter().map(|e| e.value).collect();
+        let value = if used.contains(&TRANSPORT_CC_EXT_VALUE) {
+            let allow_mixed = self.attribute(ATTR_KEY_EXTMAP_ALLOW_MIXED).is_some();
+            allocate_ext_map_id(&used, allow_mixed).ok_or(Error::ExtMapIdsExhausted)?
+        } else {
+            TRANSPORT_CC_EXT_VALUE
         };
 
-        self.with_extmap(e)
+        Ok(self.with_extmap(ExtMap::new(value, TRANSPORT_CC_EXT)))
     }
 }
 
+fn is_codec_attribute_of(a: &Attribute, payload_type: u8) -> bool {
+    matches!(a.key.as_str(), ATTR_KEY_RTPMAP | ATTR_KEY_FMTP | ATTR_KEY_RTCP_FB)
+        && a.value.as_deref().and_then(payload_type_of) == Some(payload_type)
+}
+
 /// RangedPort supports special format for the media field "m=" port value. If
 /// it may be necessary to specify multiple transport ports, the protocol allows
 /// to write it as: <port>/<number of ports> where number of ports is a an
 /// offsetting range.
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RangedPort {
     pub value: isize,
     pub range: Option<isize>,
@@ -199,7 +460,8 @@ impl fmt::Display for RangedPort {
 }
 
 /// MediaName describes the "m=" field storage structure.
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MediaName {
     pub media: String,
     pub port: RangedPort,
@@ -209,13 +471,21 @@ pub struct MediaName {
 
 impl fmt::Display for MediaName {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let s = vec![
-            self.media.clone(),
-            self.port.to_string(),
-            self.protos.join("/"),
-            self.formats.join(" "),
-        ];
-        write!(f, "{}", s.join(" "))
+        write!(f, "{} {} ", self.media, self.port)?;
+        for (i, proto) in self.protos.iter().enumerate() {
+            if i > 0 {
+                write!(f, "/")?;
+            }
+            write!(f, "{}", proto)?;
+        }
+        write!(f, " ")?;
+        for (i, format) in self.formats.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}", format)?;
+        }
+        Ok(())
     }
 }
 
@@ -1,26 +1,45 @@
 #[cfg(test)]
 mod sdp_test;
 
+use std::fmt;
 use std::io::Cursor;
 use std::io;
-
-mod session;
-mod time;
-mod media;
-mod common;
-mod error;
-mod direction;
+use std::net::IpAddr;
+use std::str::FromStr;
+
+pub mod session;
+pub mod time;
+pub mod media;
+pub mod common;
+pub mod error;
+pub mod direction;
 mod lexer;
+pub mod codec;
+pub mod jsep;
+pub mod candidate;
+pub mod simulcast;
+pub mod group;
+pub mod dtls;
+pub mod effective;
+pub mod validate;
+pub mod datachannel;
+pub mod diff;
+pub mod ssrc;
 pub mod extmap;
 
-use common::*;
-use session::*;
-use time::*;
-use media::*;
-use error::*;
+pub use common::{Address, Attribute, Bandwidth, ConnectionInformation};
+pub use direction::Direction;
+pub use error::{Error, Result};
+pub use lexer::{LineEnding, ParseWarning, SdpLine, SdpLines, UnmarshalOptions};
+pub use media::{MediaDescription, MediaName, RangedPort, ATTR_KEY_ICE_PWD, ATTR_KEY_ICE_UFRAG};
+pub use session::{Origin, Session, TimeZone};
+pub use time::{RepeatTime, TimeDescription, Timing};
+
 use lexer::*;
 use url::Url;
 
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SDP {
     pub session: Session,
 
@@ -31,8 +50,8 @@ pub struct SDP {
     /// <https://tools.ietf.org/html/rfc4566#section-5.14>
     pub media_descriptions: Vec<MediaDescription>,
 
-    // todo datachannel
-    // pub application: AppDescription,
+    /// line terminator used by `marshal`, taken from the input when parsed
+    pub line_ending: LineEnding,
 }
 
 //    Some lines in each description are REQUIRED and some are OPTIONAL,
@@ -97,96 +116,202 @@ pub struct SDP {
     /// | 15 |   mo_bbkaa                      |   |   |   |   |   |   |   |   |15 |   |   |   |16 |16 |13 |
     /// | 16 |   mo_aa                         |   |   |   |   |   |   |   |   |   |   |   |   |   |16 |13 |
     /// +----+---------------------------------+---+---+---+---+---+---+---+---+---+---+---+---+---+---+---+
-
 impl SDP {
-    pub fn unmarshal(literalSdp: &[u8]) -> Result<Self> {
-        let mut reader = Cursor::new(literalSdp);
-
-        let mut lexer = Lexer {
-            sdp: SDP {
-                session: Session::default(),
-                time_descriptions: vec![],
-                media_descriptions: vec![],
-            },
-            reader: &mut reader,
-        };
+    pub fn unmarshal(literal_sdp: &[u8]) -> Result<Self> {
+        let mut reader = Cursor::new(literal_sdp);
+        SDP::unmarshal_from(&mut reader)
+    }
+
+    /// unmarshal_from parses a session description from any buffered
+    /// reader, e.g. a pipe or a TCP stream, until the end of the input
+    pub fn unmarshal_from<R: io::BufRead>(reader: &mut R) -> Result<Self> {
+        let mut lexer = Lexer::new(reader);
 
         let mut state = Some(StateFn { f: s_v });
         while let Some(s) = state {
-            state = (s.f)(&mut lexer)?;
+            state = (s.f)(&mut lexer).map_err(|err| lexer.syntax_error(err))?;
         }
 
         Ok(lexer.sdp)
     }
 
+    /// lines iterates over the `<type>=<value>` lines of a session
+    /// description without parsing or copying the values
+    pub fn lines(literal_sdp: &str) -> SdpLines<'_> {
+        SdpLines::new(literal_sdp)
+    }
+
+    /// unmarshal_with_options parses a session description, in lenient mode
+    /// deviations from RFC 4566 are accepted and returned as warnings
+    pub fn unmarshal_with_options(
+        literal_sdp: &[u8],
+        options: &UnmarshalOptions,
+    ) -> Result<(Self, Vec<ParseWarning>)> {
+        if options.strict {
+            return Ok((SDP::unmarshal(literal_sdp)?, vec![]));
+        }
+
+        let mut reader = Cursor::new(literal_sdp);
+        let mut lexer = Lexer::new(&mut reader);
+        let mut warnings = vec![];
+        unmarshal_lenient(&mut lexer, options, &mut warnings)
+            .map_err(|err| lexer.syntax_error(err))?;
+
+        Ok((lexer.sdp, warnings))
+    }
+
+    /// update_origin prepares a subsequent local offer or answer: the origin
+    /// of `previous` is carried over so that username, session id and
+    /// address stay stable, and its session version is incremented only if
+    /// anything besides the origin changed. Returns whether the version was
+    /// incremented.
+    /// <https://tools.ietf.org/html/rfc8829#section-5.2.2>
+    pub fn update_origin(&mut self, previous: &SDP) -> bool {
+        let origin = previous.session.origin.clone();
+        self.session.origin = origin.clone();
+
+        // the line ending is a serialization detail, not content
+        let mut unchanged = previous.clone();
+        unchanged.line_ending = self.line_ending;
+        let changed = *self != unchanged;
+        if changed {
+            self.session.origin.session_version = origin.session_version.wrapping_add(1);
+        }
+        changed
+    }
+
+    /// marshal renders the session description as a string, see
+    /// `write_to`
     pub fn marshal(sdp: &SDP) -> String {
-        let mut result = String::new();
+        // every line is written from Display implementations, i.e. UTF-8
+        String::from_utf8(sdp.serialize()).expect("SDP is valid UTF-8")
+    }
 
-        result += key_value_build("v=", Some(&sdp.session.version.to_string())).as_str();
-        result += key_value_build("o=", Some(&sdp.session.origin.to_string())).as_str();
-        result += key_value_build("s=", Some(&sdp.session.session_name)).as_str();
+    /// serialize renders the session description into a buffer sized by
+    /// `marshal_size`, `marshal` returns the same bytes as a string.
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut srl = Vec::<u8>::with_capacity(self.marshal_size());
+        // writing into a Vec<u8> cannot fail
+        self.write_to(&mut srl).expect("write to Vec<u8>");
+        srl
+    }
 
-        result += key_value_build("i=", sdp.session.session_information.as_ref()).as_str();
+    /// marshal_size returns the number of bytes `write_to` produces
+    pub fn marshal_size(&self) -> usize {
+        let mut counter = ByteCounter(0);
+        // ByteCounter never returns an error
+        let _ = self.write_to(&mut counter);
+        counter.0
+    }
 
-        if let Some(uri) = &sdp.session.uri {
-            result += key_value_build("u=", Some(&format!("{}", uri))).as_str();
+    /// write_to writes the session description line by line into `w`
+    /// without building intermediate strings.
+    pub fn write_to<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        let eol = self.line_ending.as_str().as_bytes();
+        write_line(w, b"v=", &self.session.version, eol)?;
+        write_line(w, b"o=", &self.session.origin, eol)?;
+        write_line(w, b"s=", &self.session.session_name, eol)?;
+        write_opt_line(w, b"i=", &self.session.session_information, eol)?;
+        if let Some(uri) = &self.session.uri {
+            write_line(w, b"u=", uri, eol)?;
         }
-        result += key_value_build("e=", sdp.session.email_address.as_ref()).as_str();
-        result += key_value_build("p=", sdp.session.phone_number.as_ref()).as_str();
-        if let Some(connection_information) = &sdp.session.connection_information {
-            result += key_value_build("c=", Some(&connection_information.to_string())).as_str();
+        write_opt_line(w, b"e=", &self.session.email_address, eol)?;
+        write_opt_line(w, b"p=", &self.session.phone_number, eol)?;
+        if let Some(connection_information) = &self.session.connection_information {
+            write_line(w, b"c=", connection_information, eol)?;
         }
-
-        for bandwidth in &sdp.session.bandwidth {
-            result += key_value_build("b=", Some(&bandwidth.to_string())).as_str();
+        for bandwidth in &self.session.bandwidth {
+            write_line(w, b"b=", bandwidth, eol)?;
         }
-        for time_description in &sdp.time_descriptions {
-            result += key_value_build("t=", Some(&time_description.timing.to_string())).as_str();
+        for time_description in &self.time_descriptions {
+            write_line(w, b"t=", &time_description.timing, eol)?;
             for repeat_time in &time_description.repeat_times {
-                result += key_value_build("r=", Some(&repeat_time.to_string())).as_str();
+                write_line(w, b"r=", repeat_time, eol)?;
             }
         }
-        if !sdp.session.time_zones.is_empty() {
-            let mut time_zones = vec![];
-            for time_zone in &sdp.session.time_zones {
-                time_zones.push(time_zone.to_string());
+        if !self.session.time_zones.is_empty() {
+            w.write_all(b"z=")?;
+            for (i, time_zone) in self.session.time_zones.iter().enumerate() {
+                if i > 0 {
+                    w.write_all(b" ")?;
+                }
+                write!(w, "{}", time_zone)?;
             }
-            result += key_value_build("z=", Some(&time_zones.join(" "))).as_str();
+            w.write_all(eol)?;
         }
-        result += key_value_build("k=", sdp.session.encryption_key.as_ref()).as_str();
-        for attribute in &sdp.session.attributes {
-            result += key_value_build("a=", Some(&attribute.to_string())).as_str();
+        write_opt_line(w, b"k=", &self.session.encryption_key, eol)?;
+        for attribute in &self.session.attributes {
+            write_line(w, b"a=", attribute, eol)?;
         }
 
-        for media_description in &sdp.media_descriptions {
-            result +=
-                key_value_build("m=", Some(&media_description.media_name.to_string())).as_str();
-            result += key_value_build("i=", media_description.media_title.as_ref()).as_str();
+        for media_description in &self.media_descriptions {
+            write_line(w, b"m=", &media_description.media_name, eol)?;
+            write_opt_line(w, b"i=", &media_description.media_title, eol)?;
             if let Some(connection_information) = &media_description.connection_information {
-                result += key_value_build("c=", Some(&connection_information.to_string())).as_str();
+                write_line(w, b"c=", connection_information, eol)?;
             }
             for bandwidth in &media_description.bandwidth {
-                result += key_value_build("b=", Some(&bandwidth.to_string())).as_str();
+                write_line(w, b"b=", bandwidth, eol)?;
             }
-            result += key_value_build("k=", media_description.encryption_key.as_ref()).as_str();
+            write_opt_line(w, b"k=", &media_description.encryption_key, eol)?;
             for attribute in &media_description.attributes {
-                result += key_value_build("a=", Some(&attribute.to_string())).as_str();
+                write_line(w, b"a=", attribute, eol)?;
             }
         }
 
-        result
+        Ok(())
     }
+}
 
-    pub fn serialize(&self) -> Vec<u8> {
-        let mut srl = Vec::<u8>::with_capacity(1024);
-        
+impl FromStr for SDP {
+    type Err = Error;
 
-        srl
+    fn from_str(literal_sdp: &str) -> Result<Self> {
+        SDP::unmarshal(literal_sdp.as_bytes())
+    }
+}
+
+/// ByteCounter is an io::Write sink that only counts the bytes written
+struct ByteCounter(usize);
+
+impl io::Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
     }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn write_line<W: io::Write, T: fmt::Display + ?Sized>(
+    w: &mut W,
+    key: &[u8],
+    value: &T,
+    eol: &[u8],
+) -> io::Result<()> {
+    w.write_all(key)?;
+    write!(w, "{}", value)?;
+    w.write_all(eol)
+}
+
+fn write_opt_line<W: io::Write>(
+    w: &mut W,
+    key: &[u8],
+    value: &Option<String>,
+    eol: &[u8],
+) -> io::Result<()> {
+    if let Some(value) = value {
+        w.write_all(key)?;
+        w.write_all(value.as_bytes())?;
+        w.write_all(eol)?;
+    }
+    Ok(())
 }
 
-fn s_v<'a, R: io::BufRead + io::Seek>(lexer: &mut Lexer<'a, R>) -> Result<Option<StateFn<'a, R>>> {
-    let (key, _) = read_type(lexer.reader)?;
+fn s_v<'a, R: io::BufRead>(lexer: &mut Lexer<'a, R>) -> Result<Option<StateFn<'a, R>>> {
+    let (key, _) = lexer.read_type()?;
     if &key == b"v=" {
         return Ok(Some(StateFn {
             f: unmarshal_protocol_version,
@@ -196,8 +321,8 @@ fn s_v<'a, R: io::BufRead + io::Seek>(lexer: &mut Lexer<'a, R>) -> Result<Option
     Err(Error::SdpInvalidSyntax(String::from_utf8(key)?))
 }
 
-fn s_o<'a, R: io::BufRead + io::Seek>(lexer: &mut Lexer<'a, R>) -> Result<Option<StateFn<'a, R>>> {
-    let (key, _) = read_type(lexer.reader)?;
+fn s_o<'a, R: io::BufRead>(lexer: &mut Lexer<'a, R>) -> Result<Option<StateFn<'a, R>>> {
+    let (key, _) = lexer.read_type()?;
     if &key == b"o=" {
         return Ok(Some(StateFn {
             f: unmarshal_origin,
@@ -207,8 +332,8 @@ fn s_o<'a, R: io::BufRead + io::Seek>(lexer: &mut Lexer<'a, R>) -> Result<Option
     Err(Error::SdpInvalidSyntax(String::from_utf8(key)?))
 }
 
-fn s_s<'a, R: io::BufRead + io::Seek>(lexer: &mut Lexer<'a, R>) -> Result<Option<StateFn<'a, R>>> {
-    let (key, _) = read_type(lexer.reader)?;
+fn s_s<'a, R: io::BufRead>(lexer: &mut Lexer<'a, R>) -> Result<Option<StateFn<'a, R>>> {
+    let (key, _) = lexer.read_type()?;
     if &key == b"s=" {
         return Ok(Some(StateFn {
             f: unmarshal_session_name,
@@ -218,8 +343,8 @@ fn s_s<'a, R: io::BufRead + io::Seek>(lexer: &mut Lexer<'a, R>) -> Result<Option
     Err(Error::SdpInvalidSyntax(String::from_utf8(key)?))
 }
 
-fn so_iuepcbb_or_t_t<'a, R: io::BufRead + io::Seek>(lexer: &mut Lexer<'a, R>) -> Result<Option<StateFn<'a, R>>> {
-    let (key, _) = read_type(lexer.reader)?;
+fn so_iuepcbb_or_t_t<'a, R: io::BufRead>(lexer: &mut Lexer<'a, R>) -> Result<Option<StateFn<'a, R>>> {
+    let (key, _) = lexer.read_type()?;
     match key.as_slice() {
         b"i=" => Ok(Some(StateFn {
             f: unmarshal_session_information,
@@ -240,8 +365,8 @@ fn so_iuepcbb_or_t_t<'a, R: io::BufRead + io::Seek>(lexer: &mut Lexer<'a, R>) ->
     }
 }
 
-fn so_uepcbb_or_t_t<'a, R: io::BufRead + io::Seek>(lexer: &mut Lexer<'a, R>) -> Result<Option<StateFn<'a, R>>> {
-    let (key, _) = read_type(lexer.reader)?;
+fn so_uepcbb_or_t_t<'a, R: io::BufRead>(lexer: &mut Lexer<'a, R>) -> Result<Option<StateFn<'a, R>>> {
+    let (key, _) = lexer.read_type()?;
     match key.as_slice() {
         b"u=" => Ok(Some(StateFn { f: unmarshal_uri })),
         b"e=" => Ok(Some(StateFn { f: unmarshal_email })),
@@ -259,8 +384,8 @@ fn so_uepcbb_or_t_t<'a, R: io::BufRead + io::Seek>(lexer: &mut Lexer<'a, R>) ->
     }
 }
 
-fn so_epcbb_or_t_t<'a, R: io::BufRead + io::Seek>(lexer: &mut Lexer<'a, R>) -> Result<Option<StateFn<'a, R>>> {
-    let (key, _) = read_type(lexer.reader)?;
+fn so_epcbb_or_t_t<'a, R: io::BufRead>(lexer: &mut Lexer<'a, R>) -> Result<Option<StateFn<'a, R>>> {
+    let (key, _) = lexer.read_type()?;
     match key.as_slice() {
         b"e=" => Ok(Some(StateFn { f: unmarshal_email })),
         b"p=" => Ok(Some(StateFn { f: unmarshal_phone })),
@@ -277,8 +402,8 @@ fn so_epcbb_or_t_t<'a, R: io::BufRead + io::Seek>(lexer: &mut Lexer<'a, R>) -> R
     }
 }
 
-fn so_pcbb_or_t_t<'a, R: io::BufRead + io::Seek>(lexer: &mut Lexer<'a, R>) -> Result<Option<StateFn<'a, R>>> {
-    let (key, _) = read_type(lexer.reader)?;
+fn so_pcbb_or_t_t<'a, R: io::BufRead>(lexer: &mut Lexer<'a, R>) -> Result<Option<StateFn<'a, R>>> {
+    let (key, _) = lexer.read_type()?;
     match key.as_slice() {
         b"p=" => Ok(Some(StateFn { f: unmarshal_phone })),
         b"c=" => Ok(Some(StateFn {
@@ -294,8 +419,8 @@ fn so_pcbb_or_t_t<'a, R: io::BufRead + io::Seek>(lexer: &mut Lexer<'a, R>) -> Re
     }
 }
 
-fn so_cbb_or_t_t<'a, R: io::BufRead + io::Seek>(lexer: &mut Lexer<'a, R>) -> Result<Option<StateFn<'a, R>>> {
-    let (key, _) = read_type(lexer.reader)?;
+fn so_cbb_or_t_t<'a, R: io::BufRead>(lexer: &mut Lexer<'a, R>) -> Result<Option<StateFn<'a, R>>> {
+    let (key, _) = lexer.read_type()?;
     match key.as_slice() {
         b"c=" => Ok(Some(StateFn {
             f: unmarshal_session_connection_information,
@@ -310,8 +435,8 @@ fn so_cbb_or_t_t<'a, R: io::BufRead + io::Seek>(lexer: &mut Lexer<'a, R>) -> Res
     }
 }
 
-fn so_bb_or_t_t<'a, R: io::BufRead + io::Seek>(lexer: &mut Lexer<'a, R>) -> Result<Option<StateFn<'a, R>>> {
-    let (key, _) = read_type(lexer.reader)?;
+fn so_bb_or_t_t<'a, R: io::BufRead>(lexer: &mut Lexer<'a, R>) -> Result<Option<StateFn<'a, R>>> {
+    let (key, _) = lexer.read_type()?;
     match key.as_slice() {
         b"b=" => Ok(Some(StateFn {
             f: unmarshal_session_bandwidth,
@@ -323,8 +448,8 @@ fn so_bb_or_t_t<'a, R: io::BufRead + io::Seek>(lexer: &mut Lexer<'a, R>) -> Resu
     }
 }
 
-fn to_rr_or_so_zkaa_or_m<'a, R: io::BufRead + io::Seek>(lexer: &mut Lexer<'a, R>) -> Result<Option<StateFn<'a, R>>> {
-    let (key, num_bytes) = read_type(lexer.reader)?;
+fn to_rr_or_so_zkaa_or_m<'a, R: io::BufRead>(lexer: &mut Lexer<'a, R>) -> Result<Option<StateFn<'a, R>>> {
+    let (key, num_bytes) = lexer.read_type()?;
     if key.is_empty() && num_bytes == 0 {
         return Ok(None);
     }
@@ -352,8 +477,8 @@ fn to_rr_or_so_zkaa_or_m<'a, R: io::BufRead + io::Seek>(lexer: &mut Lexer<'a, R>
     }
 }
 
-fn so_kaa_or_m<'a, R: io::BufRead + io::Seek>(lexer: &mut Lexer<'a, R>) -> Result<Option<StateFn<'a, R>>> {
-    let (key, num_bytes) = read_type(lexer.reader)?;
+fn so_kaa_or_m<'a, R: io::BufRead>(lexer: &mut Lexer<'a, R>) -> Result<Option<StateFn<'a, R>>> {
+    let (key, num_bytes) = lexer.read_type()?;
     if key.is_empty() && num_bytes == 0 {
         return Ok(None);
     }
@@ -372,8 +497,8 @@ fn so_kaa_or_m<'a, R: io::BufRead + io::Seek>(lexer: &mut Lexer<'a, R>) -> Resul
     }
 }
 
-fn so_aa_or_m<'a, R: io::BufRead + io::Seek>(lexer: &mut Lexer<'a, R>) -> Result<Option<StateFn<'a, R>>> {
-    let (key, num_bytes) = read_type(lexer.reader)?;
+fn so_aa_or_m<'a, R: io::BufRead>(lexer: &mut Lexer<'a, R>) -> Result<Option<StateFn<'a, R>>> {
+    let (key, num_bytes) = lexer.read_type()?;
     if key.is_empty() && num_bytes == 0 {
         return Ok(None);
     }
@@ -389,8 +514,8 @@ fn so_aa_or_m<'a, R: io::BufRead + io::Seek>(lexer: &mut Lexer<'a, R>) -> Result
     }
 }
 
-fn moall<'a, R: io::BufRead + io::Seek>(lexer: &mut Lexer<'a, R>) -> Result<Option<StateFn<'a, R>>> {
-    let (key, num_bytes) = read_type(lexer.reader)?;
+fn moall<'a, R: io::BufRead>(lexer: &mut Lexer<'a, R>) -> Result<Option<StateFn<'a, R>>> {
+    let (key, num_bytes) = lexer.read_type()?;
     if key.is_empty() && num_bytes == 0 {
         return Ok(None);
     }
@@ -418,8 +543,8 @@ fn moall<'a, R: io::BufRead + io::Seek>(lexer: &mut Lexer<'a, R>) -> Result<Opti
     }
 }
 
-fn mo_cbbkaa<'a, R: io::BufRead + io::Seek>(lexer: &mut Lexer<'a, R>) -> Result<Option<StateFn<'a, R>>> {
-    let (key, num_bytes) = read_type(lexer.reader)?;
+fn mo_cbbkaa<'a, R: io::BufRead>(lexer: &mut Lexer<'a, R>) -> Result<Option<StateFn<'a, R>>> {
+    let (key, num_bytes) = lexer.read_type()?;
     if key.is_empty() && num_bytes == 0 {
         return Ok(None);
     }
@@ -448,8 +573,8 @@ fn mo_cbbkaa<'a, R: io::BufRead + io::Seek>(lexer: &mut Lexer<'a, R>) -> Result<
     }
 }
 
-fn mo_bbkaa<'a, R: io::BufRead + io::Seek>(lexer: &mut Lexer<'a, R>) -> Result<Option<StateFn<'a, R>>> {
-    let (key, num_bytes) = read_type(lexer.reader)?;
+fn mo_bbkaa<'a, R: io::BufRead>(lexer: &mut Lexer<'a, R>) -> Result<Option<StateFn<'a, R>>> {
+    let (key, num_bytes) = lexer.read_type()?;
     if key.is_empty() && num_bytes == 0 {
         return Ok(None);
     }
@@ -479,8 +604,8 @@ fn mo_bbkaa<'a, R: io::BufRead + io::Seek>(lexer: &mut Lexer<'a, R>) -> Result<O
     }
 }
 
-fn mo_aa<'a, R: io::BufRead + io::Seek>(lexer: &mut Lexer<'a, R>) -> Result<Option<StateFn<'a, R>>> {
-    let (key, num_bytes) = read_type(lexer.reader)?;
+fn mo_aa<'a, R: io::BufRead>(lexer: &mut Lexer<'a, R>) -> Result<Option<StateFn<'a, R>>> {
+    let (key, num_bytes) = lexer.read_type()?;
     if key.is_empty() && num_bytes == 0 {
         return Ok(None);
     }
@@ -512,10 +637,114 @@ fn mo_aa<'a, R: io::BufRead + io::Seek>(lexer: &mut Lexer<'a, R>) -> Result<Opti
     }
 }
 
-fn unmarshal_protocol_version<'a, R: io::BufRead + io::Seek>(
+/// Session and media line types in the order given by RFC 4566 section 5
+const SESSION_LINE_ORDER: &[u8] = b"vosiuepcbtrzka";
+const MEDIA_LINE_ORDER: &[u8] = b"micbka";
+const REQUIRED_SESSION_LINES: &[u8] = b"vost";
+
+/// unmarshal_lenient dispatches every line to its value parser regardless of
+/// the state machine above. Lines before the first "m=" belong to the
+/// session, later ones to the latest media description.
+fn unmarshal_lenient<'a, R: io::BufRead>(
+    lexer: &mut Lexer<'a, R>,
+    options: &UnmarshalOptions,
+    warnings: &mut Vec<ParseWarning>,
+) -> Result<()> {
+    let mut seen = vec![];
+    let mut last_order = 0;
+    loop {
+        let (key, num_bytes) = lexer.read_type()?;
+        if key.is_empty() && num_bytes == 0 {
+            break;
+        }
+
+        let in_media = !lexer.sdp.media_descriptions.is_empty();
+        let line_type = key[0];
+        let f: Option<StateFnType<'a, R>> = match (in_media, line_type) {
+            (_, b'm') => Some(unmarshal_media_description),
+            (false, b'v') => Some(unmarshal_protocol_version),
+            (false, b'o') => Some(unmarshal_origin),
+            (false, b's') => Some(unmarshal_session_name),
+            (false, b'i') => Some(unmarshal_session_information),
+            (false, b'u') => Some(unmarshal_uri),
+            (false, b'e') => Some(unmarshal_email),
+            (false, b'p') => Some(unmarshal_phone),
+            (false, b'c') => Some(unmarshal_session_connection_information),
+            (false, b'b') => Some(unmarshal_session_bandwidth),
+            (false, b't') => Some(unmarshal_timing),
+            (false, b'r') => Some(unmarshal_repeat_times),
+            (false, b'z') => Some(unmarshal_time_zones),
+            (false, b'k') => Some(unmarshal_session_encryption_key),
+            (false, b'a') => Some(unmarshal_session_attribute),
+            (true, b'i') => Some(unmarshal_media_title),
+            (true, b'c') => Some(unmarshal_media_connection_information),
+            (true, b'b') => Some(unmarshal_media_bandwidth),
+            (true, b'k') => Some(unmarshal_media_encryption_key),
+            (true, b'a') => Some(unmarshal_media_attribute),
+            _ => None,
+        };
+
+        let f = match f {
+            Some(f) => f,
+            None => {
+                let known = SESSION_LINE_ORDER.contains(&line_type);
+                if !known && !options.allow_unknown_types {
+                    return Err(Error::SdpInvalidSyntax(String::from_utf8(key)?));
+                }
+                lexer.read_value()?;
+                let message = if known {
+                    "session-level line inside a media description ignored"
+                } else {
+                    "unknown line type ignored"
+                };
+                warnings.push(lexer.warning(message.to_owned()));
+                continue;
+            }
+        };
+
+        let line_order = if in_media || line_type == b'm' {
+            MEDIA_LINE_ORDER
+        } else {
+            SESSION_LINE_ORDER
+        };
+        let order = line_order
+            .iter()
+            .position(|t| *t == line_type)
+            .unwrap_or_default();
+        if line_type == b'm' {
+            last_order = 0;
+        }
+
+        (f)(lexer)?;
+
+        // "t=" may follow the "r=" lines of the previous time description
+        let repeated_time = line_type == b't' && seen.last() == Some(&b'r');
+        if order < last_order && !repeated_time {
+            warnings.push(lexer.warning(format!("`{}=` line out of order", line_type as char)));
+        }
+        last_order = last_order.max(order);
+        if !in_media {
+            seen.push(line_type);
+        }
+    }
+
+    for line_type in REQUIRED_SESSION_LINES {
+        if !seen.contains(line_type) {
+            warnings.push(ParseWarning {
+                line: 0,
+                text: String::new(),
+                message: format!("missing required `{}=` line", *line_type as char),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+fn unmarshal_protocol_version<'a, R: io::BufRead>(
     lexer: &mut Lexer<'a, R>,
 ) -> Result<Option<StateFn<'a, R>>> {
-    let (value, _) = read_value(lexer.reader)?;
+    let (value, _) = lexer.read_value()?;
 
     let version = value.parse::<isize>()?;
 
@@ -529,10 +758,10 @@ fn unmarshal_protocol_version<'a, R: io::BufRead + io::Seek>(
     Ok(Some(StateFn { f: s_o }))
 }
 
-fn unmarshal_origin<'a, R: io::BufRead + io::Seek>(
+fn unmarshal_origin<'a, R: io::BufRead>(
     lexer: &mut Lexer<'a, R>,
 ) -> Result<Option<StateFn<'a, R>>> {
-    let (value, _) = read_value(lexer.reader)?;
+    let (value, _) = lexer.read_value()?;
 
     let fields: Vec<&str> = value.split_whitespace().collect();
     if fields.len() != 6 {
@@ -570,50 +799,50 @@ fn unmarshal_origin<'a, R: io::BufRead + io::Seek>(
     Ok(Some(StateFn { f: s_s }))
 }
 
-fn unmarshal_session_name<'a, R: io::BufRead + io::Seek>(
+fn unmarshal_session_name<'a, R: io::BufRead>(
     lexer: &mut Lexer<'a, R>,
 ) -> Result<Option<StateFn<'a, R>>> {
-    let (value, _) = read_value(lexer.reader)?;
+    let (value, _) = lexer.read_value()?;
     lexer.sdp.session.session_name = value;
     Ok(Some(StateFn { f: so_iuepcbb_or_t_t }))
 }
 
-fn unmarshal_session_information<'a, R: io::BufRead + io::Seek>(
+fn unmarshal_session_information<'a, R: io::BufRead>(
     lexer: &mut Lexer<'a, R>,
 ) -> Result<Option<StateFn<'a, R>>> {
-    let (value, _) = read_value(lexer.reader)?;
+    let (value, _) = lexer.read_value()?;
     lexer.sdp.session.session_information = Some(value);
     Ok(Some(StateFn { f: so_uepcbb_or_t_t }))
 }
 
-fn unmarshal_uri<'a, R: io::BufRead + io::Seek>(
+fn unmarshal_uri<'a, R: io::BufRead>(
     lexer: &mut Lexer<'a, R>,
 ) -> Result<Option<StateFn<'a, R>>> {
-    let (value, _) = read_value(lexer.reader)?;
+    let (value, _) = lexer.read_value()?;
     lexer.sdp.session.uri = Some(Url::parse(&value)?);
     Ok(Some(StateFn { f: so_epcbb_or_t_t }))
 }
 
-fn unmarshal_email<'a, R: io::BufRead + io::Seek>(
+fn unmarshal_email<'a, R: io::BufRead>(
     lexer: &mut Lexer<'a, R>,
 ) -> Result<Option<StateFn<'a, R>>> {
-    let (value, _) = read_value(lexer.reader)?;
+    let (value, _) = lexer.read_value()?;
     lexer.sdp.session.email_address = Some(value);
     Ok(Some(StateFn { f: so_pcbb_or_t_t }))
 }
 
-fn unmarshal_phone<'a, R: io::BufRead + io::Seek>(
+fn unmarshal_phone<'a, R: io::BufRead>(
     lexer: &mut Lexer<'a, R>,
 ) -> Result<Option<StateFn<'a, R>>> {
-    let (value, _) = read_value(lexer.reader)?;
+    let (value, _) = lexer.read_value()?;
     lexer.sdp.session.phone_number = Some(value);
     Ok(Some(StateFn { f: so_cbb_or_t_t }))
 }
 
-fn unmarshal_session_connection_information<'a, R: io::BufRead + io::Seek>(
+fn unmarshal_session_connection_information<'a, R: io::BufRead>(
     lexer: &mut Lexer<'a, R>,
 ) -> Result<Option<StateFn<'a, R>>> {
-    let (value, _) = read_value(lexer.reader)?;
+    let (value, _) = lexer.read_value()?;
     lexer.sdp.session.connection_information = unmarshal_connection_information(&value)?;
     Ok(Some(StateFn { f: so_bb_or_t_t }))
 }
@@ -639,11 +868,7 @@ fn unmarshal_connection_information(value: &str) -> Result<Option<ConnectionInfo
     }
 
     let address = if fields.len() > 2 {
-        Some(Address {
-            address: fields[2].to_owned(),
-            ttl: None,
-            range: None,
-        })
+        Some(unmarshal_address(fields[1], fields[2])?)
     } else {
         None
     };
@@ -655,11 +880,64 @@ fn unmarshal_connection_information(value: &str) -> Result<Option<ConnectionInfo
     }))
 }
 
+/// unmarshal_address parses the connection-address of a "c=" line
+/// <https://tools.ietf.org/html/rfc4566#section-5.7>
+///
+/// IP4 multicast addresses carry a mandatory TTL and an optional range
+/// (`224.2.1.1/127/3`), IP6 multicast addresses only an optional range
+/// (`FF15::101/3`). Unicast addresses and FQDNs take neither.
+fn unmarshal_address(address_type: &str, value: &str) -> Result<Address> {
+    let mut parts = value.split('/');
+    let mut address = Address {
+        address: parts.next().unwrap_or_default().to_owned(),
+        ttl: None,
+        range: None,
+    };
+    let suffixes: Vec<&str> = parts.collect();
+
+    let ip = address.ip_addr();
+    let family_matches = match ip {
+        Some(IpAddr::V4(_)) => address_type == "IP4",
+        Some(IpAddr::V6(_)) => address_type == "IP6",
+        None => true,
+    };
+    if !family_matches {
+        return Err(Error::SdpInvalidValue(address.address));
+    }
+
+    if suffixes.is_empty() {
+        return Ok(address);
+    }
+    if !address.is_multicast() {
+        return Err(Error::SdpInvalidValue(value.to_owned()));
+    }
+
+    let parse_ttl = |ttl: &str| match ttl.parse::<isize>() {
+        Ok(ttl) if (0..=255).contains(&ttl) => Ok(ttl),
+        _ => Err(Error::SdpInvalidValue(ttl.to_owned())),
+    };
+    let parse_range = |range: &str| match range.parse::<isize>() {
+        Ok(range) if range > 0 => Ok(range),
+        _ => Err(Error::SdpInvalidValue(range.to_owned())),
+    };
+
+    match (address_type, suffixes.as_slice()) {
+        ("IP4", [ttl]) => address.ttl = Some(parse_ttl(ttl)?),
+        ("IP4", [ttl, range]) => {
+            address.ttl = Some(parse_ttl(ttl)?);
+            address.range = Some(parse_range(range)?);
+        }
+        ("IP6", [range]) => address.range = Some(parse_range(range)?),
+        _ => return Err(Error::SdpInvalidValue(value.to_owned())),
+    }
 
-fn unmarshal_session_bandwidth<'a, R: io::BufRead + io::Seek>(
+    Ok(address)
+}
+
+fn unmarshal_session_bandwidth<'a, R: io::BufRead>(
     lexer: &mut Lexer<'a, R>,
 ) -> Result<Option<StateFn<'a, R>>> {
-    let (value, _) = read_value(lexer.reader)?;
+    let (value, _) = lexer.read_value()?;
     lexer.sdp.session.bandwidth.push(unmarshal_bandwidth(&value)?);
     Ok(Some(StateFn { f: so_bb_or_t_t }))
 }
@@ -692,10 +970,10 @@ fn unmarshal_bandwidth(value: &str) -> Result<Bandwidth> {
 }
 
 
-fn unmarshal_timing<'a, R: io::BufRead + io::Seek>(
+fn unmarshal_timing<'a, R: io::BufRead>(
     lexer: &mut Lexer<'a, R>,
 ) -> Result<Option<StateFn<'a, R>>> {
-    let (value, _) = read_value(lexer.reader)?;
+    let (value, _) = lexer.read_value()?;
 
     let fields: Vec<&str> = value.split_whitespace().collect();
     if fields.len() < 2 {
@@ -716,10 +994,10 @@ fn unmarshal_timing<'a, R: io::BufRead + io::Seek>(
     Ok(Some(StateFn { f: to_rr_or_so_zkaa_or_m }))
 }
 
-fn unmarshal_repeat_times<'a, R: io::BufRead + io::Seek>(
+fn unmarshal_repeat_times<'a, R: io::BufRead>(
     lexer: &mut Lexer<'a, R>,
 ) -> Result<Option<StateFn<'a, R>>> {
-    let (value, _) = read_value(lexer.reader)?;
+    let (value, _) = lexer.read_value()?;
 
     let fields: Vec<&str> = value.split_whitespace().collect();
     if fields.len() < 3 {
@@ -763,16 +1041,16 @@ fn parse_time_units(value: &str) -> Result<i64> {
         .ok_or_else(|| Error::SdpInvalidValue(value.to_owned()))
 }
 
-fn unmarshal_time_zones<'a, R: io::BufRead + io::Seek>(
+fn unmarshal_time_zones<'a, R: io::BufRead>(
     lexer: &mut Lexer<'a, R>,
 ) -> Result<Option<StateFn<'a, R>>> {
-    let (value, _) = read_value(lexer.reader)?;
+    let (value, _) = lexer.read_value()?;
 
     // These fields are transimitted in pairs
     // z=<adjustment time> <offset> <adjustment time> <offset> ....
     // so we are making sure that there are actually multiple of 2 total.
     let fields: Vec<&str> = value.split_whitespace().collect();
-    if fields.len() % 2 != 0 {
+    if !fields.len().is_multiple_of(2) {
         return Err(Error::SdpInvalidSyntax(format!("`t={}`", value)));
     }
 
@@ -789,18 +1067,18 @@ fn unmarshal_time_zones<'a, R: io::BufRead + io::Seek>(
     Ok(Some(StateFn { f: so_kaa_or_m }))
 }
 
-fn unmarshal_session_encryption_key<'a, R: io::BufRead + io::Seek>(
+fn unmarshal_session_encryption_key<'a, R: io::BufRead>(
     lexer: &mut Lexer<'a, R>,
 ) -> Result<Option<StateFn<'a, R>>> {
-    let (value, _) = read_value(lexer.reader)?;
+    let (value, _) = lexer.read_value()?;
     lexer.sdp.session.encryption_key = Some(value);
     Ok(Some(StateFn { f: so_aa_or_m }))
 }
 
-fn unmarshal_session_attribute<'a, R: io::BufRead + io::Seek>(
+fn unmarshal_session_attribute<'a, R: io::BufRead>(
     lexer: &mut Lexer<'a, R>,
 ) -> Result<Option<StateFn<'a, R>>> {
-    let (value, _) = read_value(lexer.reader)?;
+    let (value, _) = lexer.read_value()?;
 
     let fields: Vec<&str> = value.splitn(2, ':').collect();
     let attribute = if fields.len() == 2 {
@@ -819,10 +1097,10 @@ fn unmarshal_session_attribute<'a, R: io::BufRead + io::Seek>(
     Ok(Some(StateFn { f: so_aa_or_m }))
 }
 
-fn unmarshal_media_description<'a, R: io::BufRead + io::Seek>(
+fn unmarshal_media_description<'a, R: io::BufRead>(
     lexer: &mut Lexer<'a, R>,
 ) -> Result<Option<StateFn<'a, R>>> {
-    let (value, _) = read_value(lexer.reader)?;
+    let (value, _) = lexer.read_value()?;
 
     let fields: Vec<&str> = value.split_whitespace().collect();
     if fields.len() < 4 {
@@ -857,7 +1135,7 @@ fn unmarshal_media_description<'a, R: io::BufRead + io::Seek>(
         let i = index_of(
             proto,
             &[
-                "UDP", "RTP", "AVP", "SAVP", "SAVPF", "TLS", "DTLS", "SCTP", "AVPF",
+                "UDP", "TCP", "RTP", "AVP", "SAVP", "SAVPF", "TLS", "DTLS", "SCTP", "AVPF",
             ],
         );
         if i == -1 {
@@ -892,10 +1170,10 @@ fn unmarshal_media_description<'a, R: io::BufRead + io::Seek>(
     Ok(Some(StateFn { f: moall }))
 }
 
-fn unmarshal_media_title<'a, R: io::BufRead + io::Seek>(
+fn unmarshal_media_title<'a, R: io::BufRead>(
     lexer: &mut Lexer<'a, R>,
 ) -> Result<Option<StateFn<'a, R>>> {
-    let (value, _) = read_value(lexer.reader)?;
+    let (value, _) = lexer.read_value()?;
 
     if let Some(latest_media_desc) = lexer.sdp.media_descriptions.last_mut() {
         latest_media_desc.media_title = Some(value);
@@ -905,10 +1183,10 @@ fn unmarshal_media_title<'a, R: io::BufRead + io::Seek>(
     }
 }
 
-fn unmarshal_media_connection_information<'a, R: io::BufRead + io::Seek>(
+fn unmarshal_media_connection_information<'a, R: io::BufRead>(
     lexer: &mut Lexer<'a, R>,
 ) -> Result<Option<StateFn<'a, R>>> {
-    let (value, _) = read_value(lexer.reader)?;
+    let (value, _) = lexer.read_value()?;
 
     if let Some(latest_media_desc) = lexer.sdp.media_descriptions.last_mut() {
         latest_media_desc.connection_information = unmarshal_connection_information(&value)?;
@@ -918,10 +1196,10 @@ fn unmarshal_media_connection_information<'a, R: io::BufRead + io::Seek>(
     }
 }
 
-fn unmarshal_media_bandwidth<'a, R: io::BufRead + io::Seek>(
+fn unmarshal_media_bandwidth<'a, R: io::BufRead>(
     lexer: &mut Lexer<'a, R>,
 ) -> Result<Option<StateFn<'a, R>>> {
-    let (value, _) = read_value(lexer.reader)?;
+    let (value, _) = lexer.read_value()?;
 
     if let Some(latest_media_desc) = lexer.sdp.media_descriptions.last_mut() {
         let bandwidth = unmarshal_bandwidth(&value)?;
@@ -932,10 +1210,10 @@ fn unmarshal_media_bandwidth<'a, R: io::BufRead + io::Seek>(
     }
 }
 
-fn unmarshal_media_encryption_key<'a, R: io::BufRead + io::Seek>(
+fn unmarshal_media_encryption_key<'a, R: io::BufRead>(
     lexer: &mut Lexer<'a, R>,
 ) -> Result<Option<StateFn<'a, R>>> {
-    let (value, _) = read_value(lexer.reader)?;
+    let (value, _) = lexer.read_value()?;
 
     if let Some(latest_media_desc) = lexer.sdp.media_descriptions.last_mut() {
         latest_media_desc.encryption_key = Some(value);
@@ -945,10 +1223,10 @@ fn unmarshal_media_encryption_key<'a, R: io::BufRead + io::Seek>(
     }
 }
 
-fn unmarshal_media_attribute<'a, R: io::BufRead + io::Seek>(
+fn unmarshal_media_attribute<'a, R: io::BufRead>(
     lexer: &mut Lexer<'a, R>,
 ) -> Result<Option<StateFn<'a, R>>> {
-    let (value, _) = read_value(lexer.reader)?;
+    let (value, _) = lexer.read_value()?;
 
     let fields: Vec<&str> = value.splitn(2, ':').collect();
     let attribute = if fields.len() == 2 {
@@ -970,13 +1248,3 @@ fn unmarshal_media_attribute<'a, R: io::BufRead + io::Seek>(
         Err(Error::SdpEmptyTimeDescription)
     }
 }
-
-
-
-fn key_value_build(key: &str, value: Option<&String>) -> String {
-    if let Some(val) = value {
-        format!("{}{}{}", key, val, END_LINE)
-    } else {
-        "".to_string()
-    }
-}
@@ -1,6 +1,6 @@
 use super::*;
 
-const SDPSTR: &str = "v=0\r\no=- 1336763028228163073 3 IN IP4 127.0.0.1\r\ns=-\r\nt=0 0\r\na=group:BUNDLE 2 3 4\r\na=extmap-allow-mixed\r\na=msid-semantic: WMS\r\nm=audio 9 UDP/TLS/RTP/SAVPF 111 63 103 104 9 0 8 106 105 13 110 112 113 126\r\nc=IN IP4 0.0.0.0\r\na=rtcp:9 IN IP4 0.0.0.0\r\na=ice-ufrag:AOV8\r\na=ice-pwd:JZBw86RpZXvCLCn5E2rya5bI\r\na=ice-options:trickle\r\na=fingerprint:sha-256 96:01:FD:7F:1D:4D:D8:3E:54:9A:1A:E2:0F:E3:9D:60:05:D3:0E:D7:AB:03:98:D3:B8:8D:BE:95:85:19:BC:E3\r\na=setup:actpass\r\na=mid:2\r\na=extmap:1 urn:ietf:params:rtp-hdrext:ssrc-audio-level\r\na=extmap:2 http://www.webrtc.org/experiments/rtp-hdrext/abs-send-time\r\na=extmap:3 http://www.ietf.org/id/draft-holmer-rmcat-transport-wide-cc-extensions-01\r\na=extmap:4 urn:ietf:params:rtp-hdrext:sdes:mid\r\na=sendrecv\r\na=msid:- f279f012-989a-466f-8423-ee1e0cefdd3c\r\na=rtcp-mux\r\na=rtpmap:111 opus/48000/2\r\na=rtcp-fb:111 transport-cc\r\na=fmtp:111 minptime=10;useinbandfec=1\r\na=rtpmap:63 red/48000/2\r\na=fmtp:63 111/111\r\na=rtpmap:103 ISAC/16000\r\na=rtpmap:104 ISAC/32000\r\na=rtpmap:9 G722/8000\r\na=rtpmap:0 PCMU/8000\r\na=rtpmap:8 PCMA/8000\r\na=rtpmap:106 CN/32000\r\na=rtpmap:105 CN/16000\r\na=rtpmap:13 CN/8000\r\na=rtpmap:110 telephone-event/48000\r\na=rtpmap:112 telephone-event/32000\r\na=rtpmap:113 telephone-event/16000\r\na=rtpmap:126 telephone-event/8000\r\na=ssrc:3794136405 cname:BlPa1deUgsMx8yL/\r\na=ssrc:3794136405 msid:- f279f012-989a-466f-8423-ee1e0cefdd3c\r\na=ssrc:3794136405 mslabel:-\r\na=ssrc:3794136405 label:f279f012-989a-466f-8423-ee1e0cefdd3c\r\nm=video 9 UDP/TLS/RTP/SAVPF 96 97 98 99 100 101 102 121 127 120 125 107 108 109 124 119 123 117 35 36 114 115 116 62 118\r\nc=IN IP4 0.0.0.0\r\na=rtcp:9 IN IP4 0.0.0.0\r\na=ice-ufrag:AOV8\r\na=ice-pwd:JZBw86RpZXvCLCn5E2rya5bI\r\na=ice-options:trickle\r\na=fingerprint:sha-256 96:01:FD:7F:1D:4D:D8:3E:54:9A:1A:E2:0F:E3:9D:60:05:D3:0E:D7:AB:03:98:D3:B8:8D:BE:95:85:19:BC:E3\r\na=setup:actpass\r\na=mid:3\r\na=extmap:14 urn:ietf:params:rtp-hdrext:toffset\r\na=extmap:2 http://www.webrtc.org/experiments/rtp-hdrext/abs-send-time\r\na=extmap:13 urn:3gpp:video-orientation\r\na=extmap:3 http://www.ietf.org/id/draft-holmer-rmcat-transport-wide-cc-extensions-01\r\na=extmap:5 http://www.webrtc.org/experiments/rtp-hdrext/playout-delay\r\na=extmap:6 http://www.webrtc.org/experiments/rtp-hdrext/video-content-type\r\na=extmap:7 http://www.webrtc.org/experiments/rtp-hdrext/video-timing\r\na=extmap:8 http://www.webrtc.org/experiments/rtp-hdrext/color-space\r\na=extmap:4 urn:ietf:params:rtp-hdrext:sdes:mid\r\na=extmap:10 urn:ietf:params:rtp-hdrext:sdes:rtp-stream-id\r\na=extmap:11 urn:ietf:params:rtp-hdrext:sdes:repaired-rtp-stream-id\r\na=sendrecv\r\na=msid:- 59288a99-df30-4537-b097-f49576534bd2\r\na=rtcp-mux\r\na=rtcp-rsize\r\na=rtpmap:96 VP8/90000\r\na=rtcp-fb:96 goog-remb\r\na=rtcp-fb:96 transport-cc\r\na=rtcp-fb:96 ccm fir\r\na=rtcp-fb:96 nack\r\na=rtcp-fb:96 nack pli\r\na=rtpmap:97 rtx/90000\r\na=fmtp:97 apt=96\r\na=rtpmap:98 VP9/90000\r\na=rtcp-fb:98 goog-remb\r\na=rtcp-fb:98 transport-cc\r\na=rtcp-fb:98 ccm fir\r\na=rtcp-fb:98 nack\r\na=rtcp-fb:98 nack pli\r\na=fmtp:98 profile-id=0\r\na=rtpmap:99 rtx/90000\r\na=fmtp:99 apt=98\r\na=rtpmap:100 VP9/90000\r\na=rtcp-fb:100 goog-remb\r\na=rtcp-fb:100 transport-cc\r\na=rtcp-fb:100 ccm fir\r\na=rtcp-fb:100 nack\r\na=rtcp-fb:100 nack pli\r\na=fmtp:100 profile-id=2\r\na=rtpmap:101 rtx/90000\r\na=fmtp:101 apt=100\r\na=rtpmap:102 H264/90000\r\na=rtcp-fb:102 goog-remb\r\na=rtcp-fb:102 transport-cc\r\na=rtcp-fb:102 ccm fir\r\na=rtcp-fb:102 nack\r\na=rtcp-fb:102 nack pli\r\na=fmtp:102 level-asymmetry-allowed=1;packetization-mode=1;profile-level-id=42001f\r\na=rtpmap:121 rtx/90000\r\na=fmtp:121 apt=102\r\na=rtpmap:127 H264/90000\r\na=rtcp-fb:127 goog-remb\r\na=rtcp-fb:127 transport-cc\r\na=rtcp-fb:127 ccm fir\r\na=rtcp-fb:127 nack\r\na=rtcp-fb:127 nack pli\r\na=fmtp:127 level-asymmetry-allowed=1;packetization-mode=0;profile-level-id=42001f\r\na=rtpmap:120 rtx/90000\r\na=fmtp:120 apt=127\r\na=rtpmap:125 H264/90000\r\na=rtcp-fb:125 goog-remb\r\na=rtcp-fb:125 transport-cc\r\na=rtcp-fb:125 ccm fir\r\na=rtcp-fb:125 nack\r\na=rtcp-fb:125 nack pli\r\na=fmtp:125 level-asymmetry-allowed=1;packetization-mode=1;profile-level-id=42e01f\r\na=rtpmap:107 rtx/90000\r\na=fmtp:107 apt=125\r\na=rtpmap:108 H264/90000\r\na=rtcp-fb:108 goog-remb\r\na=rtcp-fb:108 transport-cc\r\na=rtcp-fb:108 ccm fir\r\na=rtcp-fb:108 nack\r\na=rtcp-fb:108 nack pli\r\na=fmtp:108 level-asymmetry-allowed=1;packetization-mode=0;profile-level-id=42e01f\r\na=rtpmap:109 rtx/90000\r\na=fmtp:109 apt=108\r\na=rtpmap:124 H264/90000\r\na=rtcp-fb:124 goog-remb\r\na=rtcp-fb:124 transport-cc\r\na=rtcp-fb:124 ccm fir\r\na=rtcp-fb:124 nack\r\na=rtcp-fb:124 nack pli\r\na=fmtp:124 level-asymmetry-allowed=1;packetization-mode=1;profile-level-id=4d001f\r\na=rtpmap:119 rtx/90000\r\na=fmtp:119 apt=124\r\na=rtpmap:123 H264/90000\r\na=rtcp-fb:123 goog-remb\r\na=rtcp-fb:123 transport-cc\r\na=rtcp-fb:123 ccm fir\r\na=rtcp-fb:123 nack\r\na=rtcp-fb:123 nack pli\r\na=fmtp:123 level-asymmetry-allowed=1;packetization-mode=0;profile-level-id=4d001f\r\na=rtpmap:117 rtx/90000\r\na=fmtp:117 apt=123\r\na=rtpmap:35 AV1/90000\r\na=rtcp-fb:35 goog-remb\r\na=rtcp-fb:35 transport-cc\r\na=rtcp-fb:35 ccm fir\r\na=rtcp-fb:35 nack\r\na=rtcp-fb:35 nack pli\r\na=rtpmap:36 rtx/90000\r\na=fmtp:36 apt=35\r\na=rtpmap:114 H264/90000\r\na=rtcp-fb:114 goog-remb\r\na=rtcp-fb:114 transport-cc\r\na=rtcp-fb:114 ccm fir\r\na=rtcp-fb:114 nack\r\na=rtcp-fb:114 nack pli\r\na=fmtp:114 level-asymmetry-allowed=1;packetization-mode=1;profile-level-id=640032\r\na=rtpmap:115 rtx/90000\r\na=fmtp:115 apt=114\r\na=rtpmap:116 red/90000\r\na=rtpmap:62 rtx/90000\r\na=fmtp:62 apt=116\r\na=rtpmap:118 ulpfec/90000\r\na=rid:high send\r\na=rid:middle send\r\na=rid:low send\r\na=simulcast:send high;middle;~low\r\nm=video 9 UDP/TLS/RTP/SAVPF 96 97 98 99 100 101 102 121 127 120 125 107 108 109 124 119 123 117 35 36 114 115 116 62 118\r\nc=IN IP4 0.0.0.0\r\na=rtcp:9 IN IP4 0.0.0.0\r\na=ice-ufrag:AOV8\r\na=ice-pwd:JZBw86RpZXvCLCn5E2rya5bI\r\na=ice-options:trickle\r\na=fingerprint:sha-256 96:01:FD:7F:1D:4D:D8:3E:54:9A:1A:E2:0F:E3:9D:60:05:D3:0E:D7:AB:03:98:D3:B8:8D:BE:95:85:19:BC:E3\r\na=setup:actpass\r\na=mid:4\r\na=extmap:14 urn:ietf:params:rtp-hdrext:toffset\r\na=extmap:2 http://www.webrtc.org/experiments/rtp-hdrext/abs-send-time\r\na=extmap:13 urn:3gpp:video-orientation\r\na=extmap:3 http://www.ietf.org/id/draft-holmer-rmcat-transport-wide-cc-extensions-01\r\na=extmap:5 http://www.webrtc.org/experiments/rtp-hdrext/playout-delay\r\na=extmap:6 http://www.webrtc.org/experiments/rtp-hdrext/video-content-type\r\na=extmap:7 http://www.webrtc.org/experiments/rtp-hdrext/video-timing\r\na=extmap:8 http://www.webrtc.org/experiments/rtp-hdrext/color-space\r\na=extmap:4 urn:ietf:params:rtp-hdrext:sdes:mid\r\na=extmap:10 urn:ietf:params:rtp-hdrext:sdes:rtp-stream-id\r\na=extmap:11 urn:ietf:params:rtp-hdrext:sdes:repaired-rtp-stream-id\r\na=sendrecv\r\na=msid:- 6e91b7db-e2f5-48f2-8bcf-39fddf9c1d3a\r\na=rtcp-mux\r\na=rtcp-rsize\r\na=rtpmap:96 VP8/90000\r\na=rtcp-fb:96 goog-remb\r\na=rtcp-fb:96 transport-cc\r\na=rtcp-fb:96 ccm fir\r\na=rtcp-fb:96 nack\r\na=rtcp-fb:96 nack pli\r\na=rtpmap:97 rtx/90000\r\na=fmtp:97 apt=96\r\na=rtpmap:98 VP9/90000\r\na=rtcp-fb:98 goog-remb\r\na=rtcp-fb:98 transport-cc\r\na=rtcp-fb:98 ccm fir\r\na=rtcp-fb:98 nack\r\na=rtcp-fb:98 nack pli\r\na=fmtp:98 profile-id=0\r\na=rtpmap:99 rtx/90000\r\na=fmtp:99 apt=98\r\na=rtpmap:100 VP9/90000\r\na=rtcp-fb:100 goog-remb\r\na=rtcp-fb:100 transport-cc\r\na=rtcp-fb:100 ccm fir\r\na=rtcp-fb:100 nack\r\na=rtcp-fb:100 nack pli\r\na=fmtp:100 profile-id=2\r\na=rtpmap:101 rtx/90000\r\na=fmtp:101 apt=100\r\na=rtpmap:102 H264/90000\r\na=rtcp-fb:102 goog-remb\r\na=rtcp-fb:102 transport-cc\r\na=rtcp-fb:102 ccm fir\r\na=rtcp-fb:102 nack\r\na=rtcp-fb:102 nack pli\r\na=fmtp:102 level-asymmetry-allowed=1;packetization-mode=1;profile-level-id=42001f\r\na=rtpmap:121 rtx/90000\r\na=fmtp:121 apt=102\r\na=rtpmap:127 H264/90000\r\na=rtcp-fb:127 goog-remb\r\na=rtcp-fb:127 transport-cc\r\na=rtcp-fb:127 ccm fir\r\na=rtcp-fb:127 nack\r\na=rtcp-fb:127 nack pli\r\na=fmtp:127 level-asymmetry-allowed=1;packetization-mode=0;profile-level-id=42001f\r\na=rtpmap:120 rtx/90000\r\na=fmtp:120 apt=127\r\na=rtpmap:125 H264/90000\r\na=rtcp-fb:125 goog-remb\r\na=rtcp-fb:125 transport-cc\r\na=rtcp-fb:125 ccm fir\r\na=rtcp-fb:125 nack\r\na=rtcp-fb:125 nack pli\r\na=fmtp:125 level-asymmetry-allowed=1;packetization-mode=1;profile-level-id=42e01f\r\na=rtpmap:107 rtx/90000\r\na=fmtp:107 apt=125\r\na=rtpmap:108 H264/90000\r\na=rtcp-fb:108 goog-remb\r\na=rtcp-fb:108 transport-cc\r\na=rtcp-fb:108 ccm fir\r\na=rtcp-fb:108 nack\r\na=rtcp-fb:108 nack pli\r\na=fmtp:108 level-asymmetry-allowed=1;packetization-mode=0;profile-level-id=42e01f\r\na=rtpmap:109 rtx/90000\r\na=fmtp:109 apt=108\r\na=rtpmap:124 H264/90000\r\na=rtcp-fb:124 goog-remb\r\na=rtcp-fb:124 transport-cc\r\na=rtcp-fb:124 ccm fir\r\na=rtcp-fb:124 nack\r\na=rtcp-fb:124 nack pli\r\na=fmtp:124 level-asymmetry-allowed=1;packetization-mode=1;profile-level-id=4d001f\r\na=rtpmap:119 rtx/90000\r\na=fmtp:119 apt=124\r\na=rtpmap:123 H264/90000\r\na=rtcp-fb:123 goog-remb\r\na=rtcp-fb:123 transport-cc\r\na=rtcp-fb:123 ccm fir\r\na=rtcp-fb:123 nack\r\na=rtcp-fb:123 nack pli\r\na=fmtp:123 level-asymmetry-allowed=1;packetization-mode=0;profile-level-id=4d001f\r\na=rtpmap:117 rtx/90000\r\na=fmtp:117 apt=123\r\na=rtpmap:35 AV1/90000\r\na=rtcp-fb:35 goog-remb\r\na=rtcp-fb:35 transport-cc\r\na=rtcp-fb:35 ccm fir\r\na=rtcp-fb:35 nack\r\na=rtcp-fb:35 nack pli\r\na=rtpmap:36 rtx/90000\r\na=fmtp:36 apt=35\r\na=rtpmap:114 H264/90000\r\na=rtcp-fb:114 goog-remb\r\na=rtcp-fb:114 transport-cc\r\na=rtcp-fb:114 ccm fir\r\na=rtcp-fb:114 nack\r\na=rtcp-fb:114 nack pli\r\na=fmtp:114 level-asymmetry-allowed=1;packetization-mode=1;profile-level-id=640032\r\na=rtpmap:115 rtx/90000\r\na=fmtp:115 apt=114\r\na=rtpmap:116 red/90000\r\na=rtpmap:62 rtx/90000\r\na=fmtp:62 apt=116\r\na=rtpmap:118 ulpfec/90000\r\na=rid:high send\r\na=rid:middle send\r\na=rid:low send\r\na=simulcast:send high;middle;~low\r\n";
+pub(crate) const SDPSTR: &str = "v=0\r\no=- 1336763028228163073 3 IN IP4 127.0.0.1\r\ns=-\r\nt=0 0\r\na=group:BUNDLE 2 3 4\r\na=extmap-allow-mixed\r\na=msid-semantic: WMS\r\nm=audio 9 UDP/TLS/RTP/SAVPF 111 63 103 104 9 0 8 106 105 13 110 112 113 126\r\nc=IN IP4 0.0.0.0\r\na=rtcp:9 IN IP4 0.0.0.0\r\na=ice-ufrag:AOV8\r\na=ice-pwd:JZBw86RpZXvCLCn5E2rya5bI\r\na=ice-options:trickle\r\na=fingerprint:sha-256 96:01:FD:7F:1D:4D:D8:3E:54:9A:1A:E2:0F:E3:9D:60:05:D3:0E:D7:AB:03:98:D3:B8:8D:BE:95:85:19:BC:E3\r\na=setup:actpass\r\na=mid:2\r\na=extmap:1 urn:ietf:params:rtp-hdrext:ssrc-audio-level\r\na=extmap:2 http://www.webrtc.org/experiments/rtp-hdrext/abs-send-time\r\na=extmap:3 http://www.ietf.org/id/draft-holmer-rmcat-transport-wide-cc-extensions-01\r\na=extmap:4 urn:ietf:params:rtp-hdrext:sdes:mid\r\na=sendrecv\r\na=msid:- f279f012-989a-466f-8423-ee1e0cefdd3c\r\na=rtcp-mux\r\na=rtpmap:111 opus/48000/2\r\na=rtcp-fb:111 transport-cc\r\na=fmtp:111 minptime=10;useinbandfec=1\r\na=rtpmap:63 red/48000/2\r\na=fmtp:63 111/111\r\na=rtpmap:103 ISAC/16000\r\na=rtpmap:104 ISAC/32000\r\na=rtpmap:9 G722/8000\r\na=rtpmap:0 PCMU/8000\r\na=rtpmap:8 PCMA/8000\r\na=rtpmap:106 CN/32000\r\na=rtpmap:105 CN/16000\r\na=rtpmap:13 CN/8000\r\na=rtpmap:110 telephone-event/48000\r\na=rtpmap:112 telephone-event/32000\r\na=rtpmap:113 telephone-event/16000\r\na=rtpmap:126 telephone-event/8000\r\na=ssrc:3794136405 cname:BlPa1deUgsMx8yL/\r\na=ssrc:3794136405 msid:- f279f012-989a-466f-8423-ee1e0cefdd3c\r\na=ssrc:3794136405 mslabel:-\r\na=ssrc:3794136405 label:f279f012-989a-466f-8423-ee1e0cefdd3c\r\nm=video 9 UDP/TLS/RTP/SAVPF 96 97 98 99 100 101 102 121 127 120 125 107 108 109 124 119 123 117 35 36 114 115 116 62 118\r\nc=IN IP4 0.0.0.0\r\na=rtcp:9 IN IP4 0.0.0.0\r\na=ice-ufrag:AOV8\r\na=ice-pwd:JZBw86RpZXvCLCn5E2rya5bI\r\na=ice-options:trickle\r\na=fingerprint:sha-256 96:01:FD:7F:1D:4D:D8:3E:54:9A:1A:E2:0F:E3:9D:60:05:D3:0E:D7:AB:03:98:D3:B8:8D:BE:95:85:19:BC:E3\r\na=setup:actpass\r\na=mid:3\r\na=extmap:14 urn:ietf:params:rtp-hdrext:toffset\r\na=extmap:2 http://www.webrtc.org/experiments/rtp-hdrext/abs-send-time\r\na=extmap:13 urn:3gpp:video-orientation\r\na=extmap:3 http://www.ietf.org/id/draft-holmer-rmcat-transport-wide-cc-extensions-01\r\na=extmap:5 http://www.webrtc.org/experiments/rtp-hdrext/playout-delay\r\na=extmap:6 http://www.webrtc.org/experiments/rtp-hdrext/video-content-type\r\na=extmap:7 http://www.webrtc.org/experiments/rtp-hdrext/video-timing\r\na=extmap:8 http://www.webrtc.org/experiments/rtp-hdrext/color-space\r\na=extmap:4 urn:ietf:params:rtp-hdrext:sdes:mid\r\na=extmap:10 urn:ietf:params:rtp-hdrext:sdes:rtp-stream-id\r\na=extmap:11 urn:ietf:params:rtp-hdrext:sdes:repaired-rtp-stream-id\r\na=sendrecv\r\na=msid:- 59288a99-df30-4537-b097-f49576534bd2\r\na=rtcp-mux\r\na=rtcp-rsize\r\na=rtpmap:96 VP8/90000\r\na=rtcp-fb:96 goog-remb\r\na=rtcp-fb:96 transport-cc\r\na=rtcp-fb:96 ccm fir\r\na=rtcp-fb:96 nack\r\na=rtcp-fb:96 nack pli\r\na=rtpmap:97 rtx/90000\r\na=fmtp:97 apt=96\r\na=rtpmap:98 VP9/90000\r\na=rtcp-fb:98 goog-remb\r\na=rtcp-fb:98 transport-cc\r\na=rtcp-fb:98 ccm fir\r\na=rtcp-fb:98 nack\r\na=rtcp-fb:98 nack pli\r\na=fmtp:98 profile-id=0\r\na=rtpmap:99 rtx/90000\r\na=fmtp:99 apt=98\r\na=rtpmap:100 VP9/90000\r\na=rtcp-fb:100 goog-remb\r\na=rtcp-fb:100 transport-cc\r\na=rtcp-fb:100 ccm fir\r\na=rtcp-fb:100 nack\r\na=rtcp-fb:100 nack pli\r\na=fmtp:100 profile-id=2\r\na=rtpmap:101 rtx/90000\r\na=fmtp:101 apt=100\r\na=rtpmap:102 H264/90000\r\na=rtcp-fb:102 goog-remb\r\na=rtcp-fb:102 transport-cc\r\na=rtcp-fb:102 ccm fir\r\na=rtcp-fb:102 nack\r\na=rtcp-fb:102 nack pli\r\na=fmtp:102 level-asymmetry-allowed=1;packetization-mode=1;profile-level-id=42001f\r\na=rtpmap:121 rtx/90000\r\na=fmtp:121 apt=102\r\na=rtpmap:127 H264/90000\r\na=rtcp-fb:127 goog-remb\r\na=rtcp-fb:127 transport-cc\r\na=rtcp-fb:127 ccm fir\r\na=rtcp-fb:127 nack\r\na=rtcp-fb:127 nack pli\r\na=fmtp:127 level-asymmetry-allowed=1;packetization-mode=0;profile-level-id=42001f\r\na=rtpmap:120 rtx/90000\r\na=fmtp:120 apt=127\r\na=rtpmap:125 H264/90000\r\na=rtcp-fb:125 goog-remb\r\na=rtcp-fb:125 transport-cc\r\na=rtcp-fb:125 ccm fir\r\na=rtcp-fb:125 nack\r\na=rtcp-fb:125 nack pli\r\na=fmtp:125 level-asymmetry-allowed=1;packetization-mode=1;profile-level-id=42e01f\r\na=rtpmap:107 rtx/90000\r\na=fmtp:107 apt=125\r\na=rtpmap:108 H264/90000\r\na=rtcp-fb:108 goog-remb\r\na=rtcp-fb:108 transport-cc\r\na=rtcp-fb:108 ccm fir\r\na=rtcp-fb:108 nack\r\na=rtcp-fb:108 nack pli\r\na=fmtp:108 level-asymmetry-allowed=1;packetization-mode=0;profile-level-id=42e01f\r\na=rtpmap:109 rtx/90000\r\na=fmtp:109 apt=108\r\na=rtpmap:124 H264/90000\r\na=rtcp-fb:124 goog-remb\r\na=rtcp-fb:124 transport-cc\r\na=rtcp-fb:124 ccm fir\r\na=rtcp-fb:124 nack\r\na=rtcp-fb:124 nack pli\r\na=fmtp:124 level-asymmetry-allowed=1;packetization-mode=1;profile-level-id=4d001f\r\na=rtpmap:119 rtx/90000\r\na=fmtp:119 apt=124\r\na=rtpmap:123 H264/90000\r\na=rtcp-fb:123 goog-remb\r\na=rtcp-fb:123 transport-cc\r\na=rtcp-fb:123 ccm fir\r\na=rtcp-fb:123 nack\r\na=rtcp-fb:123 nack pli\r\na=fmtp:123 level-asymmetry-allowed=1;packetization-mode=0;profile-level-id=4d001f\r\na=rtpmap:117 rtx/90000\r\na=fmtp:117 apt=123\r\na=rtpmap:35 AV1/90000\r\na=rtcp-fb:35 goog-remb\r\na=rtcp-fb:35 transport-cc\r\na=rtcp-fb:35 ccm fir\r\na=rtcp-fb:35 nack\r\na=rtcp-fb:35 nack pli\r\na=rtpmap:36 rtx/90000\r\na=fmtp:36 apt=35\r\na=rtpmap:114 H264/90000\r\na=rtcp-fb:114 goog-remb\r\na=rtcp-fb:114 transport-cc\r\na=rtcp-fb:114 ccm fir\r\na=rtcp-fb:114 nack\r\na=rtcp-fb:114 nack pli\r\na=fmtp:114 level-asymmetry-allowed=1;packetization-mode=1;profile-level-id=640032\r\na=rtpmap:115 rtx/90000\r\na=fmtp:115 apt=114\r\na=rtpmap:116 red/90000\r\na=rtpmap:62 rtx/90000\r\na=fmtp:62 apt=116\r\na=rtpmap:118 ulpfec/90000\r\na=rid:high send\r\na=rid:middle send\r\na=rid:low send\r\na=simulcast:send high;middle;~low\r\nm=video 9 UDP/TLS/RTP/SAVPF 96 97 98 99 100 101 102 121 127 120 125 107 108 109 124 119 123 117 35 36 114 115 116 62 118\r\nc=IN IP4 0.0.0.0\r\na=rtcp:9 IN IP4 0.0.0.0\r\na=ice-ufrag:AOV8\r\na=ice-pwd:JZBw86RpZXvCLCn5E2rya5bI\r\na=ice-options:trickle\r\na=fingerprint:sha-256 96:01:FD:7F:1D:4D:D8:3E:54:9A:1A:E2:0F:E3:9D:60:05:D3:0E:D7:AB:03:98:D3:B8:8D:BE:95:85:19:BC:E3\r\na=setup:actpass\r\na=mid:4\r\na=extmap:14 urn:ietf:params:rtp-hdrext:toffset\r\na=extmap:2 http://www.webrtc.org/experiments/rtp-hdrext/abs-send-time\r\na=extmap:13 urn:3gpp:video-orientation\r\na=extmap:3 http://www.ietf.org/id/draft-holmer-rmcat-transport-wide-cc-extensions-01\r\na=extmap:5 http://www.webrtc.org/experiments/rtp-hdrext/playout-delay\r\na=extmap:6 http://www.webrtc.org/experiments/rtp-hdrext/video-content-type\r\na=extmap:7 http://www.webrtc.org/experiments/rtp-hdrext/video-timing\r\na=extmap:8 http://www.webrtc.org/experiments/rtp-hdrext/color-space\r\na=extmap:4 urn:ietf:params:rtp-hdrext:sdes:mid\r\na=extmap:10 urn:ietf:params:rtp-hdrext:sdes:rtp-stream-id\r\na=extmap:11 urn:ietf:params:rtp-hdrext:sdes:repaired-rtp-stream-id\r\na=sendrecv\r\na=msid:- 6e91b7db-e2f5-48f2-8bcf-39fddf9c1d3a\r\na=rtcp-mux\r\na=rtcp-rsize\r\na=rtpmap:96 VP8/90000\r\na=rtcp-fb:96 goog-remb\r\na=rtcp-fb:96 transport-cc\r\na=rtcp-fb:96 ccm fir\r\na=rtcp-fb:96 nack\r\na=rtcp-fb:96 nack pli\r\na=rtpmap:97 rtx/90000\r\na=fmtp:97 apt=96\r\na=rtpmap:98 VP9/90000\r\na=rtcp-fb:98 goog-remb\r\na=rtcp-fb:98 transport-cc\r\na=rtcp-fb:98 ccm fir\r\na=rtcp-fb:98 nack\r\na=rtcp-fb:98 nack pli\r\na=fmtp:98 profile-id=0\r\na=rtpmap:99 rtx/90000\r\na=fmtp:99 apt=98\r\na=rtpmap:100 VP9/90000\r\na=rtcp-fb:100 goog-remb\r\na=rtcp-fb:100 transport-cc\r\na=rtcp-fb:100 ccm fir\r\na=rtcp-fb:100 nack\r\na=rtcp-fb:100 nack pli\r\na=fmtp:100 profile-id=2\r\na=rtpmap:101 rtx/90000\r\na=fmtp:101 apt=100\r\na=rtpmap:102 H264/90000\r\na=rtcp-fb:102 goog-remb\r\na=rtcp-fb:102 transport-cc\r\na=rtcp-fb:102 ccm fir\r\na=rtcp-fb:102 nack\r\na=rtcp-fb:102 nack pli\r\na=fmtp:102 level-asymmetry-allowed=1;packetization-mode=1;profile-level-id=42001f\r\na=rtpmap:121 rtx/90000\r\na=fmtp:121 apt=102\r\na=rtpmap:127 H264/90000\r\na=rtcp-fb:127 goog-remb\r\na=rtcp-fb:127 transport-cc\r\na=rtcp-fb:127 ccm fir\r\na=rtcp-fb:127 nack\r\na=rtcp-fb:127 nack pli\r\na=fmtp:127 level-asymmetry-allowed=1;packetization-mode=0;profile-level-id=42001f\r\na=rtpmap:120 rtx/90000\r\na=fmtp:120 apt=127\r\na=rtpmap:125 H264/90000\r\na=rtcp-fb:125 goog-remb\r\na=rtcp-fb:125 transport-cc\r\na=rtcp-fb:125 ccm fir\r\na=rtcp-fb:125 nack\r\na=rtcp-fb:125 nack pli\r\na=fmtp:125 level-asymmetry-allowed=1;packetization-mode=1;profile-level-id=42e01f\r\na=rtpmap:107 rtx/90000\r\na=fmtp:107 apt=125\r\na=rtpmap:108 H264/90000\r\na=rtcp-fb:108 goog-remb\r\na=rtcp-fb:108 transport-cc\r\na=rtcp-fb:108 ccm fir\r\na=rtcp-fb:108 nack\r\na=rtcp-fb:108 nack pli\r\na=fmtp:108 level-asymmetry-allowed=1;packetization-mode=0;profile-level-id=42e01f\r\na=rtpmap:109 rtx/90000\r\na=fmtp:109 apt=108\r\na=rtpmap:124 H264/90000\r\na=rtcp-fb:124 goog-remb\r\na=rtcp-fb:124 transport-cc\r\na=rtcp-fb:124 ccm fir\r\na=rtcp-fb:124 nack\r\na=rtcp-fb:124 nack pli\r\na=fmtp:124 level-asymmetry-allowed=1;packetization-mode=1;profile-level-id=4d001f\r\na=rtpmap:119 rtx/90000\r\na=fmtp:119 apt=124\r\na=rtpmap:123 H264/90000\r\na=rtcp-fb:123 goog-remb\r\na=rtcp-fb:123 transport-cc\r\na=rtcp-fb:123 ccm fir\r\na=rtcp-fb:123 nack\r\na=rtcp-fb:123 nack pli\r\na=fmtp:123 level-asymmetry-allowed=1;packetization-mode=0;profile-level-id=4d001f\r\na=rtpmap:117 rtx/90000\r\na=fmtp:117 apt=123\r\na=rtpmap:35 AV1/90000\r\na=rtcp-fb:35 goog-remb\r\na=rtcp-fb:35 transport-cc\r\na=rtcp-fb:35 ccm fir\r\na=rtcp-fb:35 nack\r\na=rtcp-fb:35 nack pli\r\na=rtpmap:36 rtx/90000\r\na=fmtp:36 apt=35\r\na=rtpmap:114 H264/90000\r\na=rtcp-fb:114 goog-remb\r\na=rtcp-fb:114 transport-cc\r\na=rtcp-fb:114 ccm fir\r\na=rtcp-fb:114 nack\r\na=rtcp-fb:114 nack pli\r\na=fmtp:114 level-asymmetry-allowed=1;packetization-mode=1;profile-level-id=640032\r\na=rtpmap:115 rtx/90000\r\na=fmtp:115 apt=114\r\na=rtpmap:116 red/90000\r\na=rtpmap:62 rtx/90000\r\na=fmtp:62 apt=116\r\na=rtpmap:118 ulpfec/90000\r\na=rid:high send\r\na=rid:middle send\r\na=rid:low send\r\na=simulcast:send high;middle;~low\r\n";
 
 #[test]
 fn test_unmarshal() {
@@ -15,3 +15,393 @@ fn test_unmarshal() {
 
     assert_eq!(SDPSTR, &sdpstr)
 }
+
+#[test]
+fn test_unmarshal_codecs() -> Result<()> {
+    let sdp = SDP::unmarshal(SDPSTR.as_bytes())?;
+
+    let audio = &sdp.media_descriptions[0];
+    let codecs = audio.codecs();
+    assert_eq!(codecs.len(), audio.media_name.formats.len());
+    let opus = audio.codec_for_payload_type(111)?;
+    assert_eq!(opus.to_string(), "111 opus/48000/2");
+    assert_eq!(opus.fmtp.to_string(), "minptime=10;useinbandfec=1");
+    assert_eq!(opus.rtcp_feedback, vec!["transport-cc"]);
+
+    let video = &sdp.media_descriptions[1];
+    let vp9 = video.codec_for_payload_type(100)?;
+    assert_eq!(vp9.fmtp_param("profile-id"), Some("2"));
+    assert_eq!(
+        vp9.rtcp_feedback,
+        vec!["goog-remb", "transport-cc", "ccm fir", "nack", "nack pli"]
+    );
+    let rtx = video.codec_for_payload_type(101)?;
+    assert!(rtx.is_rtx());
+    assert_eq!(rtx.fmtp_param("apt"), Some("100"));
+
+    Ok(())
+}
+
+pub(crate) const CANONICAL_MARSHAL_SDP: &str = "v=0\r\n\
+o=jdoe 2890844526 2890842807 IN IP4 10.47.16.5\r\n\
+s=SDP Seminar\r\n\
+i=A Seminar on the session description protocol\r\n\
+u=http://www.example.com/seminars/sdp.pdf\r\n\
+e=j.doe@example.com (Jane Doe)\r\n\
+p=+1 617 555-6011\r\n\
+c=IN IP4 224.2.17.12\r\n\
+b=X-YZ:128\r\n\
+b=AS:12345\r\n\
+t=2873397496 2873404696\r\n\
+r=604800 3600 0 90000\r\n\
+z=2882844526 -3600 2898848070 0\r\n\
+k=prompt\r\n\
+a=recvonly\r\n\
+m=audio 49170 RTP/AVP 0\r\n\
+i=Vivamus a posuere nisl\r\n\
+c=IN IP4 203.0.113.1\r\n\
+b=X-YZ:128\r\n\
+k=prompt\r\n\
+a=sendrecv\r\n\
+m=video 51372 RTP/AVP 99\r\n\
+a=rtpmap:99 h263-1998/90000\r\n";
+
+#[test]
+fn test_serialize() -> Result<()> {
+    for input in [SDPSTR, CANONICAL_MARSHAL_SDP] {
+        let sdp = SDP::unmarshal(input.as_bytes())?;
+
+        let serialized = sdp.serialize();
+        assert_eq!(serialized, SDP::marshal(&sdp).into_bytes());
+        assert_eq!(serialized, input.as_bytes());
+        assert_eq!(sdp.marshal_size(), input.len());
+        // the size pass allocates the exact buffer up front
+        assert_eq!(serialized.capacity(), input.len());
+
+        let mut writer = io::Cursor::new(vec![]);
+        sdp.write_to(&mut writer)?;
+        assert_eq!(writer.into_inner(), serialized);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_unmarshal_syntax_error_location() {
+    let tests = [
+        (
+            "v=1\r\n",
+            1,
+            "v=1",
+            2,
+            Error::SdpInvalidSyntax("1".to_owned()),
+        ),
+        (
+            "v=0\r\no=- 1 x IN IP4 127.0.0.1\r\n",
+            2,
+            "o=- 1 x IN IP4 127.0.0.1",
+            2,
+            Error::ParseInt("x".parse::<u64>().unwrap_err()),
+        ),
+        (
+            "v=0\r\no=- 1 1 IN IP5 127.0.0.1\r\n",
+            2,
+            "o=- 1 1 IN IP5 127.0.0.1",
+            11,
+            Error::SdpInvalidValue("IP5".to_owned()),
+        ),
+        (
+            "v=0\no=- 1 1 IN IP4 127.0.0.1\ns=-\nt=0 0\nm=audio 9 UDP/TLS/RTP/SAVPF 0\nc=IN IP4 0.0.0.0\nq=foo\n",
+            7,
+            "q=foo",
+            0,
+            Error::SdpInvalidSyntax("q=".to_owned()),
+        ),
+        (
+            "v=0\r\no=- 1 1 IN IP4 127.0.0.1\r\ns=-\r\n\r\nt=0 0\r\nm=audio 9 UDP/TLS/RTP/SAVPF 0\r\nb=YZ:128\r\n",
+            7,
+            "b=YZ:128",
+            2,
+            Error::SdpInvalidValue("YZ".to_owned()),
+        ),
+        (
+            "v=0\r\no=- 1 1 IN IP4 127.0.0.1\r\ns=-\r\nt=0 0\r\nm=audio 9 UDP/TLS/RTP/SAVPF 0\r\nab=c\r\n",
+            6,
+            "ab=",
+            0,
+            Error::SdpInvalidSyntax("ab=".to_owned()),
+        ),
+        (
+            "v=0\r\no=- 1 1 IN IP4 127.0.0.1\r\ns=-\r\nt=0 0\r\nm=audio 9 UDP/TLS/RTP/SAVPX 0\r\n",
+            5,
+            "m=audio 9 UDP/TLS/RTP/SAVPX 0",
+            10,
+            Error::SdpInvalidValue("UDP/TLS/RTP/SAVPX".to_owned()),
+        ),
+    ];
+
+    for (i, (input, line, s, p, source)) in tests.into_iter().enumerate() {
+        let expected = Error::SyntaxError {
+            line,
+            s: s.to_owned(),
+            p,
+            source: Box::new(source),
+        };
+        assert_eq!(
+            SDP::unmarshal(input.as_bytes()).err(),
+            Some(expected),
+            "{}: {:?}",
+            i,
+            input
+        );
+    }
+
+    let err = SDP::unmarshal(b"v=0\r\no=- 1 1 IN IP5 127.0.0.1\r\n").err().unwrap();
+    assert_eq!(
+        err.to_string(),
+        "line 2: o=- 1 1 IN  --> I <-- P5 127.0.0.1: SdpInvalidValue: IP5"
+    );
+}
+
+#[test]
+fn test_unmarshal_lenient() -> Result<()> {
+    let literal_sdp = "v=0\no=- 1 1 IN IP4 127.0.0.1\ns=-\nt=0 0\ny=0123456789\nm=audio 49170 RTP/AVP 0\na=rtpmap:0 PCMU/8000\nb=AS:64\n";
+
+    let strict = SDP::unmarshal_with_options(literal_sdp.as_bytes(), &UnmarshalOptions::default());
+    assert!(strict.is_err());
+    assert!(SDP::unmarshal(literal_sdp.as_bytes()).is_err());
+
+    let (sdp, warnings) =
+        SDP::unmarshal_with_options(literal_sdp.as_bytes(), &UnmarshalOptions::lenient())?;
+    assert_eq!(sdp.line_ending, LineEnding::Lf);
+    assert_eq!(sdp.media_descriptions.len(), 1);
+    assert_eq!(sdp.media_descriptions[0].bandwidth.len(), 1);
+    assert_eq!(
+        warnings.iter().map(|w| w.to_string()).collect::<Vec<String>>(),
+        vec![
+            "line 5: y=0123456789: unknown line type ignored",
+            "line 8: b=AS:64: `b=` line out of order",
+        ]
+    );
+
+    // re-marshal keeps the LF line ending and restores the RFC order
+    let expected = "v=0\no=- 1 1 IN IP4 127.0.0.1\ns=-\nt=0 0\nm=audio 49170 RTP/AVP 0\nb=AS:64\na=rtpmap:0 PCMU/8000\n";
+    assert_eq!(SDP::marshal(&sdp), expected);
+    assert_eq!(String::from_utf8(sdp.serialize()).unwrap(), expected);
+
+    let options = UnmarshalOptions {
+        allow_unknown_types: false,
+        ..UnmarshalOptions::lenient()
+    };
+    assert!(SDP::unmarshal_with_options(literal_sdp.as_bytes(), &options).is_err());
+
+    // "b=" precedes "k=" within a media description
+    let literal_sdp = "v=0\r\no=- 1 1 IN IP4 127.0.0.1\r\ns=-\r\nt=0 0\r\nm=audio 49170 RTP/AVP 0\r\nb=AS:64\r\nk=prompt\r\na=rtpmap:0 PCMU/8000\r\n";
+    let strict = SDP::unmarshal(literal_sdp.as_bytes())?;
+    let (sdp, warnings) =
+        SDP::unmarshal_with_options(literal_sdp.as_bytes(), &UnmarshalOptions::lenient())?;
+    assert!(warnings.is_empty(), "{:?}", warnings);
+    assert_eq!(sdp, strict);
+    assert_eq!(SDP::marshal(&sdp), literal_sdp);
+
+    let (_, warnings) = SDP::unmarshal_with_options(
+        b"v=0\r\ns=-\r\nm=audio 9 RTP/AVP 0\r\nt=0 0\r\n",
+        &UnmarshalOptions::lenient(),
+    )?;
+    assert_eq!(
+        warnings.iter().map(|w| w.to_string()).collect::<Vec<String>>(),
+        vec![
+            "line 4: t=0 0: session-level line inside a media description ignored",
+            "missing required `o=` line",
+            "missing required `t=` line",
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_unmarshal_keeps_line_ending() -> Result<()> {
+    let crlf = SDP::unmarshal(CANONICAL_MARSHAL_SDP.as_bytes())?;
+    assert_eq!(crlf.line_ending, LineEnding::CrLf);
+
+    let literal_sdp = CANONICAL_MARSHAL_SDP.replace("\r\n", "\n");
+    let lf = SDP::unmarshal(literal_sdp.as_bytes())?;
+    assert_eq!(lf.line_ending, LineEnding::Lf);
+    assert_eq!(SDP::marshal(&lf), literal_sdp);
+
+    Ok(())
+}
+
+#[test]
+fn test_unmarshal_connection_information() -> Result<()> {
+    let passingtests = [
+        ("IN IP4 203.0.113.1", None, None),
+        ("IN IP4 224.2.1.1/127", Some(127), None),
+        ("IN IP4 224.2.1.1/127/3", Some(127), Some(3)),
+        ("IN IP4 239.255.0.1/0", Some(0), None),
+        ("IN IP6 FF15::101", None, None),
+        ("IN IP6 FF15::101/3", None, Some(3)),
+        ("IN IP6 2001:db8::1", None, None),
+        ("IN IP4 host.example.com", None, None),
+    ];
+
+    for (i, u) in passingtests.iter().enumerate() {
+        let connection_information = unmarshal_connection_information(u.0)?.unwrap();
+        let address = connection_information.address.as_ref().unwrap();
+        assert_eq!(address.ttl, u.1, "{}: {}", i, u.0);
+        assert_eq!(address.range, u.2, "{}: {}", i, u.0);
+        assert_eq!(connection_information.to_string(), u.0, "{}: {}", i, u.0);
+    }
+
+    let multicast = unmarshal_connection_information("IN IP4 224.2.1.1/127/3")?.unwrap();
+    let address = multicast.address.unwrap();
+    assert_eq!(address.ip_addr(), Some("224.2.1.1".parse().unwrap()));
+    assert!(address.is_multicast());
+
+    let failingtests = [
+        "IN IP4 203.0.113.1/127",
+        "IN IP4 224.2.1.1/256",
+        "IN IP4 224.2.1.1/127/0",
+        "IN IP4 224.2.1.1/127/3/1",
+        "IN IP4 224.2.1.1/x",
+        "IN IP6 FF15::101/127/3",
+        "IN IP6 2001:db8::1/3",
+        "IN IP4 FF15::101",
+        "IN IP6 224.2.1.1",
+        "IN IP4 host.example.com/127",
+    ];
+    for u in failingtests {
+        assert!(unmarshal_connection_information(u).is_err(), "{}", u);
+    }
+
+    // TTL and range survive a round trip of a full description
+    let literal_sdp = CANONICAL_MARSHAL_SDP.replace(
+        "c=IN IP4 224.2.17.12\r\n",
+        "c=IN IP4 224.2.17.12/127/2\r\n",
+    );
+    assert_ne!(literal_sdp, CANONICAL_MARSHAL_SDP);
+    let sdp = SDP::unmarshal(literal_sdp.as_bytes())?;
+    assert_eq!(SDP::marshal(&sdp), literal_sdp);
+
+    Ok(())
+}
+
+#[test]
+fn test_clone_and_eq() -> Result<()> {
+    let sdp = SDP::unmarshal(CANONICAL_MARSHAL_SDP.as_bytes())?;
+    let mut copy = sdp.clone();
+    assert_eq!(sdp, copy);
+
+    copy.media_descriptions[0].attributes[0].value = Some("changed".to_owned());
+    assert_ne!(sdp, copy);
+
+    Ok(())
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_round_trip() -> Result<()> {
+    let sdp = SDP::unmarshal(CANONICAL_MARSHAL_SDP.as_bytes())?;
+
+    let json = serde_json::to_string(&sdp).unwrap();
+    let parsed: SDP = serde_json::from_str(&json).unwrap();
+    assert_eq!(sdp, parsed);
+    assert_eq!(SDP::marshal(&parsed), CANONICAL_MARSHAL_SDP);
+
+    Ok(())
+}
+
+#[test]
+fn test_unmarshal_from_reader() -> Result<()> {
+    let expected = SDP::unmarshal(CANONICAL_MARSHAL_SDP.as_bytes())?;
+
+    // &[u8] is BufRead but not Seek
+    let mut reader = CANONICAL_MARSHAL_SDP.as_bytes();
+    assert_eq!(SDP::unmarshal_from(&mut reader)?, expected);
+
+    // a tiny buffer splits line endings and types across refills
+    for capacity in 1..8 {
+        let mut reader = io::BufReader::with_capacity(capacity, CANONICAL_MARSHAL_SDP.as_bytes());
+        assert_eq!(SDP::unmarshal_from(&mut reader)?, expected, "{}", capacity);
+    }
+
+    let parsed: SDP = CANONICAL_MARSHAL_SDP.parse()?;
+    assert_eq!(parsed, expected);
+
+    let mut reader = io::BufReader::with_capacity(4, "v=0\r\n\r\no=- 1 1 IN IP5 127.0.0.1\r\n".as_bytes());
+    match SDP::unmarshal_from(&mut reader) {
+        Err(Error::SyntaxError { line, .. }) => assert_eq!(line, 3),
+        _ => panic!("expected a syntax error"),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_lines() {
+    let lines: Vec<SdpLine<'_>> = SDP::lines("v=0\r\no=- 1 1 IN IP4 127.0.0.1\n\ns=-\r\na=sendrecv")
+        .collect::<Result<_>>()
+        .unwrap();
+    assert_eq!(
+        lines,
+        vec![
+            SdpLine { line: 1, typ: b'v', value: "0" },
+            SdpLine { line: 2, typ: b'o', value: "- 1 1 IN IP4 127.0.0.1" },
+            SdpLine { line: 4, typ: b's', value: "-" },
+            SdpLine { line: 5, typ: b'a', value: "sendrecv" },
+        ]
+    );
+
+    let err = SDP::lines("v=0\r\nbad line\r\n").nth(1).unwrap();
+    assert_eq!(
+        err.err().unwrap().to_string(),
+        "line 2:  --> b <-- ad line: SdpInvalidSyntax: bad line"
+    );
+}
+
+#[test]
+fn test_new_jsep_origin() {
+    let origin = Origin::new_jsep();
+    assert!(origin.session_id < 1 << 62);
+    assert_eq!(origin.session_version, 0);
+    assert_eq!(
+        origin.to_string(),
+        format!("- {} 0 IN IP4 127.0.0.1", origin.session_id)
+    );
+
+    let ids: Vec<u64> = (0..8).map(|_| Origin::new_session_id()).collect();
+    assert!(ids.iter().all(|id| *id < 1 << 62));
+    assert!(ids.iter().any(|id| *id != ids[0]));
+}
+
+#[test]
+fn test_update_origin() -> Result<()> {
+    let previous = SDP::unmarshal(SDPSTR.as_bytes())?;
+
+    // a re-offer with identical content keeps the version, whatever origin
+    // the new description was generated with
+    let mut offer = previous.clone();
+    offer.session.origin = Origin::new_jsep();
+    offer.line_ending = LineEnding::Lf;
+    assert!(!offer.update_origin(&previous));
+    assert_eq!(offer.session.origin, previous.session.origin);
+
+    // any other change increments the version by one
+    offer.media_descriptions[0].attributes.retain(|a| a.key != "sendrecv");
+    offer.media_descriptions[0]
+        .attributes
+        .push(Attribute::new("recvonly".to_owned(), None));
+    assert!(offer.update_origin(&previous));
+    assert_eq!(offer.session.origin.session_id, previous.session.origin.session_id);
+    assert_eq!(offer.session.origin.session_version, 4);
+    assert_eq!(offer.session.origin.username, "-");
+    assert_eq!(offer.session.origin.unicast_address, "127.0.0.1");
+
+    let next = offer.clone();
+    let mut again = offer.clone();
+    assert!(!again.update_origin(&next));
+    assert_eq!(again.session.origin.session_version, 4);
+
+    Ok(())
+}
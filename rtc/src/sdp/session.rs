@@ -2,7 +2,8 @@ use std::fmt;
 use url::Url;
 use crate::sdp::common::*;
 
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Session {
     /// `v=0`
     ///
@@ -69,9 +70,22 @@ pub struct Session {
     pub attributes: Vec<Attribute>,
 }
 
+impl Session {
+    /// attribute returns the value of an attribute and if it exists
+    pub fn attribute(&self, key: &str) -> Option<Option<&str>> {
+        for a in &self.attributes {
+            if a.key == key {
+                return Some(a.value.as_ref().map(|s| s.as_ref()));
+            }
+        }
+        None
+    }
+}
+
 /// TimeZone defines the structured object for "z=" line which describes
 /// repeated sessions scheduling.
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TimeZone {
     pub adjustment_time: u64,
     pub offset: i64,
@@ -85,7 +99,8 @@ impl fmt::Display for TimeZone {
 
 /// Origin defines the structure for the "o=" field which provides the
 /// originator of the session plus a session identifier and version number.
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Origin {
     pub username: String,
     pub session_id: u64,
@@ -110,6 +125,11 @@ impl fmt::Display for Origin {
     }
 }
 
+/// session ids are kept below 2^62, JSEP requires them to fit a signed
+/// 64-bit integer and to be less than 2^63-1
+/// <https://tools.ietf.org/html/rfc8829#section-5.2.1>
+const SESSION_ID_MASK: u64 = (1 << 62) - 1;
+
 impl Origin {
     pub fn new() -> Self {
         Origin {
@@ -121,4 +141,24 @@ impl Origin {
             unicast_address: "".to_owned(),
         }
     }
+
+    /// new_session_id generates a random 62-bit session id
+    pub fn new_session_id() -> u64 {
+        rand::random::<u64>() & SESSION_ID_MASK
+    }
+
+    /// new_jsep creates the origin of a new local session, a fresh random
+    /// session id with version 0 and the placeholder username and address
+    /// recommended by JSEP: "- <session id> 0 IN IP4 127.0.0.1"
+    /// <https://tools.ietf.org/html/rfc8829#section-5.2.1>
+    pub fn new_jsep() -> Self {
+        Origin {
+            username: "-".to_owned(),
+            session_id: Origin::new_session_id(),
+            session_version: 0,
+            network_type: "IN".to_owned(),
+            address_type: "IP4".to_owned(),
+            unicast_address: "127.0.0.1".to_owned(),
+        }
+    }
 }
@@ -0,0 +1,401 @@
+#[cfg(test)]
+mod simulcast_test;
+
+use super::common::Attribute;
+use super::error::{Error, Result};
+
+use std::fmt;
+use std::str::FromStr;
+
+pub const ATTR_KEY_RID: &str = "rid";
+pub const ATTR_KEY_SIMULCAST: &str = "simulcast";
+
+const RID_PARAM_PT: &str = "pt";
+const RID_PARAM_MAX_WIDTH: &str = "max-width";
+const RID_PARAM_MAX_HEIGHT: &str = "max-height";
+const RID_PARAM_MAX_FPS: &str = "max-fps";
+const RID_PARAM_MAX_FS: &str = "max-fs";
+const RID_PARAM_MAX_BR: &str = "max-br";
+const RID_PARAM_MAX_PPS: &str = "max-pps";
+const RID_PARAM_DEPEND: &str = "depend";
+
+/// RidDirection is the direction of an RTP stream identifier
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RidDirection {
+    Send,
+    Recv,
+}
+
+const RID_DIRECTION_SEND_STR: &str = "send";
+const RID_DIRECTION_RECV_STR: &str = "recv";
+
+impl fmt::Display for RidDirection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            RidDirection::Send => RID_DIRECTION_SEND_STR,
+            RidDirection::Recv => RID_DIRECTION_RECV_STR,
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl FromStr for RidDirection {
+    type Err = Error;
+
+    fn from_str(raw: &str) -> Result<Self> {
+        match raw {
+            RID_DIRECTION_SEND_STR => Ok(RidDirection::Send),
+            RID_DIRECTION_RECV_STR => Ok(RidDirection::Recv),
+            _ => Err(Error::ParseRid(format!("unknown direction {}", raw))),
+        }
+    }
+}
+
+/// Rid is the structured form of an "a=rid" attribute
+///
+/// `rid:<rid-id> <direction> [pt=<fmt>,...;][<param>[=<value>];...]`
+///
+/// <https://tools.ietf.org/html/rfc8851#section-10>
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rid {
+    pub id: String,
+    pub direction: RidDirection,
+    /// payload types the stream is restricted to, empty for all
+    pub payload_types: Vec<u8>,
+    /// restrictions such as "max-width" in received order, values are kept
+    /// as text and interpreted by the typed accessors
+    pub restrictions: Vec<(String, Option<String>)>,
+}
+
+/// Renders the value of the "a=rid" attribute.
+impl fmt::Display for Rid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.id, self.direction)?;
+
+        let mut separator = ' ';
+        if !self.payload_types.is_empty() {
+            write!(f, " {}=", RID_PARAM_PT)?;
+            for (i, pt) in self.payload_types.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ",")?;
+                }
+                write!(f, "{}", pt)?;
+            }
+            separator = ';';
+        }
+        for (name, value) in &self.restrictions {
+            write!(f, "{}{}", separator, name)?;
+            if let Some(value) = value {
+                write!(f, "={}", value)?;
+            }
+            separator = ';';
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Rid {
+    type Err = Error;
+
+    fn from_str(raw: &str) -> Result<Self> {
+        Rid::unmarshal(raw)
+    }
+}
+
+impl Rid {
+    pub fn new(id: &str, direction: RidDirection) -> Self {
+        Rid {
+            id: id.to_owned(),
+            direction,
+            payload_types: vec![],
+            restrictions: vec![],
+        }
+    }
+
+    /// unmarshal parses a rid, with or without the "a=rid:" or "rid:" prefix
+    pub fn unmarshal(raw: &str) -> Result<Self> {
+        let value = raw.trim();
+        let value = value.strip_prefix("a=").unwrap_or(value);
+        let value = value.strip_prefix("rid:").unwrap_or(value);
+
+        let mut fields = value.split_whitespace();
+        let (id, direction) = match (fields.next(), fields.next()) {
+            (Some(id), Some(direction)) => (id, direction),
+            _ => return Err(Error::ParseRid(raw.to_owned())),
+        };
+        if !is_rid_id(id) {
+            return Err(Error::ParseRid(format!("invalid rid-id {}", id)));
+        }
+
+        let mut rid = Rid::new(id, direction.parse()?);
+
+        let params = fields.next();
+        if fields.next().is_some() {
+            return Err(Error::ParseRid(raw.to_owned()));
+        }
+        for param in params.into_iter().flat_map(|p| p.split(';')) {
+            let (name, value) = match param.split_once('=') {
+                Some((name, value)) => (name, Some(value)),
+                None => (param, None),
+            };
+            if name.is_empty() {
+                return Err(Error::ParseRid(raw.to_owned()));
+            }
+
+            if name == RID_PARAM_PT {
+                if !rid.payload_types.is_empty() || !rid.restrictions.is_empty() {
+                    return Err(Error::ParseRid(format!("misplaced {}", param)));
+                }
+                for pt in value.unwrap_or_default().split(',') {
+                    rid.payload_types.push(pt.parse::<u8>()?);
+                }
+                continue;
+            }
+
+            rid.restrictions
+                .push((name.to_owned(), value.map(|v| v.to_owned())));
+        }
+
+        if rid.depend().iter().any(|d| !is_rid_id(d)) {
+            return Err(Error::ParseRid(format!("invalid {}", RID_PARAM_DEPEND)));
+        }
+
+        Ok(rid)
+    }
+
+    /// marshal renders the rid as "rid:..." attribute line value
+    pub fn marshal(&self) -> String {
+        format!("{}:{}", ATTR_KEY_RID, self)
+    }
+
+    /// converts this object to an Attribute
+    pub fn convert(&self) -> Attribute {
+        Attribute {
+            key: ATTR_KEY_RID.to_owned(),
+            value: Some(self.to_string()),
+        }
+    }
+
+    /// restriction returns the value of a restriction, Some("") if it has
+    /// no value
+    pub fn restriction(&self, name: &str) -> Option<&str> {
+        self.restrictions
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_deref().unwrap_or_default())
+    }
+
+    /// with_restriction adds or replaces a restriction
+    pub fn with_restriction(mut self, name: &str, value: &str) -> Self {
+        let value = Some(value.to_owned());
+        match self.restrictions.iter_mut().find(|(n, _)| n == name) {
+            Some(restriction) => restriction.1 = value,
+            None => self.restrictions.push((name.to_owned(), value)),
+        }
+        self
+    }
+
+    pub fn max_width(&self) -> Option<u32> {
+        self.restriction(RID_PARAM_MAX_WIDTH)?.parse().ok()
+    }
+
+    pub fn max_height(&self) -> Option<u32> {
+        self.restriction(RID_PARAM_MAX_HEIGHT)?.parse().ok()
+    }
+
+    pub fn max_fps(&self) -> Option<f64> {
+        self.restriction(RID_PARAM_MAX_FPS)?.parse().ok()
+    }
+
+    pub fn max_fs(&self) -> Option<u32> {
+        self.restriction(RID_PARAM_MAX_FS)?.parse().ok()
+    }
+
+    pub fn max_br(&self) -> Option<u32> {
+        self.restriction(RID_PARAM_MAX_BR)?.parse().ok()
+    }
+
+    pub fn max_pps(&self) -> Option<u32> {
+        self.restriction(RID_PARAM_MAX_PPS)?.parse().ok()
+    }
+
+    /// depend returns the rid-ids this stream depends on
+    pub fn depend(&self) -> Vec<&str> {
+        match self.restriction(RID_PARAM_DEPEND) {
+            Some(depend) => depend.split(',').collect(),
+            None => vec![],
+        }
+    }
+}
+
+/// SimulcastRid is a single rid-id in a simulcast stream description
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SimulcastRid {
+    pub id: String,
+    /// the "~" prefix, the stream is offered but initially paused
+    pub paused: bool,
+}
+
+impl fmt::Display for SimulcastRid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.paused {
+            write!(f, "~")?;
+        }
+        write!(f, "{}", self.id)
+    }
+}
+
+/// SimulcastStream is one simulcast stream, any one of its alternatives
+/// can be used to send it
+pub type SimulcastStream = Vec<SimulcastRid>;
+
+/// Simulcast is the structured form of an "a=simulcast" attribute
+///
+/// `simulcast:send <rid>[,<alt>];... recv <rid>;...`
+///
+/// <https://tools.ietf.org/html/rfc8853#section-5.1>
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Simulcast {
+    pub send: Vec<SimulcastStream>,
+    pub recv: Vec<SimulcastStream>,
+}
+
+/// Renders the value of the "a=simulcast" attribute, send streams first.
+impl fmt::Display for Simulcast {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut first = true;
+        for (direction, streams) in [
+            (RidDirection::Send, &self.send),
+            (RidDirection::Recv, &self.recv),
+        ] {
+            if streams.is_empty() {
+                continue;
+            }
+            if !first {
+                write!(f, " ")?;
+            }
+            first = false;
+
+            write!(f, "{} ", direction)?;
+            for (i, stream) in streams.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ";")?;
+                }
+                for (j, rid) in stream.iter().enumerate() {
+                    if j > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", rid)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Simulcast {
+    type Err = Error;
+
+    fn from_str(raw: &str) -> Result<Self> {
+        Simulcast::unmarshal(raw)
+    }
+}
+
+impl Simulcast {
+    /// unmarshal parses a simulcast description, with or without the
+    /// "a=simulcast:" or "simulcast:" prefix
+    pub fn unmarshal(raw: &str) -> Result<Self> {
+        let value = raw.trim();
+        let value = value.strip_prefix("a=").unwrap_or(value);
+        let value = value.strip_prefix("simulcast:").unwrap_or(value);
+
+        let fields: Vec<&str> = value.split_whitespace().collect();
+        if fields.is_empty() || fields.len() > 4 || !fields.len().is_multiple_of(2) {
+            return Err(Error::ParseSimulcast(raw.to_owned()));
+        }
+
+        let mut simulcast = Simulcast::default();
+        for pair in fields.chunks(2) {
+            let streams = match pair[0]
+                .parse::<RidDirection>()
+                .map_err(|_| Error::ParseSimulcast(raw.to_owned()))?
+            {
+                RidDirection::Send => &mut simulcast.send,
+                RidDirection::Recv => &mut simulcast.recv,
+            };
+            if !streams.is_empty() {
+                return Err(Error::ParseSimulcast(format!("duplicate {}", pair[0])));
+            }
+            *streams = parse_simulcast_streams(pair[1])?;
+        }
+
+        Ok(simulcast)
+    }
+
+    /// marshal renders the simulcast as "simulcast:..." attribute line value
+    pub fn marshal(&self) -> String {
+        format!("{}:{}", ATTR_KEY_SIMULCAST, self)
+    }
+
+    /// converts this object to an Attribute
+    pub fn convert(&self) -> Attribute {
+        Attribute {
+            key: ATTR_KEY_SIMULCAST.to_owned(),
+            value: Some(self.to_string()),
+        }
+    }
+
+    /// validate checks that every rid-id refers to an "a=rid" of the same
+    /// direction <https://tools.ietf.org/html/rfc8853#section-5.3>
+    pub fn validate(&self, rids: &[Rid]) -> Result<()> {
+        for (direction, streams) in [
+            (RidDirection::Send, &self.send),
+            (RidDirection::Recv, &self.recv),
+        ] {
+            for rid in streams.iter().flatten() {
+                if !rids
+                    .iter()
+                    .any(|r| r.id == rid.id && r.direction == direction)
+                {
+                    return Err(Error::SimulcastRidNotFound(rid.id.clone()));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn parse_simulcast_streams(list: &str) -> Result<Vec<SimulcastStream>> {
+    list.split(';')
+        .map(|stream| {
+            stream
+                .split(',')
+                .map(|id| {
+                    let (id, paused) = match id.strip_prefix('~') {
+                        Some(id) => (id, true),
+                        None => (id, false),
+                    };
+                    if !is_rid_id(id) {
+                        return Err(Error::ParseSimulcast(format!("invalid rid-id {}", id)));
+                    }
+                    Ok(SimulcastRid {
+                        id: id.to_owned(),
+                        paused,
+                    })
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// rid-id = 1*(alpha-numeric / "-" / "_")
+fn is_rid_id(id: &str) -> bool {
+    !id.is_empty()
+        && id
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+}
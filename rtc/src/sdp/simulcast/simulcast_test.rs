@@ -0,0 +1,157 @@
+use super::*;
+use crate::sdp::media::MediaDescription;
+use crate::sdp::sdp_test::SDPSTR;
+use crate::sdp::SDP;
+
+#[test]
+fn test_rid_round_trip() -> Result<()> {
+    let passingtests = [
+        "high send",
+        "1 recv",
+        "f_1-a send pt=96,97",
+        "high send max-width=1280;max-height=720;max-fps=30",
+        "hi send pt=98;max-br=64000;depend=lo,mid",
+        "lo recv x-custom;max-pps=100",
+    ];
+
+    for (i, u) in passingtests.iter().enumerate() {
+        let rid = Rid::unmarshal(u)?;
+        assert_eq!(*u, rid.to_string(), "{}: {}", i, u);
+        assert_eq!(format!("rid:{}", u), rid.marshal(), "{}: {}", i, u);
+
+        let prefixed = Rid::unmarshal(&format!("a=rid:{}\r\n", u))?;
+        assert_eq!(rid, prefixed, "{}: {}", i, u);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_rid_fields() -> Result<()> {
+    let rid: Rid =
+        "hi send pt=96,98;max-width=1280;max-height=720;max-fps=29.97;max-fs=3600;depend=lo"
+            .parse()?;
+    assert_eq!(rid.id, "hi");
+    assert_eq!(rid.direction, RidDirection::Send);
+    assert_eq!(rid.payload_types, vec![96, 98]);
+    assert_eq!(rid.max_width(), Some(1280));
+    assert_eq!(rid.max_height(), Some(720));
+    assert_eq!(rid.max_fps(), Some(29.97));
+    assert_eq!(rid.max_fs(), Some(3600));
+    assert_eq!(rid.max_br(), None);
+    assert_eq!(rid.depend(), vec!["lo"]);
+
+    let rid = Rid::new("lo", RidDirection::Recv)
+        .with_restriction("max-width", "320")
+        .with_restriction("max-width", "640");
+    assert_eq!(rid.to_string(), "lo recv max-width=640");
+
+    let failingtests = [
+        "",
+        "high",
+        "high both",
+        "hi.gh send",
+        "high send pt=x",
+        "high send pt=96 extra",
+        "high send max-width=1;pt=96",
+        "high send ;max-width=1",
+        "high send depend=a,",
+    ];
+    for u in failingtests {
+        assert!(Rid::unmarshal(u).is_err(), "{}", u);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_simulcast_round_trip() -> Result<()> {
+    let passingtests = [
+        "send high;middle;~low",
+        "recv 1;2,3",
+        "send 1,~4;2;3 recv c",
+        "send ~a",
+    ];
+
+    for (i, u) in passingtests.iter().enumerate() {
+        let simulcast = Simulcast::unmarshal(u)?;
+        assert_eq!(*u, simulcast.to_string(), "{}: {}", i, u);
+        assert_eq!(format!("simulcast:{}", u), simulcast.marshal());
+    }
+
+    // send streams are rendered first
+    let simulcast = Simulcast::unmarshal("a=simulcast:recv c send a;b")?;
+    assert_eq!(simulcast.to_string(), "send a;b recv c");
+
+    let failingtests = [
+        "",
+        "send",
+        "send a recv",
+        "send a send b",
+        "both a",
+        "send a;;b",
+        "send a,~",
+        "send a recv b extra c",
+    ];
+    for u in failingtests {
+        assert!(Simulcast::unmarshal(u).is_err(), "{}", u);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_simulcast_fields() -> Result<()> {
+    let simulcast = Simulcast::unmarshal("send 1,~4;2 recv c")?;
+    assert_eq!(
+        simulcast.send,
+        vec![
+            vec![
+                SimulcastRid {
+                    id: "1".to_owned(),
+                    paused: false
+                },
+                SimulcastRid {
+                    id: "4".to_owned(),
+                    paused: true
+                },
+            ],
+            vec![SimulcastRid {
+                id: "2".to_owned(),
+                paused: false
+            }],
+        ]
+    );
+    assert_eq!(simulcast.recv.len(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn test_media_description_simulcast() -> Result<()> {
+    let sdp = SDP::unmarshal(SDPSTR.as_bytes())?;
+    let video = &sdp.media_descriptions[1];
+
+    let rids = video.rids()?;
+    let ids: Vec<&str> = rids.iter().map(|r| r.id.as_str()).collect();
+    assert_eq!(ids, vec!["high", "middle", "low"]);
+    assert!(rids.iter().all(|r| r.direction == RidDirection::Send));
+
+    let simulcast = video.simulcast()?.unwrap();
+    assert_eq!(simulcast.send.len(), 3);
+    assert!(simulcast.send[2][0].paused);
+    assert!(simulcast.recv.is_empty());
+
+    assert_eq!(sdp.media_descriptions[0].simulcast()?, None);
+
+    let media = MediaDescription::default()
+        .with_rid(&Rid::new("a", RidDirection::Send))
+        .with_rid(&Rid::new("b", RidDirection::Recv))
+        .with_simulcast(&Simulcast::unmarshal("send a;b")?);
+    assert_eq!(
+        media.simulcast(),
+        Err(Error::SimulcastRidNotFound("b".to_owned()))
+    );
+
+    Ok(())
+}
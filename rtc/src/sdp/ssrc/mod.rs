@@ -0,0 +1,392 @@
+#[cfg(test)]
+mod ssrc_test;
+
+use super::common::Attribute;
+use super::error::{Error, Result};
+use super::media::MediaDescription;
+
+use std::fmt;
+use std::str::FromStr;
+
+pub const ATTR_KEY_SSRC: &str = "ssrc";
+pub const ATTR_KEY_SSRC_GROUP: &str = "ssrc-group";
+pub const ATTR_KEY_MSID: &str = "msid";
+
+pub const SSRC_ATTR_CNAME: &str = "cname";
+pub const SSRC_ATTR_MSID: &str = "msid";
+pub const SSRC_ATTR_MSLABEL: &str = "mslabel";
+pub const SSRC_ATTR_LABEL: &str = "label";
+
+pub const SSRC_GROUP_SEMANTICS_FID: &str = "FID";
+pub const SSRC_GROUP_SEMANTICS_SIM: &str = "SIM";
+pub const SSRC_GROUP_SEMANTICS_FEC_FR: &str = "FEC-FR";
+
+/// Msid is the structured form of an "a=msid" attribute, or of the msid
+/// source attribute of an "a=ssrc" line
+///
+/// `msid:<stream id> [<track id>]`
+///
+/// <https://tools.ietf.org/html/rfc8830#section-2>
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Msid {
+    /// id of the MediaStream, "-" for a track without stream
+    pub stream_id: String,
+    /// appdata, the id of the MediaStreamTrack
+    pub track_id: Option<String>,
+}
+
+/// Renders the value of the "a=msid" attribute.
+impl fmt::Display for Msid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.stream_id)?;
+        if let Some(track_id) = &self.track_id {
+            write!(f, " {}", track_id)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Msid {
+    type Err = Error;
+
+    fn from_str(raw: &str) -> Result<Self> {
+        Msid::unmarshal(raw)
+    }
+}
+
+impl Msid {
+    pub fn new(stream_id: &str, track_id: &str) -> Self {
+        Msid {
+            stream_id: stream_id.to_owned(),
+            track_id: Some(track_id.to_owned()),
+        }
+    }
+
+    /// unmarshal parses an msid, with or without the "a=msid:" or "msid:"
+    /// prefix
+    pub fn unmarshal(raw: &str) -> Result<Self> {
+        let value = raw.trim();
+        let value = value.strip_prefix("a=").unwrap_or(value);
+        let value = value.strip_prefix("msid:").unwrap_or(value);
+
+        let fields: Vec<&str> = value.split_whitespace().collect();
+        if fields.is_empty() || fields.len() > 2 {
+            return Err(Error::ParseMsid(raw.to_owned()));
+        }
+
+        Ok(Msid {
+            stream_id: fields[0].to_owned(),
+            track_id: fields.get(1).map(|id| id.to_string()),
+        })
+    }
+
+    /// marshal renders the msid as "msid:..." attribute line value
+    pub fn marshal(&self) -> String {
+        format!("{}:{}", ATTR_KEY_MSID, self)
+    }
+
+    /// converts this object to an Attribute
+    pub fn convert(&self) -> Attribute {
+        Attribute {
+            key: ATTR_KEY_MSID.to_owned(),
+            value: Some(self.to_string()),
+        }
+    }
+}
+
+/// SsrcInfo collects the source attributes of all "a=ssrc" lines sharing
+/// the same SSRC
+///
+/// `ssrc:<ssrc-id> <attribute>[:<value>]`
+///
+/// <https://tools.ietf.org/html/rfc5576#section-4.1>
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SsrcInfo {
+    pub ssrc: u32,
+    /// <https://tools.ietf.org/html/rfc5576#section-6.1>
+    pub cname: Option<String>,
+    pub msid: Option<Msid>,
+    /// legacy stream label of Plan B endpoints
+    pub mslabel: Option<String>,
+    /// legacy track label of Plan B endpoints
+    pub label: Option<String>,
+    /// all other source attributes in their original order
+    pub attributes: Vec<(String, Option<String>)>,
+}
+
+impl SsrcInfo {
+    pub fn new(ssrc: u32, cname: &str) -> Self {
+        SsrcInfo {
+            ssrc,
+            cname: Some(cname.to_owned()),
+            ..Default::default()
+        }
+    }
+
+    /// with_msid sets the msid source attribute
+    pub fn with_msid(mut self, msid: Msid) -> Self {
+        self.msid = Some(msid);
+        self
+    }
+
+    /// with_labels sets the legacy mslabel and label source attributes
+    pub fn with_labels(mut self, mslabel: &str, label: &str) -> Self {
+        self.mslabel = Some(mslabel.to_owned());
+        self.label = Some(label.to_owned());
+        self
+    }
+
+    /// set adds a source attribute parsed from an "a=ssrc" line
+    fn set(&mut self, name: &str, value: Option<&str>) -> Result<()> {
+        match (name, value) {
+            (SSRC_ATTR_CNAME, Some(value)) => self.cname = Some(value.to_owned()),
+            (SSRC_ATTR_MSID, Some(value)) => self.msid = Some(Msid::unmarshal(value)?),
+            (SSRC_ATTR_MSLABEL, Some(value)) => self.mslabel = Some(value.to_owned()),
+            (SSRC_ATTR_LABEL, Some(value)) => self.label = Some(value.to_owned()),
+            _ => self
+                .attributes
+                .push((name.to_owned(), value.map(|v| v.to_owned()))),
+        }
+        Ok(())
+    }
+
+    /// converts this object to "a=ssrc" Attributes, one per source attribute
+    pub fn convert(&self) -> Vec<Attribute> {
+        let known = [
+            (SSRC_ATTR_CNAME, self.cname.clone()),
+            (SSRC_ATTR_MSID, self.msid.as_ref().map(|m| m.to_string())),
+            (SSRC_ATTR_MSLABEL, self.mslabel.clone()),
+            (SSRC_ATTR_LABEL, self.label.clone()),
+        ];
+        let known = known
+            .into_iter()
+            .filter_map(|(name, value)| value.map(|value| (name.to_owned(), Some(value))));
+
+        known
+            .chain(self.attributes.iter().cloned())
+            .map(|(name, value)| {
+                let value = match value {
+                    Some(value) => format!("{} {}:{}", self.ssrc, name, value),
+                    None => format!("{} {}", self.ssrc, name),
+                };
+                Attribute::new(ATTR_KEY_SSRC.to_owned(), Some(value))
+            })
+            .collect()
+    }
+}
+
+/// SsrcGroupSemantics is the semantics token of an "a=ssrc-group" attribute
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SsrcGroupSemantics {
+    /// flow identification, used for RTX pairs
+    /// <https://tools.ietf.org/html/rfc5576#section-4.2>
+    Fid,
+    /// simulcast layers of legacy endpoints
+    Sim,
+    /// <https://tools.ietf.org/html/rfc5956#section-4.3>
+    FecFr,
+    Other(String),
+}
+
+impl fmt::Display for SsrcGroupSemantics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            SsrcGroupSemantics::Fid => SSRC_GROUP_SEMANTICS_FID,
+            SsrcGroupSemantics::Sim => SSRC_GROUP_SEMANTICS_SIM,
+            SsrcGroupSemantics::FecFr => SSRC_GROUP_SEMANTICS_FEC_FR,
+            SsrcGroupSemantics::Other(s) => s,
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl FromStr for SsrcGroupSemantics {
+    type Err = Error;
+
+    fn from_str(raw: &str) -> Result<Self> {
+        Ok(match raw {
+            SSRC_GROUP_SEMANTICS_FID => SsrcGroupSemantics::Fid,
+            SSRC_GROUP_SEMANTICS_SIM => SsrcGroupSemantics::Sim,
+            SSRC_GROUP_SEMANTICS_FEC_FR => SsrcGroupSemantics::FecFr,
+            _ if !raw.is_empty() => SsrcGroupSemantics::Other(raw.to_owned()),
+            _ => return Err(Error::ParseSsrc(raw.to_owned())),
+        })
+    }
+}
+
+/// SsrcGroup is the structured form of an "a=ssrc-group" attribute
+///
+/// `ssrc-group:<semantics> <ssrc-id> ...`
+///
+/// <https://tools.ietf.org/html/rfc5576#section-4.2>
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SsrcGroup {
+    pub semantics: SsrcGroupSemantics,
+    pub ssrcs: Vec<u32>,
+}
+
+/// Renders the value of the "a=ssrc-group" attribute.
+impl fmt::Display for SsrcGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.semantics)?;
+        for ssrc in &self.ssrcs {
+            write!(f, " {}", ssrc)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for SsrcGroup {
+    type Err = Error;
+
+    fn from_str(raw: &str) -> Result<Self> {
+        SsrcGroup::unmarshal(raw)
+    }
+}
+
+impl SsrcGroup {
+    pub fn new(semantics: SsrcGroupSemantics, ssrcs: &[u32]) -> Self {
+        SsrcGroup {
+            semantics,
+            ssrcs: ssrcs.to_vec(),
+        }
+    }
+
+    /// unmarshal parses an ssrc group, with or without the "a=ssrc-group:"
+    /// or "ssrc-group:" prefix
+    pub fn unmarshal(raw: &str) -> Result<Self> {
+        let value = raw.trim();
+        let value = value.strip_prefix("a=").unwrap_or(value);
+        let value = value.strip_prefix("ssrc-group:").unwrap_or(value);
+
+        let mut fields = value.split_whitespace();
+        let semantics = fields
+            .next()
+            .ok_or_else(|| Error::ParseSsrc(raw.to_owned()))?
+            .parse()?;
+        let ssrcs = fields
+            .map(|ssrc| ssrc.parse::<u32>())
+            .collect::<std::result::Result<Vec<u32>, _>>()
+            .map_err(|_| Error::ParseSsrc(raw.to_owned()))?;
+        if ssrcs.is_empty() {
+            return Err(Error::ParseSsrc(raw.to_owned()));
+        }
+
+        Ok(SsrcGroup { semantics, ssrcs })
+    }
+
+    /// marshal renders the group as "ssrc-group:..." attribute line value
+    pub fn marshal(&self) -> String {
+        format!("{}:{}", ATTR_KEY_SSRC_GROUP, self)
+    }
+
+    /// converts this object to an Attribute
+    pub fn convert(&self) -> Attribute {
+        Attribute {
+            key: ATTR_KEY_SSRC_GROUP.to_owned(),
+            value: Some(self.to_string()),
+        }
+    }
+}
+
+/// parse_ssrc_line splits the value of an "a=ssrc" attribute into SSRC,
+/// source attribute name and optional value
+fn parse_ssrc_line(raw: &str) -> Result<(u32, &str, Option<&str>)> {
+    let (ssrc, attribute) = raw
+        .split_once(' ')
+        .ok_or_else(|| Error::ParseSsrc(raw.to_owned()))?;
+    let ssrc = ssrc
+        .parse::<u32>()
+        .map_err(|_| Error::ParseSsrc(raw.to_owned()))?;
+    let attribute = attribute.trim();
+    if attribute.is_empty() {
+        return Err(Error::ParseSsrc(raw.to_owned()));
+    }
+    Ok(match attribute.split_once(':') {
+        Some((name, value)) => (ssrc, name, Some(value)),
+        None => (ssrc, attribute, None),
+    })
+}
+
+impl MediaDescription {
+    /// ssrc_infos groups the "a=ssrc" attributes by SSRC, in the order the
+    /// SSRCs first appear
+    pub fn ssrc_infos(&self) -> Result<Vec<SsrcInfo>> {
+        let mut infos: Vec<SsrcInfo> = vec![];
+        for attribute in self.attributes.iter().filter(|a| a.key == ATTR_KEY_SSRC) {
+            let (ssrc, name, value) =
+                parse_ssrc_line(attribute.value.as_deref().unwrap_or_default())?;
+            let index = match infos.iter().position(|info| info.ssrc == ssrc) {
+                Some(index) => index,
+                None => {
+                    infos.push(SsrcInfo {
+                        ssrc,
+                        ..Default::default()
+                    });
+                    infos.len() - 1
+                }
+            };
+            infos[index].set(name, value)?;
+        }
+        Ok(infos)
+    }
+
+    /// ssrc_info returns the source attributes of a single SSRC
+    pub fn ssrc_info(&self, ssrc: u32) -> Result<Option<SsrcInfo>> {
+        Ok(self
+            .ssrc_infos()?
+            .into_iter()
+            .find(|info| info.ssrc == ssrc))
+    }
+
+    /// ssrc_groups parses all "a=ssrc-group" attributes
+    pub fn ssrc_groups(&self) -> Result<Vec<SsrcGroup>> {
+        self.attributes
+            .iter()
+            .filter(|a| a.key == ATTR_KEY_SSRC_GROUP)
+            .map(|a| SsrcGroup::unmarshal(a.value.as_deref().unwrap_or_default()))
+            .collect()
+    }
+
+    /// rtx_ssrc returns the retransmission SSRC paired with a primary SSRC
+    /// by an "a=ssrc-group:FID <primary> <rtx>" attribute
+    pub fn rtx_ssrc(&self, primary: u32) -> Result<Option<u32>> {
+        Ok(self
+            .ssrc_groups()?
+            .into_iter()
+            .filter(|group| group.semantics == SsrcGroupSemantics::Fid)
+            .find(|group| group.ssrcs.len() == 2 && group.ssrcs[0] == primary)
+            .map(|group| group.ssrcs[1]))
+    }
+
+    /// msids parses the media-level "a=msid" attributes
+    pub fn msids(&self) -> Result<Vec<Msid>> {
+        self.attributes
+            .iter()
+            .filter(|a| a.key == ATTR_KEY_MSID)
+            .map(|a| Msid::unmarshal(a.value.as_deref().unwrap_or_default()))
+            .collect()
+    }
+
+    /// with_msid adds an "a=msid" attribute
+    pub fn with_msid(mut self, msid: &Msid) -> Self {
+        self.attributes.push(msid.convert());
+        self
+    }
+
+    /// with_ssrc_info adds one "a=ssrc" attribute per source attribute
+    pub fn with_ssrc_info(mut self, info: &SsrcInfo) -> Self {
+        self.attributes.extend(info.convert());
+        self
+    }
+
+    /// with_ssrc_group adds an "a=ssrc-group" attribute
+    pub fn with_ssrc_group(mut self, group: &SsrcGroup) -> Self {
+        self.attributes.push(group.convert());
+        self
+    }
+}
@@ -0,0 +1,146 @@
+use super::*;
+use crate::sdp::sdp_test::SDPSTR;
+use crate::sdp::SDP;
+
+#[test]
+fn test_msid_round_trip() -> Result<()> {
+    let passingtests = [
+        (
+            "- f279f012-989a-466f-8423-ee1e0cefdd3c",
+            Msid::new("-", "f279f012-989a-466f-8423-ee1e0cefdd3c"),
+        ),
+        (
+            "stream",
+            Msid {
+                stream_id: "stream".to_owned(),
+                track_id: None,
+            },
+        ),
+    ];
+    for (i, (u, expected)) in passingtests.iter().enumerate() {
+        let msid = Msid::unmarshal(u)?;
+        assert_eq!(*expected, msid, "{}: {}", i, u);
+        assert_eq!(*u, msid.to_string(), "{}: {}", i, u);
+        assert_eq!(format!("msid:{}", u), msid.marshal());
+        assert_eq!(msid, format!("a=msid:{}", u).parse::<Msid>()?);
+    }
+
+    for u in ["", "a b c"] {
+        assert!(Msid::unmarshal(u).is_err(), "{}", u);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_ssrc_group_round_trip() -> Result<()> {
+    let passingtests = [
+        (
+            "FID 3794136405 2951726931",
+            SsrcGroup::new(SsrcGroupSemantics::Fid, &[3794136405, 2951726931]),
+        ),
+        (
+            "SIM 1 2 3",
+            SsrcGroup::new(SsrcGroupSemantics::Sim, &[1, 2, 3]),
+        ),
+        (
+            "FEC-FR 1 2",
+            SsrcGroup::new(SsrcGroupSemantics::FecFr, &[1, 2]),
+        ),
+        (
+            "DUP 1 2",
+            SsrcGroup::new(SsrcGroupSemantics::Other("DUP".to_owned()), &[1, 2]),
+        ),
+    ];
+    for (i, (u, expected)) in passingtests.iter().enumerate() {
+        let group = SsrcGroup::unmarshal(u)?;
+        assert_eq!(*expected, group, "{}: {}", i, u);
+        assert_eq!(*u, group.to_string(), "{}: {}", i, u);
+        assert_eq!(format!("ssrc-group:{}", u), group.marshal());
+        assert_eq!(group, format!("a=ssrc-group:{}", u).parse::<SsrcGroup>()?);
+    }
+
+    for u in ["", "FID", "FID abc", "FID 4294967296"] {
+        assert!(SsrcGroup::unmarshal(u).is_err(), "{}", u);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_ssrc_infos() -> Result<()> {
+    let sdp = SDP::unmarshal(SDPSTR.as_bytes())?;
+    let audio = &sdp.media_descriptions[0];
+
+    let expected = SsrcInfo::new(3794136405, "BlPa1deUgsMx8yL/")
+        .with_msid(Msid::new("-", "f279f012-989a-466f-8423-ee1e0cefdd3c"))
+        .with_labels("-", "f279f012-989a-466f-8423-ee1e0cefdd3c");
+    assert_eq!(audio.ssrc_infos()?, vec![expected.clone()]);
+    assert_eq!(audio.ssrc_info(3794136405)?, Some(expected));
+    assert_eq!(audio.ssrc_info(1)?, None);
+    assert_eq!(
+        audio.msids()?,
+        vec![Msid::new("-", "f279f012-989a-466f-8423-ee1e0cefdd3c")]
+    );
+    assert!(sdp.media_descriptions[1].ssrc_infos()?.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_ssrc_builders() -> Result<()> {
+    let primary = SsrcInfo::new(1000, "cname").with_msid(Msid::new("stream", "track"));
+    let mut rtx = SsrcInfo::new(2000, "cname").with_msid(Msid::new("stream", "track"));
+    rtx.attributes.push(("x-flag".to_owned(), None));
+
+    let media = MediaDescription::default()
+        .with_msid(&Msid::new("stream", "track"))
+        .with_ssrc_group(&SsrcGroup::new(SsrcGroupSemantics::Fid, &[1000, 2000]))
+        .with_ssrc_info(&primary)
+        .with_ssrc_info(&rtx);
+    let values: Vec<String> = media
+        .attributes
+        .iter()
+        .map(|a| format!("{}:{}", a.key, a.value.as_deref().unwrap_or_default()))
+        .collect();
+    assert_eq!(
+        values,
+        vec![
+            "msid:stream track",
+            "ssrc-group:FID 1000 2000",
+            "ssrc:1000 cname:cname",
+            "ssrc:1000 msid:stream track",
+            "ssrc:2000 cname:cname",
+            "ssrc:2000 msid:stream track",
+            "ssrc:2000 x-flag",
+        ]
+    );
+
+    assert_eq!(media.ssrc_infos()?, vec![primary, rtx]);
+    assert_eq!(media.rtx_ssrc(1000)?, Some(2000));
+    assert_eq!(media.rtx_ssrc(2000)?, None);
+
+    // the legacy helper produces the same lines as the typed builder
+    let legacy = MediaDescription::default().with_media_source(
+        1000,
+        "cname".to_owned(),
+        "stream".to_owned(),
+        "track".to_owned(),
+    );
+    let typed = MediaDescription::default().with_ssrc_info(
+        &SsrcInfo::new(1000, "cname")
+            .with_msid(Msid::new("stream", "track"))
+            .with_labels("stream", "track"),
+    );
+    assert_eq!(legacy, typed);
+    assert_eq!(
+        legacy.attributes[3].value.as_deref(),
+        Some("1000 label:track")
+    );
+
+    let invalid = MediaDescription::default()
+        .with_value_attribute(ATTR_KEY_SSRC.to_owned(), "abc cname:x".to_owned());
+    assert!(invalid.ssrc_infos().is_err());
+
+    Ok(())
+}
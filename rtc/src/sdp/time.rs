@@ -3,7 +3,8 @@ use std::fmt;
 /// TimeDescription describes "t=", "r=" fields of the session description
 /// which are used to specify the start and stop times for a session as well as
 /// repeat intervals and durations for the scheduled session.
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TimeDescription {
     /// `t=<start-time> <stop-time>`
     ///
@@ -18,7 +19,8 @@ pub struct TimeDescription {
 
 /// Timing defines the "t=" field's structured representation for the start and
 /// stop times.
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Timing {
     pub start_time: u64,
     pub stop_time: u64,
@@ -32,7 +34,8 @@ impl fmt::Display for Timing {
 
 /// RepeatTime describes the "r=" fields of the session description which
 /// represents the intervals and durations for repeated scheduled sessions.
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RepeatTime {
     pub interval: i64,
     pub duration: i64,
@@ -41,10 +44,10 @@ pub struct RepeatTime {
 
 impl fmt::Display for RepeatTime {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let mut fields = vec![format!("{}", self.interval), format!("{}", self.duration)];
+        write!(f, "{} {}", self.interval, self.duration)?;
         for value in &self.offsets {
-            fields.push(format!("{}", value));
+            write!(f, " {}", value)?;
         }
-        write!(f, "{}", fields.join(" "))
+        Ok(())
     }
 }
\ No newline at end of file
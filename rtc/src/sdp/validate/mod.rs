@@ -0,0 +1,245 @@
+#[cfg(test)]
+mod validate_test;
+
+use super::codec::*;
+use super::common::Attribute;
+use super::error::Error;
+use super::extmap::{
+    extmaps_of, ATTR_KEY_EXTMAP, ATTR_KEY_EXTMAP_ALLOW_MIXED, ONE_BYTE_EXT_MAP_IDS,
+    TWO_BYTE_EXT_MAP_IDS,
+};
+use super::group::ATTR_KEY_GROUP;
+use super::media::{MediaDescription, ATTR_KEY_ICE_PWD, ATTR_KEY_ICE_UFRAG};
+use super::SDP;
+
+use std::fmt;
+
+/// ICE credential lengths <https://tools.ietf.org/html/rfc8839#section-5.4>
+const ICE_UFRAG_LEN: (usize, usize) = (4, 256);
+const ICE_PWD_LEN: (usize, usize) = (22, 256);
+
+/// Severity tells whether a diagnostic makes the description unusable or
+/// only deviates from a recommendation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// Diagnostic is a semantic problem found by `SDP::validate`
+#[derive(Debug, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// index of the media description, None for the session
+    pub media_index: Option<usize>,
+    /// key of the offending attribute, if any
+    pub attribute: Option<String>,
+    pub error: Error,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: ", self.severity)?;
+        match self.media_index {
+            Some(index) => write!(f, "media {}", index)?,
+            None => write!(f, "session")?,
+        }
+        if let Some(attribute) = &self.attribute {
+            write!(f, " a={}", attribute)?;
+        }
+        write!(f, ": {}", self.error)
+    }
+}
+
+impl Diagnostic {
+    /// message describes the problem
+    pub fn message(&self) -> String {
+        self.error.to_string()
+    }
+}
+
+/// Diagnostics collects the findings for one session or media description
+struct Diagnostics<'a> {
+    media_index: Option<usize>,
+    list: &'a mut Vec<Diagnostic>,
+}
+
+impl Diagnostics<'_> {
+    fn push(&mut self, severity: Severity, attribute: &str, error: Error) {
+        self.list.push(Diagnostic {
+            severity,
+            media_index: self.media_index,
+            attribute: if attribute.is_empty() {
+                None
+            } else {
+                Some(attribute.to_owned())
+            },
+            error,
+        });
+    }
+
+    fn error(&mut self, attribute: &str, error: Error) {
+        self.push(Severity::Error, attribute, error);
+    }
+
+    fn warning(&mut self, attribute: &str, error: Error) {
+        self.push(Severity::Warning, attribute, error);
+    }
+}
+
+impl SDP {
+    /// validate checks the semantics of the description beyond its grammar
+    /// and returns every problem found, an empty list if there is none.
+    ///
+    /// - every non-static payload type of an RTP m-line has an "a=rtpmap"
+    /// - "a=rtpmap", "a=fmtp" and "a=rtcp-fb" refer to listed payload types
+    /// - the "apt" of every rtx payload type refers to a listed codec
+    /// - extmap ids are unique per section and fit the header format
+    /// - grouped mids exist exactly once
+    /// - ICE credentials have legal lengths and characters
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        let mut list = vec![];
+        let allow_mixed = self
+            .session
+            .attribute(ATTR_KEY_EXTMAP_ALLOW_MIXED)
+            .is_some();
+
+        let mut session = Diagnostics {
+            media_index: None,
+            list: &mut list,
+        };
+        validate_ice_credentials(&self.session.attributes, &mut session);
+        validate_extmaps(&self.session.attributes, allow_mixed, &mut session);
+        if let Err(err) = self.validate_groups() {
+            session.error(ATTR_KEY_GROUP, err);
+        }
+
+        for (index, media) in self.media_descriptions.iter().enumerate() {
+            let mut diagnostics = Diagnostics {
+                media_index: Some(index),
+                list: &mut list,
+            };
+            let allow_mixed = allow_mixed || media.attribute(ATTR_KEY_EXTMAP_ALLOW_MIXED).is_some();
+            validate_payload_types(media, &mut diagnostics);
+            validate_extmaps(&media.attributes, allow_mixed, &mut diagnostics);
+            validate_ice_credentials(&media.attributes, &mut diagnostics);
+        }
+
+        list
+    }
+}
+
+fn validate_payload_types(media: &MediaDescription, diagnostics: &mut Diagnostics<'_>) {
+    let is_rtp = media.media_name.protos.iter().any(|p| p.contains("RTP"));
+    if !is_rtp {
+        return;
+    }
+
+    let mut formats = vec![];
+    for format in &media.media_name.formats {
+        match format.parse::<u8>() {
+            Ok(pt) if pt < 128 => formats.push(pt),
+            _ => diagnostics.error("", Error::SdpInvalidValue(format.clone())),
+        }
+    }
+
+    let mut mapped = vec![];
+    for attribute in &media.attributes {
+        let value = attribute.value.as_deref().unwrap_or_default();
+        let payload_type = match attribute.key.as_str() {
+            ATTR_KEY_RTPMAP => parse_rtpmap(value).map(|codec| Some(codec.payload_type)),
+            ATTR_KEY_FMTP => parse_fmtp(value).map(|(pt, _)| Some(pt)),
+            ATTR_KEY_RTCP_FB => parse_rtcp_fb(value).map(|(pt, _)| pt),
+            _ => continue,
+        };
+        match payload_type {
+            Ok(Some(pt)) => {
+                if attribute.key == ATTR_KEY_RTPMAP {
+                    mapped.push(pt);
+                }
+                if !formats.contains(&pt) {
+                    diagnostics.warning(&attribute.key, Error::UnlistedPayloadType(pt));
+                }
+            }
+            Ok(None) => {}
+            Err(err) => diagnostics.error(&attribute.key, err),
+        }
+    }
+
+    for pt in &formats {
+        if !mapped.contains(pt) && Codec::static_payload_type(*pt).is_none() {
+            diagnostics.error(ATTR_KEY_RTPMAP, Error::FormatWithoutRtpmap(*pt));
+        }
+    }
+
+    let codecs = media.codecs();
+    for rtx in codecs.iter().filter(|c| c.is_rtx()) {
+        let apt = rtx.fmtp_param("apt").and_then(|apt| apt.parse::<u8>().ok());
+        let associated = codecs
+            .iter()
+            .any(|c| Some(c.payload_type) == apt && !c.is_rtx());
+        if !associated {
+            diagnostics.error(
+                ATTR_KEY_FMTP,
+                Error::RtxWithoutAssociatedPayloadType(rtx.payload_type),
+            );
+        }
+    }
+}
+
+fn validate_extmaps(
+    attributes: &[Attribute],
+    allow_mixed: bool,
+    diagnostics: &mut Diagnostics<'_>,
+) {
+    let extmaps = match extmaps_of(attributes) {
+        Ok(extmaps) => extmaps,
+        Err(err) => {
+            diagnostics.error(ATTR_KEY_EXTMAP, err);
+            return;
+        }
+    };
+
+    let mut ids = vec![];
+    for extmap in &extmaps {
+        let id = extmap.value;
+        if ids.contains(&id) {
+            diagnostics.error(ATTR_KEY_EXTMAP, Error::DuplicateExtMapId(id));
+            continue;
+        }
+        ids.push(id);
+
+        if !ONE_BYTE_EXT_MAP_IDS.contains(&id) && !TWO_BYTE_EXT_MAP_IDS.contains(&id) {
+            diagnostics.error(ATTR_KEY_EXTMAP, Error::ExtMapIdOutOfRange(id));
+        } else if TWO_BYTE_EXT_MAP_IDS.contains(&id) && !allow_mixed {
+            // usable only with the two-byte header, which the peer may not
+            // support unless mixing was negotiated
+            diagnostics.warning(ATTR_KEY_EXTMAP, Error::ExtMapIdOutOfRange(id));
+        }
+    }
+}
+
+fn validate_ice_credentials(attributes: &[Attribute], diagnostics: &mut Diagnostics<'_>) {
+    for (key, (min, max)) in [
+        (ATTR_KEY_ICE_UFRAG, ICE_UFRAG_LEN),
+        (ATTR_KEY_ICE_PWD, ICE_PWD_LEN),
+    ] {
+        for attribute in attributes.iter().filter(|a| a.key == key) {
+            let value = attribute.value.as_deref().unwrap_or_default();
+            let is_ice_char = |b: u8| b.is_ascii_alphanumeric() || b == b'+' || b == b'/';
+            if value.len() < min || value.len() > max || !value.bytes().all(is_ice_char) {
+                diagnostics.error(key, Error::InvalidIceCredentials(value.to_owned()));
+            }
+        }
+    }
+}
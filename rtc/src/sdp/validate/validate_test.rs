@@ -0,0 +1,156 @@
+use super::*;
+use crate::sdp::common::Attribute;
+use crate::sdp::error::Result;
+use crate::sdp::sdp_test::SDPSTR;
+
+fn attribute(key: &str, value: &str) -> Attribute {
+    Attribute::new(key.to_owned(), Some(value.to_owned()))
+}
+
+#[test]
+fn test_validate_valid() -> Result<()> {
+    let sdp = SDP::unmarshal(SDPSTR.as_bytes())?;
+    assert_eq!(sdp.validate(), vec![]);
+
+    Ok(())
+}
+
+#[test]
+fn test_validate_payload_types() -> Result<()> {
+    let mut sdp = SDP::unmarshal(SDPSTR.as_bytes())?;
+    let video = &mut sdp.media_descriptions[1];
+    video
+        .attributes
+        .retain(|a| a.value.as_deref() != Some("96 VP8/90000"));
+    video.attributes.push(attribute(ATTR_KEY_FMTP, "50 apt=96"));
+    video.attributes.push(attribute(ATTR_KEY_RTCP_FB, "x nack"));
+
+    let diagnostics = sdp.validate();
+    assert_eq!(
+        diagnostics,
+        vec![
+            Diagnostic {
+                severity: Severity::Warning,
+                media_index: Some(1),
+                attribute: Some(ATTR_KEY_FMTP.to_owned()),
+                error: Error::UnlistedPayloadType(50),
+            },
+            Diagnostic {
+                severity: Severity::Error,
+                media_index: Some(1),
+                attribute: Some(ATTR_KEY_RTCP_FB.to_owned()),
+                error: parse_rtcp_fb("x nack").unwrap_err(),
+            },
+            Diagnostic {
+                severity: Severity::Error,
+                media_index: Some(1),
+                attribute: Some(ATTR_KEY_RTPMAP.to_owned()),
+                error: Error::FormatWithoutRtpmap(96),
+            },
+            Diagnostic {
+                severity: Severity::Error,
+                media_index: Some(1),
+                attribute: Some(ATTR_KEY_FMTP.to_owned()),
+                error: Error::RtxWithoutAssociatedPayloadType(97),
+            },
+        ]
+    );
+    assert_eq!(
+        diagnostics[2].to_string(),
+        "error: media 1 a=rtpmap: non-static payload type 96 has no rtpmap"
+    );
+
+    // static payload types need no rtpmap
+    let audio = &mut sdp.media_descriptions[0];
+    audio
+        .attributes
+        .retain(|a| a.key != ATTR_KEY_RTPMAP || a.value.as_deref() != Some("0 PCMU/8000"));
+    assert!(sdp.validate().iter().all(|d| d.media_index != Some(0)));
+
+    Ok(())
+}
+
+#[test]
+fn test_validate_extmaps() -> Result<()> {
+    let mut sdp = SDP::unmarshal(SDPSTR.as_bytes())?;
+    let audio = &mut sdp.media_descriptions[0];
+    audio
+        .attributes
+        .push(attribute(ATTR_KEY_EXTMAP, "1 urn:example:duplicate"));
+    audio
+        .attributes
+        .push(attribute(ATTR_KEY_EXTMAP, "15 urn:example:two-byte"));
+    audio
+        .attributes
+        .push(attribute(ATTR_KEY_EXTMAP, "255 urn:example:two-byte"));
+
+    let errors: Vec<Error> = sdp.validate().into_iter().map(|d| d.error).collect();
+    assert_eq!(errors, vec![Error::DuplicateExtMapId(1)]);
+
+    // two-byte ids are only a warning without extmap-allow-mixed
+    sdp.session
+        .attributes
+        .retain(|a| a.key != ATTR_KEY_EXTMAP_ALLOW_MIXED);
+    let diagnostics = sdp.validate();
+    assert_eq!(diagnostics.len(), 3);
+    assert_eq!(diagnostics[1].severity, Severity::Warning);
+    assert_eq!(diagnostics[1].error, Error::ExtMapIdOutOfRange(15));
+    assert_eq!(diagnostics[2].error, Error::ExtMapIdOutOfRange(255));
+
+    // ids beyond the two-byte range do not parse at all
+    let audio = &mut sdp.media_descriptions[0];
+    audio
+        .attributes
+        .push(attribute(ATTR_KEY_EXTMAP, "256 urn:example:invalid"));
+    let diagnostics = sdp.validate();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].attribute.as_deref(), Some(ATTR_KEY_EXTMAP));
+
+    Ok(())
+}
+
+#[test]
+fn test_validate_groups_and_ice() -> Result<()> {
+    let mut sdp = SDP::unmarshal(SDPSTR.as_bytes())?;
+    sdp.media_descriptions[2].set_mid("5");
+    sdp.session
+        .attributes
+        .push(attribute(ATTR_KEY_ICE_PWD, "short"));
+    sdp.media_descriptions[0]
+        .attributes
+        .retain(|a| a.key != ATTR_KEY_ICE_UFRAG);
+    sdp.media_descriptions[0]
+        .attributes
+        .push(attribute(ATTR_KEY_ICE_UFRAG, "AO-8"));
+
+    let diagnostics = sdp.validate();
+    assert_eq!(
+        diagnostics,
+        vec![
+            Diagnostic {
+                severity: Severity::Error,
+                media_index: None,
+                attribute: Some(ATTR_KEY_ICE_PWD.to_owned()),
+                error: Error::InvalidIceCredentials("short".to_owned()),
+            },
+            Diagnostic {
+                severity: Severity::Error,
+                media_index: None,
+                attribute: Some(ATTR_KEY_GROUP.to_owned()),
+                error: Error::MidNotFound("4".to_owned()),
+            },
+            Diagnostic {
+                severity: Severity::Error,
+                media_index: Some(0),
+                attribute: Some(ATTR_KEY_ICE_UFRAG.to_owned()),
+                error: Error::InvalidIceCredentials("AO-8".to_owned()),
+            },
+        ]
+    );
+    assert_eq!(
+        diagnostics[1].to_string(),
+        "error: session a=group: mid 4 not found"
+    );
+
+    Ok(())
+}